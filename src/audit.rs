@@ -0,0 +1,134 @@
+//! Auditing of the dependencies of the workspace against the RustSec advisory database.
+//!
+//! The audit uses the `rustsec` crate (which also underlies `cargo audit`), fetching the advisory database to
+//! `~/.cargo/advisory-db` and checking the lock file of the workspace against it. Yanked crates are identified via the
+//! crates.io index, as by `cargo audit`.
+
+use crate::{
+    findings::{Category, Finding, Location, Severity},
+    manifests::{self, line_containing},
+    Analysis, Context, Error, Format,
+};
+use rustsec::{
+    advisory, registry::CachedIndex, report::Settings, Database, Lockfile, Report, Warning,
+    WarningKind,
+};
+use std::{fs, io, time::Duration};
+
+/// How long to wait for the lock on the crates.io index, which may be held by a concurrent cargo command.
+const INDEX_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Checks dependencies against the RustSec advisory database.
+pub struct Audit;
+
+impl Analysis for Audit {
+    fn category(&self) -> Category {
+        Category::Dependencies
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Auditing dependencies via the RustSec advisory database...");
+        }
+
+        let lock_file = context.root.join("Cargo.lock");
+        let lockfile = Lockfile::load(&lock_file).map_err(|e| Error::Io {
+            path: lock_file.clone(),
+            source: io::Error::new(io::ErrorKind::InvalidData, e),
+        })?;
+        let database = Database::fetch().map_err(Error::Advisories)?;
+        let mut report = Report::generate(&database, &lockfile, &Settings::default());
+        // The index is only consulted for yanked crates, so is skipped (like `cargo audit`) when unavailable
+        if let Ok(mut index) = CachedIndex::fetch(None, INDEX_LOCK_TIMEOUT) {
+            let registry = lockfile
+                .packages
+                .iter()
+                .filter(|p| p.source.as_ref().is_some_and(|s| s.is_default_registry()));
+            for package in index.find_yanked(registry).into_iter().flatten() {
+                report
+                    .warnings
+                    .entry(WarningKind::Yanked)
+                    .or_default()
+                    .push(Warning::new(WarningKind::Yanked, package, None, None, None));
+            }
+        }
+
+        let lock = fs::read_to_string(&lock_file).unwrap_or_default();
+        let file = manifests::relative(&context.root, &lock_file.to_string_lossy());
+        let vulnerabilities = report.vulnerabilities.list.into_iter().map(|v| {
+            let issue = Issue {
+                kind: "vulnerability".into(),
+                advisory: Some(v.advisory),
+                versions: Some(v.versions),
+                package: v.package,
+            };
+            (Severity::Error, issue)
+        });
+        let warnings = report.warnings.into_values().flatten().map(|w| {
+            let issue = Issue {
+                kind: w.kind.as_str().into(),
+                advisory: w.advisory,
+                versions: w.versions,
+                package: w.package,
+            };
+            (Severity::Warning, issue)
+        });
+        let mut findings = Vec::new();
+        for (severity, issue) in vulnerabilities.chain(warnings) {
+            let (name, version) = (issue.package.name.as_str(), &issue.package.version);
+            let mut finding = Finding::new(
+                Category::Dependencies,
+                severity,
+                match &issue.advisory {
+                    Some(advisory) => {
+                        format!("{} {}: {} ({})", name, version, advisory.title, advisory.id)
+                    }
+                    None => format!("{} {} is {}", name, version, issue.kind),
+                },
+            )
+            .with_code(&format!("sbp::{}", issue.kind.replace('-', "_")));
+            let line = line_containing(&lock, &format!("name = \"{}\"", name));
+            finding.location = Some(Location {
+                file: file.clone(),
+                line,
+                column: 1,
+                line_end: line,
+                column_end: 1,
+            });
+            if let Some(patched) = issue.versions.as_ref().map(|v| v.patched()) {
+                if !patched.is_empty() {
+                    let patched: Vec<String> = patched.iter().map(ToString::to_string).collect();
+                    finding
+                        .help
+                        .push(format!("upgrade to {}", patched.join(" or ")));
+                }
+            }
+            if let Some(advisory) = &issue.advisory {
+                finding.help.push(format!(
+                    "see https://rustsec.org/advisories/{}",
+                    advisory.id
+                ));
+            }
+            if let Some(finding) = context.config.apply(finding) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        }
+        if text && findings.is_empty() {
+            println!("No advisories found");
+        }
+        Ok(findings)
+    }
+}
+
+/// A vulnerability or warning of the report.
+struct Issue {
+    /// The kind of warning, or `vulnerability`.
+    kind: String,
+    advisory: Option<advisory::Metadata>,
+    versions: Option<advisory::Versions>,
+    package: rustsec::package::Package,
+}
@@ -0,0 +1,231 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    link, manifests, Analysis, Context, Error, Format,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+
+/// Executes available benchmarks as tests, via `cargo test` with the `runtime-benchmarks` feature enabled.
+pub struct Benchmarks;
+
+impl Analysis for Benchmarks {
+    fn category(&self) -> Category {
+        Category::Benchmarks
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        if context.format == Format::Text {
+            println!("Executing available benchmarks...");
+        }
+
+        let mut command = context.cargo_packages("test");
+        command
+            .arg("--no-default-features")
+            .arg("--features=runtime-benchmarks")
+            .arg("--no-fail-fast")
+            .stdout(context.stdout());
+        let status = context
+            .output(&mut command, "benchmarks")
+            .map_err(|e| Error::command("cargo test", e))?
+            .status;
+
+        Ok(match status.success() {
+            true => Vec::new(),
+            false => context
+                .config
+                .apply(
+                    Finding::new(
+                        Category::Benchmarks,
+                        Severity::Error,
+                        format!("benchmarks failed ({})", status),
+                    )
+                    .with_code("sbp::benchmarks_failed"),
+                )
+                .into_iter()
+                .collect(),
+        })
+    }
+}
+
+/// Executes FRAME benchmarks via the `benchmark pallet` command of a node, capturing the resulting weights.
+pub struct FrameBenchmarks;
+
+impl Analysis for FrameBenchmarks {
+    fn category(&self) -> Category {
+        Category::Benchmarks
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        let binary = binary(context)?;
+        if text {
+            println!(
+                "Executing benchmarks via {} benchmark pallet...",
+                binary.display()
+            );
+        }
+
+        // Weights are output to the target directory for review
+        let metadata = manifests::metadata(context)?;
+        let output_directory = Path::new(&metadata.target_directory)
+            .join("sbp-review")
+            .join("weights");
+        let output = benchmark(context, &binary, &output_directory)?;
+
+        let mut findings = Vec::new();
+        let mut report = |finding: Finding| {
+            if let Some(finding) = context.config.apply(finding) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        };
+        for weight in weights(&String::from_utf8_lossy(&output.stdout)) {
+            report(
+                Finding::new(
+                    Category::Benchmarks,
+                    Severity::Info,
+                    format!(
+                        "{}::{} ~= {} µs, {} read(s), {} write(s)",
+                        weight.pallet, weight.extrinsic, weight.time, weight.reads, weight.writes
+                    ),
+                )
+                .with_code("sbp::benchmark_weight"),
+            );
+        }
+        if !output.status.success() {
+            report(
+                Finding::new(
+                    Category::Benchmarks,
+                    Severity::Error,
+                    format!("benchmarks failed ({})", output.status),
+                )
+                .with_code("sbp::benchmarks_failed"),
+            );
+        }
+        if text {
+            println!(
+                "Weights written to {}",
+                link(
+                    &output_directory.display().to_string(),
+                    &format!("file:///{}", output_directory.display())
+                )
+            );
+        }
+        Ok(findings)
+    }
+}
+
+/// The configured node binary used to execute benchmarks, relative to the workspace root.
+pub(crate) fn binary(context: &Context) -> Result<PathBuf, Error> {
+    match &context.config.benchmarks.binary {
+        Some(binary) => Ok(context.root.join(binary)),
+        None => Err(Error::Config(
+            "no node binary configured to execute benchmarks, use --binary or set 'binary' within [benchmarks]"
+                .into(),
+        )),
+    }
+}
+
+/// Executes the configured benchmarks via the `benchmark pallet` command of the node binary, writing the resulting
+/// weight files (one per pallet) to the output directory.
+pub(crate) fn benchmark(
+    context: &Context,
+    binary: &Path,
+    output_directory: &Path,
+) -> Result<Output, Error> {
+    let config = &context.config.benchmarks;
+    fs::create_dir_all(output_directory).map_err(|source| Error::Io {
+        path: output_directory.to_path_buf(),
+        source,
+    })?;
+    let mut command = Command::new(binary);
+    command
+        .args(["benchmark", "pallet"])
+        .args(["--chain", &config.chain])
+        .args(["--pallet", &config.pallet])
+        .args(["--extrinsic", &config.extrinsic])
+        .args(["--steps", &config.steps.to_string()])
+        .args(["--repeat", &config.repeat.to_string()])
+        .arg("--output")
+        .arg(output_directory)
+        .current_dir(&context.root)
+        .stdout(Stdio::piped())
+        .stderr(context.stdout());
+    context
+        .output(&mut command, "benchmarks")
+        .map_err(|source| match source.kind() {
+            io::ErrorKind::NotFound => Error::Io {
+                path: binary.to_path_buf(),
+                source,
+            },
+            _ => Error::command("benchmark pallet", source),
+        })
+}
+
+/// The result of benchmarking an extrinsic, according to the median slopes analysis.
+struct Weight {
+    pallet: String,
+    extrinsic: String,
+    /// The base time, in microseconds.
+    time: String,
+    reads: String,
+    writes: String,
+}
+
+/// Parses the weights resulting from `benchmark pallet` from its output, which is of the form:
+///
+/// ```text
+/// Pallet: "pallet_template", Extrinsic: "do_something", Lowest values: [], Highest values: [], Steps: 50, Repeat: 20
+/// ...
+/// Median Slopes Analysis
+/// ========
+/// -- Extrinsic Time --
+///
+/// Model:
+/// Time ~=     8.581
+///               µs
+///
+/// Reads = 0
+/// Writes = 1
+/// ```
+fn weights(output: &str) -> Vec<Weight> {
+    let mut weights: Vec<Weight> = Vec::new();
+    let mut median = false;
+    for line in output.lines().map(str::trim) {
+        if let Some(line) = line.strip_prefix("Pallet: ") {
+            let mut names = line.split('"').skip(1).step_by(2);
+            weights.push(Weight {
+                pallet: names.next().unwrap_or_default().into(),
+                extrinsic: names.next().unwrap_or_default().into(),
+                time: "-".into(),
+                reads: "-".into(),
+                writes: "-".into(),
+            });
+            median = false;
+            continue;
+        }
+        match line {
+            "Median Slopes Analysis" => median = true,
+            "Min Squares Analysis" => median = false,
+            _ => {}
+        }
+        let Some(weight) = weights.last_mut().filter(|_| median) else {
+            continue;
+        };
+        for (prefix, value) in [
+            ("Time ~=", &mut weight.time),
+            ("Reads =", &mut weight.reads),
+            ("Writes =", &mut weight.writes),
+        ] {
+            if let Some(v) = line.strip_prefix(prefix) {
+                *value = v.trim().into();
+            }
+        }
+    }
+    weights
+}
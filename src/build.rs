@@ -0,0 +1,161 @@
+use crate::{
+    clippy::Match,
+    findings::{Category, Finding, Severity},
+    manifests, Analysis, Context, Error, Format,
+};
+use std::process::{Command, Stdio};
+
+/// The target for which `no_std` crates are built, as used by runtimes.
+const TARGET: &str = "wasm32-unknown-unknown";
+
+/// Features which frequently break silently, so are each verified to build.
+const FEATURES: [&str; 2] = ["runtime-benchmarks", "try-runtime"];
+
+/// Verifies that `no_std` crates (those with a `std` feature) build for WebAssembly without default features, and that
+/// crates build with each of the `runtime-benchmarks` and `try-runtime` features, via `cargo check`.
+pub struct Build;
+
+impl Analysis for Build {
+    fn category(&self) -> Category {
+        Category::Build
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        let metadata = manifests::metadata(context)?;
+        let packages = |feature: &str| -> Vec<&str> {
+            metadata
+                .packages
+                .iter()
+                .filter(|p| context.selected(&p.name) && p.features.contains_key(feature))
+                .map(|p| p.name.as_str())
+                .collect()
+        };
+
+        // The build configurations to verify, along with the packages and arguments of each
+        let mut configurations = Vec::new();
+        let no_std = packages("std");
+        if !no_std.is_empty() {
+            let targets = Command::new("rustup")
+                .args(["target", "list", "--installed"])
+                .current_dir(&context.root)
+                .output();
+            if !targets.is_ok_and(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .lines()
+                    .any(|t| t == TARGET)
+            }) {
+                return Err(Error::NotInstalled {
+                    tool: "wasm32-unknown-unknown target",
+                    hint: "run `rustup target add wasm32-unknown-unknown`",
+                });
+            }
+            configurations.push((
+                format!("for {} without default features", TARGET),
+                no_std,
+                vec![
+                    "--no-default-features".to_string(),
+                    "--target".into(),
+                    TARGET.into(),
+                ],
+            ));
+        }
+        for feature in FEATURES {
+            let packages = packages(feature);
+            if packages.is_empty() {
+                continue;
+            }
+            let features: Vec<_> = packages
+                .iter()
+                .map(|p| format!("{}/{}", p, feature))
+                .collect();
+            configurations.push((
+                format!("with the '{}' feature", feature),
+                packages,
+                vec!["--features".into(), features.join(",")],
+            ));
+        }
+        if configurations.is_empty() {
+            if text {
+                println!("No no_std crates or feature-gated crates found");
+            }
+            return Ok(Vec::new());
+        }
+
+        let mut findings = Vec::new();
+        for (description, packages, args) in configurations {
+            if text {
+                println!(
+                    "Verifying {} crate(s) build {}...",
+                    packages.len(),
+                    description
+                );
+            }
+            let mut command = context.cargo("check");
+            for package in &packages {
+                command.arg("--package").arg(package);
+            }
+            command
+                .args(&args)
+                .arg("--message-format=json")
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+            let output = context
+                .output(&mut command, "build")
+                .map_err(|e| Error::command("cargo check", e))?;
+
+            let mut report = |mut finding: Finding| {
+                finding.category = Category::Build;
+                finding.help.push(format!("when building {}", description));
+                if let Some(finding) = context.config.apply(finding) {
+                    if text {
+                        finding.print(context);
+                    }
+                    findings.push(finding);
+                }
+            };
+            let mut errors = 0;
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                let Ok(m) = serde_json::from_str::<Match>(line) else {
+                    continue;
+                };
+                // Only errors are reported, as warnings are reported by the code analysis; summary messages (e.g.
+                // 'aborting due to previous error') have no location
+                if m.message
+                    .as_ref()
+                    .is_none_or(|m| m.level != "error" || m.spans.is_empty())
+                {
+                    continue;
+                }
+                let mut finding = Finding::from(&m);
+                if let Some(location) = &mut finding.location {
+                    location.file = manifests::relative(&context.root, &location.file);
+                }
+                errors += 1;
+                report(finding);
+            }
+
+            // Failures without compiler errors (e.g. a failing build script) are reported as a whole
+            if !output.status.success() && errors == 0 {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let mut finding = Finding::new(
+                    Category::Build,
+                    Severity::Error,
+                    format!("build {} failed", description),
+                )
+                .with_code("sbp::build_failed");
+                finding.help.extend(
+                    stderr
+                        .lines()
+                        .filter(|l| l.starts_with("error"))
+                        .map(Into::into),
+                );
+                report(finding);
+            }
+            if text && output.status.success() {
+                println!("{} crate(s) build {}", packages.len(), description);
+            }
+        }
+        Ok(findings)
+    }
+}
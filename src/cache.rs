@@ -0,0 +1,93 @@
+//! Caching of analysis results, keyed by a hash of the content of the analysed files.
+
+use crate::findings::Finding;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A cache of findings per package, located within the target directory.
+pub struct Cache {
+    directory: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    key: String,
+    findings: Vec<Finding>,
+}
+
+impl Cache {
+    pub fn new(target_directory: &Path, analysis: &str) -> Self {
+        Cache {
+            directory: target_directory.join("sbp-review").join(analysis),
+        }
+    }
+
+    /// Returns the cached findings for the package, provided they were cached with the same key.
+    pub fn get(&self, package: &str, key: &str) -> Option<Vec<Finding>> {
+        let contents = fs::read(self.path(package)).ok()?;
+        let entry: Entry = serde_json::from_slice(&contents).ok()?;
+        (entry.key == key).then_some(entry.findings)
+    }
+
+    /// Caches the findings for the package. Failures are ignored, as the cache is only an optimisation.
+    pub fn put(&self, package: &str, key: &str, findings: Vec<Finding>) {
+        let entry = Entry {
+            key: key.into(),
+            findings,
+        };
+        if let Ok(contents) = serde_json::to_vec(&entry) {
+            let _ = fs::create_dir_all(&self.directory)
+                .and_then(|_| fs::write(self.path(package), contents));
+        }
+    }
+
+    fn path(&self, package: &str) -> PathBuf {
+        self.directory.join(format!("{}.json", package))
+    }
+}
+
+/// A 64-bit FNV-1a hasher, used as its output is stable across toolchain versions.
+pub struct Hasher(u64);
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Hasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher {
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Hashes the names and contents of all files within the directory, excluding hidden and `target` directories.
+    pub fn write_directory(&mut self, directory: &Path) {
+        let Ok(entries) = fs::read_dir(directory) else {
+            return;
+        };
+        let mut paths: Vec<_> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        paths.sort();
+        for path in paths {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if name.starts_with('.') || name == "target" {
+                continue;
+            }
+            self.write(name.as_bytes());
+            if path.is_dir() {
+                self.write_directory(&path);
+            } else if let Ok(contents) = fs::read(&path) {
+                self.write(&contents);
+            }
+        }
+    }
+
+    pub fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
@@ -0,0 +1,418 @@
+use crate::{
+    findings::{Category, Finding, Location, Severity},
+    manifests::{self, line_containing},
+    Analysis, Context, Error, Format,
+};
+use serde_json::Value;
+use std::{
+    fs,
+    net::{TcpStream, ToSocketAddrs},
+    path::PathBuf,
+    time::Duration,
+};
+
+/// Well-known development accounts, derived from the `//Alice`..`//Ferdie` seeds, as SS58 addresses (generic prefix)
+/// and public keys.
+const DEV_KEYS: [(&str, &str, &str); 7] = [
+    (
+        "Alice",
+        "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+        "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d",
+    ),
+    (
+        "Alice (ed25519)",
+        "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu",
+        "88dc3417d5058ec4b4503e0c12ea1a0a89be200fe98922423d4334014fa6b0ee",
+    ),
+    (
+        "Bob",
+        "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty",
+        "8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48",
+    ),
+    (
+        "Charlie",
+        "5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y",
+        "90b5ab205c6974c9ea841be688864633dc9ca8a357843eeacf2314649965fe22",
+    ),
+    (
+        "Dave",
+        "5DAAnrj7VHTznn2AWBemMuyBwZWs6FNFjdyVXUeYum3PTXFy",
+        "306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20",
+    ),
+    (
+        "Eve",
+        "5HGjWAeFDfFCWPsjFQdVV2Msvz2XtMktvgocEZcCj68kUMaw",
+        "e659a7a1628cdd93febc04a4e0646ea20e9f5f0ce097d9a05290d4a9e054df4e",
+    ),
+    (
+        "Ferdie",
+        "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+        "1cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07c",
+    ),
+];
+
+/// The maximum number of token decimals considered sensible.
+const MAX_DECIMALS: u64 = 18;
+
+/// Validates a chain specification: its structure, the absence of development keys within non-development chains, its
+/// token properties and (optionally) the reachability of its boot nodes.
+pub struct ChainSpec {
+    /// The path of the chain specification (JSON).
+    pub path: PathBuf,
+    /// Whether the boot nodes are verified to be reachable.
+    pub boot_nodes: bool,
+}
+
+impl Analysis for ChainSpec {
+    fn category(&self) -> Category {
+        Category::Runtime
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Validating chain spec {}...", self.path.display());
+        }
+        let contents = fs::read_to_string(&self.path).map_err(|source| Error::Io {
+            path: self.path.clone(),
+            source,
+        })?;
+        let file = manifests::relative(
+            &context.root,
+            &self
+                .path
+                .canonicalize()
+                .unwrap_or_else(|_| self.path.clone())
+                .to_string_lossy(),
+        );
+
+        let mut findings = Vec::new();
+        let mut report = |severity, code: &str, message: String, needle: &str| {
+            let line = line_containing(&contents, needle);
+            let finding = Finding::new(Category::Runtime, severity, message)
+                .with_code(code)
+                .with_location(Location {
+                    file: file.clone(),
+                    line,
+                    column: 1,
+                    line_end: line,
+                    column_end: 1,
+                });
+            if let Some(finding) = context.config.apply(finding) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        };
+
+        let spec: Value = match serde_json::from_str(&contents) {
+            Ok(spec) => spec,
+            Err(e) => {
+                report(
+                    Severity::Error,
+                    "sbp::invalid_chain_spec",
+                    format!("chain spec is not valid JSON: {}", e),
+                    "",
+                );
+                return Ok(findings);
+            }
+        };
+
+        check(&spec, &mut report);
+        if self.boot_nodes && !context.offline {
+            for boot_node in boot_nodes(&spec) {
+                let reachable = address(boot_node).is_some_and(|(host, port)| {
+                    (host, port).to_socket_addrs().is_ok_and(|mut addresses| {
+                        addresses
+                            .any(|a| TcpStream::connect_timeout(&a, Duration::from_secs(5)).is_ok())
+                    })
+                });
+                if !reachable {
+                    report(
+                        Severity::Warning,
+                        "sbp::boot_nodes",
+                        format!("boot node {} is not reachable", boot_node),
+                        boot_node,
+                    );
+                }
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// Checks the structure of the chain spec, the absence of development keys within non-development chains, its token
+/// properties and the presence of boot nodes, reporting the severity, code and message of each issue along with text
+/// locating it within the chain spec.
+fn check(spec: &Value, report: &mut impl FnMut(Severity, &str, String, &str)) {
+    // Structure
+    for field in ["name", "id", "chainType", "genesis"] {
+        if spec.get(field).is_none() {
+            report(
+                Severity::Error,
+                "sbp::invalid_chain_spec",
+                format!("chain spec has no '{}'", field),
+                "",
+            );
+        }
+    }
+    let genesis = spec.get("genesis");
+    if let Some(genesis) = genesis {
+        const KINDS: [&str; 4] = ["runtime", "runtimeGenesis", "raw", "runtimeAndCode"];
+        if !KINDS.iter().any(|k| genesis.get(k).is_some()) {
+            report(
+                Severity::Error,
+                "sbp::invalid_chain_spec",
+                format!(
+                    "genesis contains none of {}",
+                    KINDS.map(|k| format!("'{}'", k)).join(", ")
+                ),
+                "\"genesis\"",
+            );
+        }
+    }
+
+    // Development keys are only expected within development (or local) chains
+    let chain_type = spec.get("chainType").and_then(Value::as_str).unwrap_or("");
+    let development = matches!(chain_type, "Development" | "Local");
+    if let Some(genesis) = genesis.filter(|_| !development) {
+        let mut strings = Vec::new();
+        collect(genesis, "/genesis".into(), &mut strings);
+        for (_, value) in &mut strings {
+            *value = value.to_lowercase();
+        }
+        for (name, address, public) in DEV_KEYS {
+            let address_lowercase = address.to_lowercase();
+            let Some((path, value)) = strings
+                .iter()
+                .find(|(_, value)| value.contains(&address_lowercase) || value.contains(public))
+            else {
+                continue;
+            };
+            report(
+                Severity::Error,
+                "sbp::dev_key",
+                format!(
+                    "{} chain spec contains the well-known development key of {} at {}",
+                    match chain_type {
+                        "" => "non-development",
+                        chain_type => chain_type,
+                    },
+                    name,
+                    path
+                ),
+                match value.contains(&address_lowercase) {
+                    true => address,
+                    false => public,
+                },
+            );
+        }
+    }
+
+    // Token properties
+    match spec.get("properties").and_then(Value::as_object) {
+        None => report(
+            Severity::Warning,
+            "sbp::chain_spec_properties",
+            "chain spec has no properties (tokenSymbol, tokenDecimals, ss58Format)".into(),
+            "",
+        ),
+        Some(properties) => {
+            let values = |key: &str| -> Vec<Value> {
+                match properties.get(key) {
+                    Some(Value::Array(values)) => values.clone(),
+                    Some(value) => vec![value.clone()],
+                    None => Vec::new(),
+                }
+            };
+            let symbols = values("tokenSymbol");
+            if symbols.is_empty()
+                || symbols
+                    .iter()
+                    .any(|s| s.as_str().is_none_or(|s| s.trim().is_empty()))
+            {
+                report(
+                    Severity::Warning,
+                    "sbp::chain_spec_properties",
+                    "chain spec has no valid 'tokenSymbol' property".into(),
+                    "\"properties\"",
+                );
+            }
+            let decimals = values("tokenDecimals");
+            if decimals.is_empty()
+                || decimals
+                    .iter()
+                    .any(|d| d.as_u64().is_none_or(|d| d > MAX_DECIMALS))
+            {
+                report(
+                    Severity::Warning,
+                    "sbp::chain_spec_properties",
+                    format!(
+                        "chain spec has no valid 'tokenDecimals' property (an integer up to {})",
+                        MAX_DECIMALS
+                    ),
+                    "\"properties\"",
+                );
+            }
+            if properties.get("ss58Format").is_some_and(|f| !f.is_u64()) {
+                report(
+                    Severity::Warning,
+                    "sbp::chain_spec_properties",
+                    "chain spec 'ss58Format' property is not an integer".into(),
+                    "\"ss58Format\"",
+                );
+            }
+        }
+    }
+
+    // Boot nodes
+    if boot_nodes(spec).is_empty() && !development {
+        report(
+            Severity::Warning,
+            "sbp::boot_nodes",
+            "chain spec has no boot nodes".into(),
+            "\"bootNodes\"",
+        );
+    }
+}
+
+/// The boot nodes of the chain spec.
+fn boot_nodes(spec: &Value) -> Vec<&str> {
+    spec.get("bootNodes")
+        .and_then(Value::as_array)
+        .map(|b| b.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Collects the string values within the JSON value, along with their JSON pointer. The runtime code is excluded, as
+/// it may legitimately contain development keys for use within development genesis presets.
+fn collect(value: &Value, path: String, strings: &mut Vec<(String, String)>) {
+    /// The keys of the runtime code, within plain and raw (`:code`) genesis.
+    const CODE: [&str; 2] = ["code", "0x3a636f6465"];
+    match value {
+        Value::String(s) => strings.push((path, s.clone())),
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                collect(value, format!("{}/{}", path, index), strings);
+            }
+        }
+        Value::Object(map) => {
+            for (key, value) in map.iter().filter(|(k, _)| !CODE.contains(&k.as_str())) {
+                // Keys of raw storage may also contain accounts
+                strings.push((path.clone(), key.clone()));
+                collect(value, format!("{}/{}", path, key), strings);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parses the host and TCP port of a boot node multiaddress, e.g. `/dns/boot.example.com/tcp/30333/p2p/12D3..`.
+fn address(multiaddress: &str) -> Option<(String, u16)> {
+    let mut parts = multiaddress.trim_start_matches('/').split('/');
+    let host = match parts.next()? {
+        "ip4" | "ip6" | "dns" | "dns4" | "dns6" => parts.next()?.to_string(),
+        _ => return None,
+    };
+    if parts.next()? != "tcp" {
+        return None;
+    }
+    Some((host, parts.next()?.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// The codes and messages of the issues reported for the chain spec.
+    fn issues(spec: Value) -> Vec<(String, String)> {
+        let mut issues = Vec::new();
+        check(&spec, &mut |_, code: &str, message, _: &str| {
+            issues.push((code.to_string(), message))
+        });
+        issues
+    }
+
+    #[test]
+    fn reports_invalid_chain_specs() {
+        let spec = json!({
+            "name": "Live",
+            "chainType": "Live",
+            "genesis": {
+                "runtimeGenesis": {
+                    "code": "0x00",
+                    "patch": {
+                        "balances": { "balances": [["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", 1000]] },
+                        "sudo": { "key": "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty" }
+                    }
+                }
+            },
+            "properties": { "tokenSymbol": " ", "tokenDecimals": 24, "ss58Format": "42" }
+        });
+        let messages: Vec<_> = issues(spec).into_iter().map(|(_, m)| m).collect();
+        assert_eq!(
+            messages,
+            [
+                "chain spec has no 'id'",
+                "Live chain spec contains the well-known development key of Alice at /genesis/runtimeGenesis/patch/balances/balances/0/0",
+                "Live chain spec contains the well-known development key of Bob at /genesis/runtimeGenesis/patch/sudo/key",
+                "chain spec has no valid 'tokenSymbol' property",
+                "chain spec has no valid 'tokenDecimals' property (an integer up to 18)",
+                "chain spec 'ss58Format' property is not an integer",
+                "chain spec has no boot nodes",
+            ]
+        );
+        assert_eq!(
+            issues(json!({ "name": "Live", "id": "live", "chainType": "Live", "genesis": {} }))
+                .into_iter()
+                .map(|(code, _)| code)
+                .collect::<Vec<_>>(),
+            [
+                "sbp::invalid_chain_spec",
+                "sbp::chain_spec_properties",
+                "sbp::boot_nodes"
+            ]
+        );
+    }
+
+    #[test]
+    fn valid_chain_specs_are_accepted() {
+        let live = json!({
+            "name": "Live",
+            "id": "live",
+            "chainType": "Live",
+            "bootNodes": ["/dns/boot.example.com/tcp/30333/p2p/12D3KooWExample"],
+            "genesis": {
+                // Development keys within the runtime code are ignored
+                "raw": { "top": { "0x3a636f6465": "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d" } }
+            },
+            "properties": { "tokenSymbol": ["LIVE"], "tokenDecimals": [12], "ss58Format": 42 }
+        });
+        assert!(issues(live).is_empty());
+        // Development keys and the absence of boot nodes are expected within development chains
+        let development = json!({
+            "name": "Development",
+            "id": "dev",
+            "chainType": "Development",
+            "genesis": { "runtimeGenesis": { "patch": { "sudo": { "key": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY" } } } },
+            "properties": { "tokenSymbol": "UNIT", "tokenDecimals": 12 }
+        });
+        assert!(issues(development).is_empty());
+    }
+
+    #[test]
+    fn parses_boot_node_addresses() {
+        assert_eq!(
+            address("/dns/boot.example.com/tcp/30333/p2p/12D3KooWExample"),
+            Some(("boot.example.com".into(), 30333))
+        );
+        assert_eq!(
+            address("/ip4/127.0.0.1/tcp/30333"),
+            Some(("127.0.0.1".into(), 30333))
+        );
+        assert_eq!(address("/ip4/127.0.0.1/udp/30333/quic-v1"), None);
+        assert_eq!(address("/memory/1234"), None);
+    }
+}
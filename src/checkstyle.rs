@@ -0,0 +1,39 @@
+use crate::{
+    findings::{Finding, Location},
+    html::escape,
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Renders the findings as a Checkstyle XML report, grouped by file. Note that only findings with a source location
+/// are included.
+pub fn render(findings: &[Finding]) -> String {
+    let mut files: BTreeMap<&str, Vec<(&Location, &Finding)>> = BTreeMap::new();
+    for finding in findings {
+        if let Some(location) = &finding.location {
+            files
+                .entry(&location.file)
+                .or_default()
+                .push((location, finding));
+        }
+    }
+
+    let mut xml =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"4.3\">\n");
+    for (file, findings) in files {
+        let _ = writeln!(xml, "  <file name=\"{}\">", escape(file));
+        for (location, finding) in findings {
+            let _ = writeln!(
+                xml,
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"{}\"/>",
+                location.line,
+                location.column,
+                finding.severity.as_str(),
+                escape(&finding.message),
+                escape(finding.code.as_deref().unwrap_or("sbp-review"))
+            );
+        }
+        xml.push_str("  </file>\n");
+    }
+    xml.push_str("</checkstyle>\n");
+    xml
+}
@@ -0,0 +1,821 @@
+use crate::{
+    cache::{Cache, Hasher},
+    config::{Config, Level, Profile},
+    findings::{self, Category, Finding, Location, Severity},
+    manifests,
+    progress::Progress,
+    watchdog::Watchdog,
+    Analysis, Context, Error, Format,
+};
+use colored::Colorize;
+use regex::RegexSet;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+};
+
+/// Analyses code for known issues, via `cargo clippy`.
+pub struct Clippy;
+
+impl Analysis for Clippy {
+    fn category(&self) -> Category {
+        Category::Code
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Analysing code via clippy...");
+        }
+
+        let args = lint_args(&context.config)?;
+        let metadata = manifests::metadata(context)?;
+        let toolchain = Command::new("cargo")
+            .args(["clippy", "--version"])
+            .current_dir(&context.root)
+            .output()
+            .map_err(|e| Error::command("cargo clippy", e))?;
+        if !toolchain.status.success() {
+            return Err(Error::NotInstalled {
+                tool: "cargo clippy",
+                hint: "run `rustup component add clippy`",
+            });
+        }
+
+        // Runtime crates (pallets, runtimes and their primitives) are those built without `std`, for WebAssembly
+        let runtime: HashSet<_> = metadata
+            .packages
+            .iter()
+            .filter(|p| p.features.contains_key("std"))
+            .map(|p| p.name.as_str())
+            .collect();
+
+        // Findings are output as they become available, unless they need to be grouped or ranked once all are available
+        let deferred = context.group_by.is_some() || context.top.is_some();
+        let mut findings = Vec::new();
+        let mut report = |mut finding: Finding| {
+            if finding
+                .package
+                .as_deref()
+                .is_some_and(|p| runtime.contains(p))
+            {
+                escalate(&mut finding);
+            }
+            if let Some(finding) = context.config.apply(finding) {
+                if text && !deferred && context.max_results.is_none_or(|max| findings.len() < max) {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        };
+
+        // Use cached findings for any packages which are unchanged since they were last analysed
+        let cache = Cache::new(Path::new(&metadata.target_directory), "clippy");
+        let macros = ignored_macros(&context.config)?;
+        let mut stale = Vec::new();
+        for package in metadata
+            .packages
+            .iter()
+            .filter(|p| context.selected(&p.name))
+        {
+            let mut hasher = Hasher::default();
+            hasher.write(&toolchain.stdout);
+            hasher.write(args.join(" ").as_bytes());
+            hasher.write(selection(context).join(" ").as_bytes());
+            hasher.write(macros.patterns().join("\n").as_bytes());
+            if let Ok(lock) = fs::read(context.root.join("Cargo.lock")) {
+                hasher.write(&lock);
+            }
+            write_package(
+                &mut hasher,
+                &package.name,
+                &metadata.packages,
+                &mut Vec::new(),
+            );
+            let key = hasher.finish();
+            match context
+                .cache
+                .then(|| cache.get(&package.name, &key))
+                .flatten()
+            {
+                Some(cached) => cached.into_iter().for_each(&mut report),
+                None => stale.push((&package.name, key)),
+            }
+        }
+
+        if !stale.is_empty() {
+            let packages: Vec<_> = stale.iter().map(|(p, _)| p.as_str()).collect();
+            let progress = Progress::new(context, "clippy", Some(packages.len()));
+            let analysed = lint(context, &packages, &args, &progress, &mut |_, f| {
+                progress.suspend(|| report(f.clone()))
+            });
+            drop(progress);
+            // Findings are not cached when clippy times out, as they may be incomplete
+            let (analysed, stale) = match analysed {
+                Ok(analysed) => (analysed, stale),
+                Err(e) => {
+                    report(e.timed_out(Category::Code)?);
+                    (Vec::new(), Vec::new())
+                }
+            };
+            for (package, key) in stale {
+                let package_findings: Vec<_> = analysed
+                    .iter()
+                    .filter(|f| f.package.as_ref() == Some(package))
+                    .cloned()
+                    .collect();
+                cache.put(package, &key, package_findings);
+            }
+        }
+
+        findings.sort_by(|a, b| {
+            let key = |f: &Finding| {
+                f.location
+                    .as_ref()
+                    .map(|l| (l.file.clone(), l.line, l.column))
+            };
+            key(a).cmp(&key(b))
+        });
+        if text {
+            let shown = findings::limit(&findings, context.top, context.max_results);
+            if deferred {
+                match context.group_by {
+                    Some(group_by) => findings::print_grouped(&shown, group_by, context),
+                    None => shown.iter().for_each(|f| f.print(context)),
+                }
+            }
+            let hidden = findings.len() - shown.len();
+            if hidden > 0 {
+                println!(
+                    "{} finding(s) hidden, re-run without `--max-results`/`--top` (or with `--lint <PATTERN>`) to see them",
+                    hidden
+                );
+            }
+            summarise(&findings);
+        }
+        Ok(findings)
+    }
+}
+
+/// Outputs the number of findings of each lint, most frequent first, followed by the total number of each severity.
+fn summarise(findings: &[Finding]) {
+    let mut lints: BTreeMap<&str, usize> = BTreeMap::new();
+    for finding in findings {
+        *lints
+            .entry(finding.code.as_deref().unwrap_or("-"))
+            .or_default() += 1;
+    }
+    let mut lints: Vec<_> = lints.into_iter().collect();
+    // Sorting is stable, so lints of equal frequency remain ordered by name
+    lints.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    println!("{}", "Lint Summary".bold());
+    let width = lints
+        .first()
+        .map_or(1, |(_, count)| count.to_string().len());
+    for (lint, count) in &lints {
+        println!("  {:>width$}  {}", count, lint.cyan(), width = width);
+    }
+    let count = |severity| findings.iter().filter(|f| f.severity == severity).count();
+    println!(
+        "  total: {} {}, {} {}, {} info",
+        count(Severity::Error),
+        "error(s)".red(),
+        count(Severity::Warning),
+        "warning(s)".yellow(),
+        count(Severity::Info)
+    );
+}
+
+/// Escalates potential panics within runtime code to errors, as a panic within the runtime halts block production.
+fn escalate(finding: &mut Finding) {
+    const PANICS: [&str; 7] = [
+        "clippy::expect_used",
+        "clippy::indexing_slicing",
+        "clippy::panic",
+        "clippy::todo",
+        "clippy::unimplemented",
+        "clippy::unreachable",
+        "clippy::unwrap_used",
+    ];
+    if finding
+        .code
+        .as_ref()
+        .is_some_and(|c| PANICS.contains(&c.as_str()))
+    {
+        finding.severity = Severity::Error;
+        finding
+            .help
+            .push("a panic within the runtime halts block production".into());
+    }
+}
+
+/// Returns the clippy arguments for the configured lints: all lints of the configured profile (or with a configured
+/// severity) are set as warning, unless overridden.
+pub(crate) fn lint_args(config: &Config) -> Result<Vec<String>, Error> {
+    let profile = config.lints.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let mut lints = profile_lints(profile, &config.profiles, 0)?;
+    for lint in config
+        .lints
+        .enable
+        .iter()
+        .chain(config.severity.keys().filter(|c| c.starts_with("clippy::")))
+    {
+        if !lints.contains(lint) {
+            lints.push(lint.clone());
+        }
+    }
+    Ok(lints
+        .iter()
+        .filter(|l| !config.lints.disable.contains(l))
+        .map(|l| match config.severity.get(l) {
+            Some(Level::Error) => format!("-D{}", l),
+            Some(Level::Ignore) => format!("-A{}", l),
+            Some(Level::Warning | Level::Info) | None => format!("-W{}", l),
+        })
+        .chain(config.lints.disable.iter().map(|l| format!("-A{}", l)))
+        .collect())
+}
+
+/// Hashes the files of a workspace package, along with those of any workspace packages it depends on.
+fn write_package<'a>(
+    hasher: &mut Hasher,
+    name: &'a str,
+    packages: &'a [manifests::Package],
+    visited: &mut Vec<&'a str>,
+) {
+    if visited.contains(&name) {
+        return;
+    }
+    visited.push(name);
+    let Some(package) = packages.iter().find(|p| p.name == name) else {
+        return;
+    };
+    if let Some(directory) = Path::new(&package.manifest_path).parent() {
+        hasher.write_directory(directory);
+    }
+    for dependency in package.dependencies.iter().filter(|d| d.path.is_some()) {
+        write_package(hasher, &dependency.name, packages, visited);
+    }
+}
+
+/// The arguments selecting the targets and features linted, in addition to the default targets and features.
+fn selection(context: &Context) -> Vec<String> {
+    let mut args: Vec<String> = match context.all_targets {
+        true => vec!["--all-targets".into()],
+        false => [("--tests", context.tests), ("--benches", context.benches)]
+            .into_iter()
+            .filter(|(_, selected)| *selected)
+            .map(|(arg, _)| arg.into())
+            .collect(),
+    };
+    if context.all_features {
+        args.push("--all-features".into());
+    } else if !context.features.is_empty() {
+        args.push("--features".into());
+        args.push(context.features.join(","));
+    }
+    args
+}
+
+/// Lints the packages via `cargo clippy`, streaming each finding (along with the message it was parsed from) to the
+/// callback as it is emitted, before returning all resulting findings. Progress is indicated as each package is checked.
+pub(crate) fn lint(
+    context: &Context,
+    packages: &[&str],
+    args: &[String],
+    progress: &Progress,
+    on_finding: &mut dyn FnMut(&Match, &Finding),
+) -> Result<Vec<Finding>, Error> {
+    let clippy_config = context.root.join("clippy.toml");
+    let clippy_config_exists = clippy_config.exists();
+    if !clippy_config_exists {
+        const CONFIG: &str = "too-many-lines-threshold=30";
+        fs::write(&clippy_config, CONFIG).map_err(|source| Error::Io {
+            path: clippy_config.clone(),
+            source,
+        })?;
+    }
+
+    let mut command = context.cargo("clippy");
+    for package in packages {
+        command.arg("--package").arg(package);
+    }
+    command.args(selection(context));
+    command
+        .arg("--message-format=json")
+        .arg("--")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let result = stream(context, command, packages, progress, on_finding);
+
+    if !clippy_config_exists {
+        fs::remove_file(&clippy_config).map_err(|source| Error::Io {
+            path: clippy_config,
+            source,
+        })?;
+    }
+    result
+}
+
+fn stream(
+    context: &Context,
+    mut command: Command,
+    packages: &[&str],
+    progress: &Progress,
+    on_finding: &mut dyn FnMut(&Match, &Finding),
+) -> Result<Vec<Finding>, Error> {
+    let macros = ignored_macros(&context.config)?;
+    let mut child = command
+        .spawn()
+        .map_err(|e| Error::command("cargo clippy", e))?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    // Stderr is read on a separate thread, so that neither pipe blocks cargo whilst the other is being read
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let stderr = thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stderr.read_to_end(&mut output);
+        output
+    });
+    let timeout = context.config.timeout("code");
+    let watchdog = Watchdog::new(&child, timeout);
+
+    let mut findings = Vec::new();
+    let mut checked = HashSet::new();
+    let mut reported = HashSet::new();
+    for line in BufReader::new(stdout).lines() {
+        let line = line.map_err(|e| Error::command("cargo clippy", e))?;
+        let m = match serde_json::from_str::<Match>(&line) {
+            Ok(m) => m,
+            Err(e) => {
+                progress.suspend(|| eprintln!("{} {}", e, line));
+                continue;
+            }
+        };
+        if m.reason == "compiler-artifact" {
+            if let Some(package) = m.package().filter(|p| packages.contains(p)) {
+                if checked.insert(package.to_string()) {
+                    progress.package(package);
+                }
+            }
+        }
+        // Filter matches
+        if !m
+            .message
+            .as_ref()
+            .is_some_and(|m| m.code.is_some() && !ignored(m, &macros))
+        {
+            continue;
+        }
+        let mut finding = Finding::from(&m);
+        if let Some(location) = &mut finding.location {
+            location.file = manifests::relative(&context.root, &location.file);
+        }
+        // Source shared by multiple targets (e.g. a library and its unit tests) is only reported once
+        let key = (
+            finding.code.clone(),
+            finding.message.clone(),
+            finding
+                .location
+                .as_ref()
+                .map(|l| (l.file.clone(), l.line, l.column)),
+        );
+        if !reported.insert(key) {
+            continue;
+        }
+        on_finding(&m, &finding);
+        findings.push(finding);
+    }
+    let status = child
+        .wait()
+        .map_err(|e| Error::command("cargo clippy", e))?;
+    if let (true, Some(timeout)) = (watchdog.finish(), timeout) {
+        return Err(Error::Timeout {
+            command: "cargo clippy",
+            timeout,
+        });
+    }
+    // Clippy failing without reporting any findings indicates it could not be run at all, e.g. due to an unknown feature
+    let stderr = stderr.join().unwrap_or_default();
+    if !status.success() && findings.is_empty() {
+        return Err(Error::failed("cargo clippy", &stderr));
+    }
+    Ok(findings)
+}
+
+/// The FRAME macros within whose expansions findings are ignored, as they are not actionable by the author.
+const FRAME_MACROS: [&str; 7] = [
+    "construct_runtime!",
+    "#[frame_support::pallet]",
+    "#[pallet::call]",
+    "#[pallet::error]",
+    "#[pallet::event]",
+    "#[pallet::pallet]",
+    "#[pallet::storage]",
+];
+
+/// The patterns matching the source of macros within whose expansions findings are ignored.
+fn ignored_macros(config: &Config) -> Result<RegexSet, Error> {
+    let frame = FRAME_MACROS
+        .iter()
+        .filter(|_| config.macros.frame)
+        .map(|m| regex::escape(m));
+    RegexSet::new(frame.chain(config.macros.ignore.iter().cloned()))
+        .map_err(|e| Error::Config(format!("invalid macro pattern: {}", e)))
+}
+
+fn ignored(message: &Message, macros: &RegexSet) -> bool {
+    message
+        .spans
+        .iter()
+        .any(|s| s.text.iter().any(|t| macros.is_match(&t.text)))
+}
+
+/// The profile used when none is configured.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Resolves the lints of a built-in or user-defined profile, including those of any profile it extends.
+pub fn profile_lints(
+    name: &str,
+    profiles: &BTreeMap<String, Profile>,
+    depth: usize,
+) -> Result<Vec<String>, Error> {
+    if depth > profiles.len() {
+        return Err(Error::Config(format!(
+            "lint profile '{}' extends itself",
+            name
+        )));
+    }
+    if let Some(profile) = profiles.get(name) {
+        let mut lints = match &profile.extends {
+            Some(extends) => profile_lints(extends, profiles, depth + 1)?,
+            None => Vec::new(),
+        };
+        lints.extend(profile.lints.iter().cloned());
+        return Ok(lints);
+    }
+    let lints: Vec<&str> = match name {
+        "strict" => STRICT.to_vec(),
+        "default" => STRICT
+            .iter()
+            .copied()
+            .filter(|l| !STYLISTIC.contains(l))
+            .collect(),
+        "relaxed" => RELAXED.to_vec(),
+        _ => {
+            return Err(Error::Config(format!(
+                "unknown lint profile '{}', expected one of strict, default, relaxed{}",
+                name,
+                profiles
+                    .keys()
+                    .map(|p| format!(", {}", p))
+                    .collect::<String>()
+            )))
+        }
+    };
+    Ok(lints.into_iter().map(Into::into).collect())
+}
+
+/// Largely stylistic lints, which are only included in the `strict` profile.
+const STYLISTIC: [&str; 33] = [
+    "clippy::bool_to_int_with_if",
+    "clippy::default_trait_access",
+    "clippy::else_if_without_else",
+    "clippy::empty_structs_with_brackets",
+    "clippy::equatable_if_let",
+    "clippy::explicit_into_iter_loop",
+    "clippy::explicit_iter_loop",
+    "clippy::if_not_else",
+    "clippy::if_then_some_else_none",
+    "clippy::inconsistent_struct_constructor",
+    "clippy::items_after_statements",
+    "clippy::large_digit_groups",
+    "clippy::manual_string_new",
+    "clippy::map_unwrap_or",
+    "clippy::match_bool",
+    "clippy::module_name_repetitions",
+    "clippy::multiple_inherent_impl",
+    "clippy::nonstandard_macro_braces",
+    "clippy::option_if_let_else",
+    "clippy::partial_pub_fields",
+    "clippy::print_stderr",
+    "clippy::print_stdout",
+    "clippy::pub_use",
+    "clippy::redundant_closure_for_method_calls",
+    "clippy::redundant_pub_crate",
+    "clippy::similar_names",
+    "clippy::uninlined_format_args",
+    "clippy::unnecessary_self_imports",
+    "clippy::unneeded_field_pattern",
+    "clippy::unnested_or_patterns",
+    "clippy::unreadable_literal",
+    "clippy::use_debug",
+    "clippy::use_self",
+];
+
+/// Lints identifying potential panics, overflows and other runtime safety issues.
+const RELAXED: [&str; 26] = [
+    "clippy::arithmetic_side_effects",
+    "clippy::cast_possible_truncation",
+    "clippy::cast_possible_wrap",
+    "clippy::cast_precision_loss",
+    "clippy::cast_sign_loss",
+    "clippy::dbg_macro",
+    "clippy::exit",
+    "clippy::expect_used",
+    "clippy::fallible_impl_from",
+    "clippy::float_arithmetic",
+    "clippy::get_unwrap",
+    "clippy::indexing_slicing",
+    "clippy::integer_division",
+    "clippy::large_stack_arrays",
+    "clippy::lossy_float_literal",
+    "clippy::panic",
+    "clippy::panic_in_result_fn",
+    "clippy::string_slice",
+    "clippy::todo",
+    "clippy::unimplemented",
+    "clippy::unreachable",
+    "clippy::unwrap_in_result",
+    "clippy::unwrap_used",
+    "clippy::cognitive_complexity",
+    "clippy::too-many-lines",
+    "clippy::maybe_infinite_iter",
+];
+
+// Source: https://rust-lang.github.io/rust-clippy/master/
+const STRICT: [&str; 124] = [
+    "clippy::alloc_instead_of_core",
+    "clippy::allow_attributes_without_reason",
+    "clippy::arithmetic_side_effects",
+    "clippy::as_underscore",
+    "clippy::assertions_on_result_states",
+    "clippy::bool_to_int_with_if",
+    "clippy::branches_sharing_code",
+    "clippy::cargo_common_metadata",
+    "clippy::cast_lossless",
+    "clippy::cast_possible_truncation",
+    "clippy::cast_possible_wrap",
+    "clippy::cast_precision_loss",
+    "clippy::cast_sign_loss",
+    "clippy::checked_conversions",
+    "clippy::cloned_instead_of_copied",
+    "clippy::cognitive_complexity",
+    "clippy::dbg_macro",
+    "clippy::default_trait_access",
+    "clippy::derive_partial_eq_without_eq",
+    "clippy::else_if_without_else",
+    "clippy::empty_structs_with_brackets",
+    "clippy::enum_glob_use",
+    "clippy::equatable_if_let",
+    "clippy::exit",
+    "clippy::expect_used",
+    "clippy::explicit_into_iter_loop",
+    "clippy::explicit_iter_loop",
+    "clippy::fallible_impl_from",
+    "clippy::filter_map_next",
+    "clippy::flat_map_option",
+    "clippy::float_arithmetic",
+    "clippy::float_cmp",
+    "clippy::float_cmp_const",
+    "clippy::format_push_string",
+    "clippy::get_unwrap",
+    "clippy::if_not_else",
+    "clippy::if_then_some_else_none",
+    "clippy::indexing_slicing",
+    "clippy::integer_division",
+    "clippy::implicit_clone",
+    "clippy::inconsistent_struct_constructor",
+    "clippy::inefficient_to_string",
+    "clippy::invalid_upcast_comparisons",
+    "clippy::items_after_statements",
+    "clippy::iter_on_empty_collections",
+    "clippy::iter_on_single_items",
+    "clippy::iter_with_drain",
+    "clippy::large_digit_groups",
+    "clippy::large_include_file",
+    "clippy::large_stack_arrays",
+    "clippy::large_types_passed_by_value",
+    "clippy::let_underscore_must_use",
+    "clippy::linkedlist",
+    "clippy::lossy_float_literal",
+    "clippy::manual_clamp",
+    "clippy::manual_ok_or",
+    "clippy::manual_string_new",
+    "clippy::many_single_char_names",
+    "clippy::map_err_ignore",
+    "clippy::map_unwrap_or",
+    "clippy::match_bool",
+    "clippy::match_on_vec_items",
+    "clippy::match_same_arms",
+    "clippy::match_wild_err_arm",
+    "clippy::match_wildcard_for_single_variants",
+    "clippy::maybe_infinite_iter",
+    "clippy::mismatching_type_param_order",
+    "clippy::mixed_read_write_in_expression",
+    "clippy::module_name_repetitions",
+    "clippy::multiple_crate_versions",
+    "clippy::multiple_inherent_impl",
+    "clippy::needless_collect",
+    "clippy::needless_continue",
+    "clippy::needless_for_each",
+    "clippy::needless_pass_by_value",
+    "clippy::no_effect_underscore_binding",
+    "clippy::nonstandard_macro_braces",
+    "clippy::option_if_let_else",
+    "clippy::option_option",
+    "clippy::or_fun_call",
+    "clippy::panic",
+    "clippy::panic_in_result_fn",
+    "clippy::partial_pub_fields",
+    "clippy::print_stderr",
+    "clippy::print_stdout",
+    "clippy::pub_use",
+    "clippy::range_minus_one",
+    "clippy::range_plus_one",
+    "clippy::redundant_clone",
+    "clippy::redundant_closure_for_method_calls",
+    "clippy::redundant_pub_crate",
+    "clippy::ref_binding_to_reference",
+    "clippy::ref_option_ref",
+    "clippy::rest_pat_in_fully_bound_structs",
+    "clippy::same_functions_in_if_condition",
+    "clippy::same_name_method",
+    "clippy::similar_names",
+    "clippy::string_slice",
+    "clippy::string_to_string",
+    "clippy::struct_excessive_bools",
+    "clippy::suspicious_operation_groupings",
+    "clippy::todo",
+    "clippy::too-many-lines",
+    "clippy::trait_duplication_in_bounds",
+    "clippy::trivial_regex",
+    "clippy::trivially_copy_pass_by_ref",
+    "clippy::try_err",
+    "clippy::type_repetition_in_bounds",
+    "clippy::unimplemented",
+    "clippy::uninlined_format_args",
+    "clippy::unnecessary_join",
+    "clippy::unnecessary_self_imports",
+    "clippy::unnecessary_wraps",
+    "clippy::unneeded_field_pattern",
+    "clippy::unnested_or_patterns",
+    "clippy::unreachable",
+    "clippy::unreadable_literal",
+    "clippy::unused_self",
+    "clippy::unwrap_in_result",
+    "clippy::unwrap_used",
+    "clippy::use_debug",
+    "clippy::use_self",
+    "clippy::useless_let_if_seq",
+    "clippy::wildcard_enum_match_arm",
+];
+
+#[derive(Serialize, Deserialize)]
+pub struct Match {
+    pub reason: String,
+    pub package_id: Option<String>,
+    /// The target being compiled when the message was emitted.
+    #[serde(default)]
+    pub target: Option<Target>,
+    pub message: Option<Message>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Target {
+    pub kind: Vec<String>,
+}
+
+impl Target {
+    /// The kind of target, e.g. `lib`, `bin`, `test`, `bench` or `example`. Note that the unit tests of a library or
+    /// binary are of the same kind as the library or binary.
+    pub fn kind(&self) -> &str {
+        match self.kind.first().map(String::as_str).unwrap_or("lib") {
+            "rlib" | "dylib" | "cdylib" | "staticlib" | "proc-macro" => "lib",
+            kind => kind,
+        }
+    }
+}
+
+impl Match {
+    /// The name of the package of the message.
+    pub fn package(&self) -> Option<&str> {
+        manifests::package_name(self.package_id.as_deref()?)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Message {
+    pub code: Option<Code>,
+    pub level: String,
+    pub message: String,
+    pub spans: Vec<Span>,
+    pub children: Vec<Message>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Code {
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Span {
+    pub file_name: String,
+    pub line_start: u16,
+    pub column_start: u16,
+    pub line_end: u16,
+    pub column_end: u16,
+    #[serde(default)]
+    pub byte_start: usize,
+    #[serde(default)]
+    pub byte_end: usize,
+    pub text: Vec<Text>,
+    /// The suggested replacement of the span, if any.
+    #[serde(default)]
+    pub suggested_replacement: Option<String>,
+    /// Whether the suggestion can be applied automatically, e.g. `MachineApplicable`.
+    #[serde(default)]
+    pub suggestion_applicability: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Text {
+    pub text: String,
+}
+
+impl From<&Match> for Finding {
+    fn from(m: &Match) -> Self {
+        let mut finding = m.message.as_ref().map_or_else(
+            || Finding::new(Category::Code, Severity::Info, m.reason.clone()),
+            Finding::from,
+        );
+        finding.package = m.package().map(Into::into);
+        finding.target = m.target.as_ref().map(|t| t.kind().into());
+        finding
+    }
+}
+
+impl From<&Message> for Finding {
+    fn from(message: &Message) -> Self {
+        Finding {
+            category: Category::Code,
+            severity: match message.level.as_str() {
+                "error" => Severity::Error,
+                "warning" => Severity::Warning,
+                _ => Severity::Info,
+            },
+            code: message.code.as_ref().map(|c| c.code.clone()),
+            message: message.message.clone(),
+            package: None,
+            target: None,
+            location: message.spans.first().map(|s| Location {
+                file: s.file_name.clone(),
+                line: s.line_start,
+                column: s.column_start,
+                line_end: s.line_end,
+                column_end: s.column_end,
+            }),
+            help: message
+                .children
+                .iter()
+                .filter(|m| m.level == "help" && !m.message.starts_with("for further information"))
+                .map(|m| m.message.clone())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifies_packages_of_messages() {
+        let package = |id: &str| {
+            let message: Match = serde_json::from_value(serde_json::json!({
+                "reason": "compiler-message",
+                "package_id": id,
+            }))
+            .unwrap();
+            message.package().map(str::to_string)
+        };
+        assert_eq!(
+            package("pallet-foo 0.1.0 (path+file:///tmp/pallets/foo)").as_deref(),
+            Some("pallet-foo")
+        );
+        assert_eq!(
+            package("path+file:///tmp/pallets/foo#pallet-foo@0.1.0").as_deref(),
+            Some("pallet-foo")
+        );
+        assert_eq!(
+            package("path+file:///tmp/pallets/foo#0.1.0").as_deref(),
+            Some("foo")
+        );
+    }
+}
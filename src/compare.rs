@@ -0,0 +1,102 @@
+//! Comparison of two reports of findings, e.g. those of consecutive review rounds.
+
+use crate::{Context, Error, Finding, Format};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+/// The findings of a report compared with those of a previous report.
+#[derive(Serialize)]
+pub struct Comparison {
+    /// Findings of the previous report which are no longer present.
+    pub fixed: Vec<Finding>,
+    /// Findings which were not present within the previous report.
+    pub introduced: Vec<Finding>,
+    /// Findings present within both reports.
+    pub unchanged: Vec<Finding>,
+}
+
+impl Comparison {
+    /// Compares the findings with the previous findings. Findings are considered the same when their code, package,
+    /// file and message match, as line numbers typically shift between revisions.
+    pub fn new(previous: Vec<Finding>, current: Vec<Finding>) -> Self {
+        let key = |f: &Finding| {
+            (
+                f.category.to_string(),
+                f.code.clone(),
+                f.package.clone(),
+                f.location.as_ref().map(|l| l.file.clone()),
+                f.message.clone(),
+            )
+        };
+        let mut fixed: Vec<_> = previous.into_iter().map(Some).collect();
+        let (mut introduced, mut unchanged) = (Vec::new(), Vec::new());
+        for finding in current {
+            let matched = fixed
+                .iter_mut()
+                .find(|p| p.as_ref().is_some_and(|p| key(p) == key(&finding)));
+            match matched {
+                Some(previous) => {
+                    *previous = None;
+                    unchanged.push(finding)
+                }
+                None => introduced.push(finding),
+            }
+        }
+        Comparison {
+            fixed: fixed.into_iter().flatten().collect(),
+            introduced,
+            unchanged,
+        }
+    }
+}
+
+/// A report exported via `--format json`: the findings, along with the score in the case of a review.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Report {
+    Findings(Vec<Finding>),
+    Review { findings: Vec<Finding> },
+}
+
+/// Loads the findings of a report previously exported via `--format json`.
+pub fn load(path: &Path) -> Result<Vec<Finding>, Error> {
+    let contents = fs::read(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let report = serde_json::from_slice(&contents).map_err(|e| Error::Io {
+        path: path.to_path_buf(),
+        source: io::Error::new(io::ErrorKind::InvalidData, e),
+    })?;
+    Ok(match report {
+        Report::Findings(findings) | Report::Review { findings } => findings,
+    })
+}
+
+/// Compares the reports, printing the findings which were fixed, introduced or unchanged.
+pub fn print(context: &Context, previous: &Path, current: &Path) -> Result<Comparison, Error> {
+    let comparison = Comparison::new(load(previous)?, load(current)?);
+    if context.format != Format::Text {
+        println!("{}", serde_json::to_string_pretty(&comparison).unwrap());
+        return Ok(comparison);
+    }
+    for (title, findings) in [
+        ("Fixed".green(), &comparison.fixed),
+        ("Introduced".red(), &comparison.introduced),
+        ("Unchanged".normal(), &comparison.unchanged),
+    ] {
+        println!("{} ({})", title.bold(), findings.len());
+        for finding in findings {
+            print!("  ");
+            finding.print(context);
+        }
+    }
+    println!(
+        "{} fixed, {} introduced, {} unchanged",
+        comparison.fixed.len(),
+        comparison.introduced.len(),
+        comparison.unchanged.len()
+    );
+    Ok(comparison)
+}
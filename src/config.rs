@@ -0,0 +1,651 @@
+use crate::{
+    findings::{Finding, Severity},
+    Error, Format,
+};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use toml_edit::ImDocument;
+
+/// The name of the configuration file, located at the workspace root.
+pub const FILE: &str = "sbp-review.toml";
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub lints: Lints,
+    /// User-defined lint profiles, keyed by name.
+    pub profiles: BTreeMap<String, Profile>,
+    pub paths: Paths,
+    pub macros: Macros,
+    pub dependencies: Dependencies,
+    pub coverage: Coverage,
+    pub docs: Docs,
+    pub licenses: Licenses,
+    pub benchmarks: Benchmarks,
+    pub migrations: Migrations,
+    pub runtime: Runtime,
+    pub srtool: Srtool,
+    pub history: History,
+    pub score: Score,
+    pub budget: Budget,
+    /// The timeouts of spawned cargo commands in seconds, keyed by subcommand (or `default` for all subcommands).
+    pub timeout: BTreeMap<String, u64>,
+    /// Severity overrides, keyed by lint/finding code.
+    pub severity: BTreeMap<String, Level>,
+    pub output: Output,
+    /// Filters restricting the findings reported, as specified via the command line.
+    #[serde(skip)]
+    pub filter: Filter,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Lints {
+    /// The lint profile to use [default: `default`].
+    pub profile: Option<String>,
+    /// Lints to enable, in addition to those of the profile.
+    pub enable: Vec<String>,
+    /// Lints to disable.
+    pub disable: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// The profile to extend, if any.
+    pub extends: Option<String>,
+    /// The lints of the profile, in addition to any of the extended profile.
+    pub lints: Vec<String>,
+}
+
+/// The level at which a lint/finding is reported.
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+    Info,
+    /// The lint/finding is not reported.
+    Ignore,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Paths {
+    /// Paths (prefixes or globs, e.g. `**/weights.rs`) excluded from analysis: findings within them are ignored and
+    /// their lines are not included within coverage.
+    pub ignore: Vec<String>,
+    /// The changed line ranges of each file, to which findings are restricted when reviewing a diff.
+    #[serde(skip)]
+    pub changed: Option<BTreeMap<String, Vec<(u16, u16)>>>,
+}
+
+/// Filters restricting the findings reported to those of interest, e.g. only `clippy::panic` within `pallets/**`.
+#[derive(Default)]
+pub struct Filter {
+    /// Patterns matching the codes of findings reported, where `*` matches any characters, e.g. `clippy::*unwrap*`.
+    pub lints: Vec<String>,
+    /// The minimum severity of findings reported.
+    pub level: Option<Severity>,
+    /// Glob patterns matching the files of findings reported, where `*` and `?` match within a path component and
+    /// `**` matches any number of components. A pattern without wildcards matches files prefixed by it.
+    pub paths: Vec<String>,
+}
+
+impl Filter {
+    /// Whether the finding satisfies all filters.
+    fn matches(&self, finding: &Finding) -> bool {
+        if self.level.is_some_and(|level| finding.severity > level) {
+            return false;
+        }
+        if !self.lints.is_empty()
+            && !finding
+                .code
+                .as_ref()
+                .is_some_and(|code| self.lints.iter().any(|l| glob(l, code)))
+        {
+            return false;
+        }
+        if !self.paths.is_empty()
+            && !finding
+                .location
+                .as_ref()
+                .is_some_and(|location| self.paths.iter().any(|p| path_matches(p, &location.file)))
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Whether the file (relative to the workspace root) matches the pattern, either a glob or a prefix such as a directory.
+fn path_matches(pattern: &str, file: &str) -> bool {
+    let (pattern, file) = (
+        pattern.trim_start_matches("./"),
+        file.trim_start_matches("./"),
+    );
+    match pattern.contains(['*', '?']) {
+        true => glob(pattern, file),
+        false => file.starts_with(pattern),
+    }
+}
+
+/// Whether the text matches the glob pattern, where `?` and `*` match any character(s) other than `/`, whilst `**` also
+/// matches across `/`.
+fn glob(pattern: &str, text: &str) -> bool {
+    fn matches(pattern: &[u8], text: &[u8]) -> bool {
+        match pattern {
+            [] => text.is_empty(),
+            [b'*', b'*', rest @ ..] => {
+                // `**/` also matches no directories at all
+                let directories = rest.strip_prefix(b"/");
+                (0..=text.len()).any(|i| {
+                    matches(rest, &text[i..]) || directories.is_some_and(|r| matches(r, &text[i..]))
+                })
+            }
+            [b'*', rest @ ..] => (0..=text.len())
+                .take_while(|i| *i == 0 || text[i - 1] != b'/')
+                .any(|i| matches(rest, &text[i..])),
+            [b'?', rest @ ..] => text
+                .split_first()
+                .is_some_and(|(c, text)| *c != b'/' && matches(rest, text)),
+            [c, rest @ ..] => text
+                .split_first()
+                .is_some_and(|(t, text)| t == c && matches(rest, text)),
+        }
+    }
+    matches(pattern.as_bytes(), text.as_bytes())
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Macros {
+    /// Whether findings within the expansions of FRAME macros (e.g. `construct_runtime!`) are ignored.
+    pub frame: bool,
+    /// Patterns (regular expressions) matching the source of further macros within whose expansions findings are
+    /// ignored, e.g. `#\[orml_.*\]`.
+    pub ignore: Vec<String>,
+}
+
+impl Default for Macros {
+    fn default() -> Self {
+        Macros {
+            frame: true,
+            ignore: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Dependencies {
+    /// Release branches which are considered up to date, in addition to those of the latest releases.
+    pub branches: Vec<String>,
+    /// The number of latest Polkadot SDK releases whose branches are considered up to date.
+    pub releases: usize,
+    /// Whether the revisions of git dependencies are verified to exist upstream.
+    pub verify_revisions: bool,
+    /// The URLs of the registries from which dependencies may be sourced.
+    pub registries: Vec<String>,
+    /// The git hosts or organisations (e.g. `github.com/paritytech`) from which dependencies may be sourced.
+    pub git: Vec<String>,
+}
+
+impl Default for Dependencies {
+    fn default() -> Self {
+        Dependencies {
+            branches: Vec::new(),
+            releases: 3,
+            verify_revisions: false,
+            registries: [
+                "https://github.com/rust-lang/crates.io-index",
+                "https://index.crates.io/",
+            ]
+            .map(String::from)
+            .to_vec(),
+            git: [
+                "github.com/paritytech",
+                "github.com/open-web3-stack",
+                "github.com/polkadot-fellows",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Coverage {
+    /// The minimum line coverage of each package, as a percentage.
+    pub threshold: f64,
+}
+
+impl Default for Coverage {
+    fn default() -> Self {
+        Coverage { threshold: 80.0 }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Docs {
+    /// The minimum percentage of public items of each package with documentation.
+    pub threshold: f64,
+}
+
+impl Default for Docs {
+    fn default() -> Self {
+        Docs { threshold: 80.0 }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Benchmarks {
+    /// The node binary used to execute benchmarks, built with the `runtime-benchmarks` feature.
+    pub binary: Option<PathBuf>,
+    /// The chain specification used to execute benchmarks.
+    pub chain: String,
+    /// The pallet(s) to benchmark, where `*` selects all pallets.
+    pub pallet: String,
+    /// The extrinsic(s) to benchmark, where `*` selects all extrinsics.
+    pub extrinsic: String,
+    /// The number of samples taken across the range of each component.
+    pub steps: u32,
+    /// The number of times each sample is repeated.
+    pub repeat: u32,
+}
+
+impl Default for Benchmarks {
+    fn default() -> Self {
+        Benchmarks {
+            binary: None,
+            chain: "dev".into(),
+            pallet: "*".into(),
+            extrinsic: "*".into(),
+            steps: 50,
+            repeat: 20,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Migrations {
+    /// The runtime package whose migrations are executed [default: the runtime package with a `try-runtime` feature].
+    pub runtime: Option<String>,
+    /// The RPC endpoint of a live chain from which state is fetched, e.g. `wss://rpc.polkadot.io:443`.
+    pub uri: Option<String>,
+    /// A state snapshot, as an alternative to fetching live state.
+    pub snapshot: Option<PathBuf>,
+    /// The checks executed: `none`, `all`, `pre-and-post` or `try-state`.
+    pub checks: String,
+}
+
+impl Default for Migrations {
+    fn default() -> Self {
+        Migrations {
+            runtime: None,
+            uri: None,
+            snapshot: None,
+            checks: "all".into(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Runtime {
+    /// The runtime package [default: the package named `*runtime*`].
+    pub package: Option<String>,
+    /// Development pallets (by name within the runtime or crate name) which are permitted, e.g. within testnet runtimes.
+    pub allow: Vec<String>,
+    /// The maximum size of the compressed runtime Wasm accepted by the relay chain, in bytes.
+    pub max_code_size: u64,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Runtime {
+            package: None,
+            allow: Vec::new(),
+            max_code_size: 3 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Srtool {
+    /// The srtool container image, whose tag should match the toolchain of the runtime.
+    pub image: String,
+}
+
+impl Default for Srtool {
+    fn default() -> Self {
+        Srtool {
+            image: "paritytech/srtool:latest".into(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Licenses {
+    /// Licenses (SPDX identifiers) which dependencies may use.
+    pub allow: Vec<String>,
+    /// Licenses (SPDX identifiers) which dependencies may not use, even if otherwise allowed.
+    pub deny: Vec<String>,
+}
+
+impl Default for Licenses {
+    fn default() -> Self {
+        Licenses {
+            allow: [
+                "Apache-2.0",
+                "BSD-2-Clause",
+                "BSD-3-Clause",
+                "BSL-1.0",
+                "CC0-1.0",
+                "ISC",
+                "MIT",
+                "MIT-0",
+                "MPL-2.0",
+                "Unicode-3.0",
+                "Unicode-DFS-2016",
+                "Unlicense",
+                "Zlib",
+                "GPL-3.0-only WITH Classpath-exception-2.0",
+                "GPL-3.0-or-later WITH Classpath-exception-2.0",
+            ]
+            .map(Into::into)
+            .to_vec(),
+            deny: Vec::new(),
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Budget {
+    /// The maximum number of errors, across all categories, before failing.
+    pub max_errors: Option<usize>,
+    /// The maximum number of warnings, across all categories, before failing.
+    pub max_warnings: Option<usize>,
+    /// The maximum number of errors and warnings of each category, keyed by category (e.g. `code`).
+    pub categories: BTreeMap<String, Limits>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Limits {
+    pub max_errors: Option<usize>,
+    pub max_warnings: Option<usize>,
+}
+
+impl Budget {
+    /// Returns a description of each budget exceeded by the findings, e.g. `12 warning(s) within code exceeds the
+    /// maximum of 10`.
+    pub fn exceeded(&self, findings: &[Finding]) -> Vec<String> {
+        let overall = Limits {
+            max_errors: self.max_errors,
+            max_warnings: self.max_warnings,
+        };
+        let mut exceeded = Vec::new();
+        for (category, limits) in std::iter::once((None, &overall))
+            .chain(self.categories.iter().map(|(c, l)| (Some(c), l)))
+        {
+            for (severity, max) in [
+                (Severity::Error, limits.max_errors),
+                (Severity::Warning, limits.max_warnings),
+            ] {
+                let Some(max) = max else {
+                    continue;
+                };
+                let count = findings
+                    .iter()
+                    .filter(|f| {
+                        f.severity == severity
+                            && category.is_none_or(|c| f.category.to_string() == *c)
+                    })
+                    .count();
+                if count > max {
+                    exceeded.push(format!(
+                        "{} {}(s){} exceeds the maximum of {}",
+                        count,
+                        severity.as_str(),
+                        category.map_or(String::new(), |c| format!(" within {}", c)),
+                        max
+                    ));
+                }
+            }
+        }
+        exceeded
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Score {
+    /// The weight of each category within the overall score of a review, keyed by category (e.g. `code`).
+    pub weights: BTreeMap<String, u32>,
+    /// The points deducted from the score of a category (out of 100) per error.
+    pub error: u32,
+    /// The points deducted from the score of a category (out of 100) per warning.
+    pub warning: u32,
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        Score {
+            weights: [
+                ("code", 3),
+                ("manifests", 1),
+                ("pallets", 3),
+                ("tests", 2),
+                ("docs", 1),
+                ("benchmarks", 2),
+                ("fmt", 1),
+                ("dependencies", 2),
+                ("coverage", 1),
+                ("build", 1),
+                ("runtime", 1),
+                ("node", 1),
+                ("migrations", 1),
+            ]
+            .into_iter()
+            .map(|(category, weight)| (category.into(), weight))
+            .collect(),
+            error: 10,
+            warning: 2,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct History {
+    /// Whether the findings of each run are recorded within `.sbp-review/history.jsonl`.
+    pub record: bool,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History { record: true }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Output {
+    pub format: Option<Format>,
+    /// The editor opened by links to source locations: one of [`EDITORS`], `none` for plain paths or a link template
+    /// such as `vscode://file/{path}:{line}:{col}` [default: `file`].
+    pub editor: Option<String>,
+}
+
+/// The link templates of supported editors, keyed by name, where `{path}`, `{line}` and `{col}` are substituted.
+pub const EDITORS: [(&str, &str); 7] = [
+    ("file", "file:///{path}:{line}:{col}"),
+    ("vscode", "vscode://file/{path}:{line}:{col}"),
+    (
+        "vscode-insiders",
+        "vscode-insiders://file/{path}:{line}:{col}",
+    ),
+    ("cursor", "cursor://file/{path}:{line}:{col}"),
+    ("idea", "idea://open?file={path}&line={line}&column={col}"),
+    (
+        "sublime",
+        "subl://open?url=file://{path}&line={line}&column={col}",
+    ),
+    ("zed", "zed://file/{path}:{line}:{col}"),
+];
+
+impl Output {
+    /// The link template of the configured editor, or `None` if source locations are output as plain paths.
+    pub fn editor(&self) -> Result<Option<&str>, Error> {
+        match self.editor.as_deref().unwrap_or("file") {
+            "none" => Ok(None),
+            editor => match EDITORS.iter().find(|(name, _)| *name == editor) {
+                Some((_, template)) => Ok(Some(template)),
+                None if editor.contains("{path}") => Ok(Some(editor)),
+                None => Err(Error::Config(format!(
+                    "unknown editor `{}`, expected one of {}, `none` or a link template containing `{{path}}`",
+                    editor,
+                    EDITORS.map(|(name, _)| format!("`{}`", name)).join(", ")
+                ))),
+            },
+        }
+    }
+}
+
+impl Config {
+    /// The timeout of cargo commands spawned by the analysis of the subcommand (e.g. `tests`), if any.
+    pub fn timeout(&self, subcommand: &str) -> Option<Duration> {
+        self.timeout
+            .get(subcommand)
+            .or_else(|| self.timeout.get("default"))
+            .map(|seconds| Duration::from_secs(*seconds))
+    }
+
+    /// Whether the file (relative to the workspace root) is excluded from analysis.
+    pub fn excluded(&self, file: &str) -> bool {
+        self.paths.ignore.iter().any(|p| path_matches(p, file))
+    }
+
+    /// Applies the configuration to a finding, returning `None` if it should be ignored.
+    pub fn apply(&self, mut finding: Finding) -> Option<Finding> {
+        if let Some(location) = &finding.location {
+            let file = location.file.trim_start_matches("./");
+            if self.excluded(file) {
+                return None;
+            }
+            if let Some(changed) = &self.paths.changed {
+                let (start, end) = (location.line, location.line_end.max(location.line));
+                if !changed
+                    .get(file)
+                    .is_some_and(|ranges| ranges.iter().any(|(s, e)| *s <= end && start <= *e))
+                {
+                    return None;
+                }
+            }
+        }
+        if let Some(code) = &finding.code {
+            if self.lints.disable.contains(code) {
+                return None;
+            }
+            match self.severity.get(code) {
+                Some(Level::Error) => finding.severity = Severity::Error,
+                Some(Level::Warning) => finding.severity = Severity::Warning,
+                Some(Level::Info) => finding.severity = Severity::Info,
+                Some(Level::Ignore) => return None,
+                None => {}
+            }
+        }
+        self.filter.matches(&finding).then_some(finding)
+    }
+}
+
+/// Loads the configuration file from the workspace root, if present, otherwise returns the default configuration.
+pub fn load(root: &Path) -> Result<Config, Error> {
+    let contents = match fs::read_to_string(root.join(FILE)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+        Err(source) => {
+            return Err(Error::Io {
+                path: root.join(FILE),
+                source,
+            })
+        }
+    };
+    let document = ImDocument::parse(contents)
+        .map_err(|e| Error::Config(format!("could not parse {}: {}", FILE, e)))?;
+    toml_edit::de::from_document(document)
+        .map_err(|e| Error::Config(format!("invalid {}: {}", FILE, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::Category;
+
+    #[test]
+    fn matches_globs() {
+        assert!(glob("src/*.rs", "src/lib.rs"));
+        assert!(!glob("src/*.rs", "src/a/lib.rs"));
+        assert!(glob("src/**/*.rs", "src/a/b/lib.rs"));
+        assert!(glob("src/**/*.rs", "src/lib.rs"));
+        assert!(glob("**/tests/*", "pallets/a/tests/mock.rs"));
+        assert!(glob("src/?.rs", "src/a.rs"));
+        assert!(!glob("src/?.rs", "src/ab.rs"));
+        assert!(!glob("src/?", "src//"));
+    }
+
+    #[test]
+    fn matches_paths() {
+        assert!(path_matches("./pallets/", "pallets/a/src/lib.rs"));
+        assert!(path_matches(
+            "pallets/*/src/lib.rs",
+            "./pallets/a/src/lib.rs"
+        ));
+        assert!(!path_matches("pallets/*/src", "pallets/a/src/lib.rs"));
+        assert!(!path_matches("runtime", "pallets/runtime/src/lib.rs"));
+    }
+
+    #[test]
+    fn reports_exceeded_budgets() {
+        let findings = [
+            (Category::Code, Severity::Error),
+            (Category::Code, Severity::Warning),
+            (Category::Code, Severity::Warning),
+            (Category::Manifests, Severity::Warning),
+        ]
+        .map(|(category, severity)| Finding::new(category, severity, String::new()));
+        let budget = Budget {
+            max_errors: Some(1),
+            max_warnings: Some(2),
+            categories: BTreeMap::from([(
+                "code".to_string(),
+                Limits {
+                    max_errors: None,
+                    max_warnings: Some(1),
+                },
+            )]),
+        };
+        assert_eq!(
+            budget.exceeded(&findings),
+            [
+                "3 warning(s) exceeds the maximum of 2",
+                "2 warning(s) within code exceeds the maximum of 1"
+            ]
+        );
+        assert!(Budget::default().exceeded(&findings).is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    link, manifests, Analysis, Context, Error, Format,
+};
+use colored::Colorize;
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path, process::Stdio};
+
+/// Measures the line coverage of tests, via `cargo llvm-cov`.
+pub struct Coverage;
+
+impl Analysis for Coverage {
+    fn category(&self) -> Category {
+        Category::Coverage
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Measuring test coverage via llvm-cov...");
+        }
+
+        let version = context
+            .cargo("llvm-cov")
+            .arg("--version")
+            .output()
+            .map_err(|e| Error::command("cargo llvm-cov", e))?;
+        if !version.status.success() {
+            return Err(Error::NotInstalled {
+                tool: "cargo llvm-cov",
+                hint: "run `cargo install cargo-llvm-cov`",
+            });
+        }
+
+        let metadata = manifests::metadata(context)?;
+        let mut command = context.cargo_packages("llvm-cov");
+        command
+            .arg("--json")
+            .arg("--summary-only")
+            .stdout(Stdio::piped())
+            .stderr(context.stdout());
+        let output = context
+            .output(&mut command, "coverage")
+            .map_err(|e| Error::command("cargo llvm-cov", e))?;
+        if !output.status.success() {
+            return Err(Error::failed("cargo llvm-cov", &output.stderr));
+        }
+        let export: Export =
+            serde_json::from_slice(&output.stdout).map_err(|source| Error::Parse {
+                command: "cargo llvm-cov",
+                source,
+            })?;
+
+        let mut packages: BTreeMap<&str, Vec<&File>> = BTreeMap::new();
+        for file in export.data.iter().flat_map(|d| &d.files) {
+            if context
+                .config
+                .excluded(&manifests::relative(&context.root, &file.filename))
+            {
+                continue;
+            }
+            if let Some(package) = metadata
+                .package_of(Path::new(&file.filename))
+                .filter(|p| context.selected(&p.name))
+            {
+                packages.entry(&package.name).or_default().push(file);
+            }
+        }
+
+        let threshold = context.config.coverage.threshold;
+        let mut findings = Vec::new();
+        for (package, files) in packages {
+            let total = files.iter().fold(Lines::default(), |total, f| Lines {
+                count: total.count + f.summary.lines.count,
+                covered: total.covered + f.summary.lines.covered,
+            });
+            let percent = total.percent();
+            if text {
+                println!("{} {:.1}%", package.cyan(), percent);
+                for file in &files {
+                    let name = manifests::relative(&context.root, &file.filename);
+                    println!(
+                        "  {} {:.1}%",
+                        link(&name, &format!("file:///{}", file.filename)),
+                        file.summary.lines.percent()
+                    );
+                }
+            }
+            if percent < threshold {
+                let finding = Finding::new(
+                    Category::Coverage,
+                    Severity::Warning,
+                    format!(
+                        "line coverage of {:.1}% is below the threshold of {}%",
+                        percent, threshold
+                    ),
+                )
+                .with_code("sbp::low_coverage")
+                .with_package(package);
+                if let Some(finding) = context.config.apply(finding) {
+                    if text {
+                        println!("  {} {}", finding.severity, finding.message);
+                    }
+                    findings.push(finding);
+                }
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// The coverage summary exported by `cargo llvm-cov --json --summary-only`.
+#[derive(Deserialize)]
+struct Export {
+    data: Vec<Data>,
+}
+
+#[derive(Deserialize)]
+struct Data {
+    files: Vec<File>,
+}
+
+#[derive(Deserialize)]
+struct File {
+    filename: String,
+    summary: Summary,
+}
+
+#[derive(Deserialize)]
+struct Summary {
+    lines: Lines,
+}
+
+#[derive(Default, Deserialize)]
+struct Lines {
+    count: u64,
+    covered: u64,
+}
+
+impl Lines {
+    fn percent(&self) -> f64 {
+        match self.count {
+            0 => 100.0,
+            count => self.covered as f64 * 100.0 / count as f64,
+        }
+    }
+}
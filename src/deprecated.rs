@@ -0,0 +1,284 @@
+//! A curated list of deprecated Substrate APIs and crates, along with their replacements.
+
+use crate::{
+    findings::{Category, Finding, Location, Severity},
+    manifests::{self, line_of, Package},
+    pallets::Source,
+};
+use proc_macro2::Span;
+use std::{collections::BTreeMap, fs, path::Path};
+use syn::{
+    visit::{self, Visit},
+    ItemUse, Macro, UseTree,
+};
+
+/// How a deprecated API is used.
+#[derive(PartialEq)]
+enum Usage {
+    /// Invoked as a macro, e.g. `decl_module! {..}`.
+    Macro,
+    /// Referenced via a path, including imports and attributes.
+    Path,
+}
+
+/// Deprecated APIs, identified by a (contiguous) sequence of path segments, along with their replacement.
+const APIS: [(Usage, &[&str], &str); 16] = [
+    (
+        Usage::Macro,
+        &["decl_module"],
+        "the `#[frame_support::pallet]` macro",
+    ),
+    (
+        Usage::Macro,
+        &["decl_storage"],
+        "the `#[frame_support::pallet]` macro",
+    ),
+    (
+        Usage::Macro,
+        &["decl_event"],
+        "the `#[frame_support::pallet]` macro",
+    ),
+    (
+        Usage::Macro,
+        &["decl_error"],
+        "the `#[frame_support::pallet]` macro",
+    ),
+    (
+        Usage::Macro,
+        &["benchmarks"],
+        "the `#[benchmarks]` macro (benchmarks v2)",
+    ),
+    (
+        Usage::Macro,
+        &["benchmarks_instance_pallet"],
+        "the `#[instance_benchmarks]` macro (benchmarks v2)",
+    ),
+    (
+        Usage::Path,
+        &["traits", "Currency"],
+        "the `fungible` traits, e.g. `fungible::Inspect` and `fungible::Mutate`",
+    ),
+    (
+        Usage::Path,
+        &["ReservableCurrency"],
+        "`fungible::MutateHold`",
+    ),
+    (
+        Usage::Path,
+        &["NamedReservableCurrency"],
+        "`fungible::MutateHold`",
+    ),
+    (
+        Usage::Path,
+        &["LockableCurrency"],
+        "`fungible::MutateFreeze`",
+    ),
+    (Usage::Path, &["GenesisBuild"], "`BuildGenesisConfig`"),
+    (
+        Usage::Path,
+        &["pallet", "generate_store"],
+        "the storage types directly, as `Store` is removed",
+    ),
+    (Usage::Path, &["T", "BlockNumber"], "`BlockNumberFor<T>`"),
+    (Usage::Path, &["from_ref_time"], "`Weight::from_parts`"),
+    (Usage::Path, &["EnsureOneOf"], "`EitherOfDiverse`"),
+    (Usage::Path, &["sp_std"], "`core` and `alloc`"),
+];
+
+/// Deprecated crates, along with their replacement.
+const CRATES: [(&str, &str); 7] = [
+    ("sp-std", "`core` and `alloc`"),
+    ("pallet-contracts", "`pallet-revive`"),
+    ("cumulus-pallet-dmp-queue", "`pallet-message-queue`"),
+    (
+        "pallet-democracy",
+        "OpenGov (`pallet-referenda` and `pallet-conviction-voting`)",
+    ),
+    (
+        "pallet-randomness-collective-flip",
+        "a secure source of randomness, such as BABE or the relay chain",
+    ),
+    ("sp-finality-grandpa", "`sp-consensus-grandpa`"),
+    ("sc-finality-grandpa", "`sc-consensus-grandpa`"),
+];
+
+/// Returns findings for the usage of deprecated APIs within the source, reported once per API at its first usage.
+pub(crate) fn apis(source: &Source) -> Vec<Finding> {
+    #[derive(Default)]
+    struct Usages(Vec<(usize, Span)>);
+    impl Usages {
+        fn find(&mut self, usage: Usage, segments: &[String], span: Span) {
+            for (index, (_, pattern, _)) in
+                APIS.iter().enumerate().filter(|(_, (u, ..))| *u == usage)
+            {
+                if segments
+                    .windows(pattern.len())
+                    .any(|window| window.iter().zip(*pattern).all(|(s, p)| s == p))
+                {
+                    self.0.push((index, span));
+                }
+            }
+        }
+    }
+    impl<'a> Visit<'a> for Usages {
+        fn visit_macro(&mut self, mac: &'a Macro) {
+            if let Some(last) = mac.path.segments.last() {
+                self.find(Usage::Macro, &[last.ident.to_string()], last.ident.span());
+            }
+            visit::visit_macro(self, mac);
+        }
+
+        fn visit_path(&mut self, path: &'a syn::Path) {
+            if let Some(last) = path.segments.last() {
+                let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+                self.find(Usage::Path, &segments, last.ident.span());
+            }
+            visit::visit_path(self, path);
+        }
+
+        fn visit_item_use(&mut self, item: &'a ItemUse) {
+            let mut paths = Vec::new();
+            flatten(&item.tree, Vec::new(), &mut paths);
+            for (segments, span) in paths {
+                self.find(Usage::Path, &segments, span);
+            }
+        }
+    }
+
+    let mut usages = Usages::default();
+    usages.visit_file(&source.file);
+    let mut apis: BTreeMap<usize, (Span, usize)> = BTreeMap::new();
+    for (index, span) in usages.0 {
+        apis.entry(index).or_insert((span, 0)).1 += 1;
+    }
+    apis.into_iter()
+        .map(|(index, (span, count))| {
+            let (usage, pattern, replacement) = &APIS[index];
+            Finding::new(
+                Category::Pallets,
+                Severity::Warning,
+                format!(
+                    "`{}{}` is deprecated, use {} instead{}",
+                    pattern.join("::"),
+                    match usage {
+                        Usage::Macro => "!",
+                        Usage::Path => "",
+                    },
+                    replacement,
+                    match count {
+                        1 => String::new(),
+                        count => format!(" ({} usages within file)", count),
+                    }
+                ),
+            )
+            .with_code("sbp::deprecated_api")
+            .with_location(source.location(span))
+        })
+        .collect()
+}
+
+/// Returns findings for any deprecated crates the package depends upon.
+pub(crate) fn dependencies(package: &Package, root: &Path) -> Vec<Finding> {
+    let manifest = fs::read_to_string(&package.manifest_path).unwrap_or_default();
+    let file = manifests::relative(root, &package.manifest_path);
+    package
+        .dependencies
+        .iter()
+        .filter_map(|dependency| {
+            let (name, replacement) = CRATES.iter().find(|(name, _)| dependency.name == *name)?;
+            let line = line_of(&manifest, dependency.rename.as_deref().unwrap_or(name));
+            Some(
+                Finding::new(
+                    Category::Pallets,
+                    Severity::Warning,
+                    format!(
+                        "dependency '{}' is deprecated, use {} instead",
+                        name, replacement
+                    ),
+                )
+                .with_code("sbp::deprecated_dependency")
+                .with_location(Location {
+                    file: file.clone(),
+                    line,
+                    column: 1,
+                    line_end: line,
+                    column_end: 1,
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Flattens the use tree into the segments of each path imported, along with the span of its final segment.
+fn flatten(tree: &UseTree, prefix: Vec<String>, paths: &mut Vec<(Vec<String>, Span)>) {
+    let with = |ident: &syn::Ident| {
+        let mut segments = prefix.clone();
+        segments.push(ident.to_string());
+        segments
+    };
+    match tree {
+        UseTree::Path(path) => flatten(&path.tree, with(&path.ident), paths),
+        UseTree::Name(name) => paths.push((with(&name.ident), name.ident.span())),
+        UseTree::Rename(rename) => paths.push((with(&rename.ident), rename.ident.span())),
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                flatten(tree, prefix.clone(), paths);
+            }
+        }
+        UseTree::Glob(glob) if !prefix.is_empty() => paths.push((prefix, glob.star_token.span)),
+        UseTree::Glob(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(code: &str) -> Vec<String> {
+        let source = Source {
+            path: "src/lib.rs".into(),
+            file: syn::parse_file(code).unwrap(),
+        };
+        apis(&source).into_iter().map(|f| f.message).collect()
+    }
+
+    #[test]
+    fn reports_deprecated_apis() {
+        assert_eq!(
+            messages(
+                r#"
+                use frame_support::traits::{Currency, ReservableCurrency};
+                use sp_std::prelude::*;
+                decl_storage! {}
+                fn weight<T: Config>(n: T::BlockNumber) -> Weight {
+                    Weight::from_ref_time(10) + Weight::from_ref_time(20)
+                }
+                "#
+            ),
+            [
+                "`decl_storage!` is deprecated, use the `#[frame_support::pallet]` macro instead",
+                "`traits::Currency` is deprecated, use the `fungible` traits, e.g. `fungible::Inspect` and `fungible::Mutate` instead",
+                "`ReservableCurrency` is deprecated, use `fungible::MutateHold` instead",
+                "`T::BlockNumber` is deprecated, use `BlockNumberFor<T>` instead",
+                "`from_ref_time` is deprecated, use `Weight::from_parts` instead (2 usages within file)",
+                "`sp_std` is deprecated, use `core` and `alloc` instead",
+            ]
+        );
+    }
+
+    #[test]
+    fn current_apis_are_accepted() {
+        assert!(messages(
+            r#"
+            use frame_support::traits::fungible::{Inspect, Mutate};
+            use alloc::vec::Vec;
+            #[benchmarks]
+            mod benchmarks {}
+            fn weight<T: Config>(n: BlockNumberFor<T>, currency: T::Currency) -> Weight {
+                Weight::from_parts(10, 0)
+            }
+            "#
+        )
+        .is_empty());
+    }
+}
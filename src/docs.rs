@@ -0,0 +1,226 @@
+use crate::{
+    clippy::Match,
+    findings::{Category, Finding, Severity},
+    manifests, Analysis, Context, Error, Format,
+};
+use colored::Colorize;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Builds documentation and reports any warnings (e.g. broken intra-doc links and missing docs), via `cargo doc`.
+pub struct Docs;
+
+impl Analysis for Docs {
+    fn category(&self) -> Category {
+        Category::Docs
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Analysing documentation via rustdoc...");
+        }
+
+        // Any existing flags are retained, so that the documentation builds as it otherwise would
+        let flags = env::var("RUSTDOCFLAGS").unwrap_or_default();
+        let mut command = context.cargo_packages("doc");
+        command
+            .arg("--no-deps")
+            .arg("--message-format=json")
+            .env(
+                "RUSTDOCFLAGS",
+                format!("{} -Dwarnings -Dmissing_docs", flags),
+            )
+            .stdout(Stdio::piped())
+            .stderr(context.stdout());
+        let output = context
+            .output(&mut command, "docs")
+            .map_err(|e| Error::command("cargo doc", e))?;
+
+        let mut findings = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Ok(m) = serde_json::from_str::<Match>(line) else {
+                continue;
+            };
+            if m.message.as_ref().is_none_or(|m| m.code.is_none()) {
+                continue;
+            }
+            let mut finding = Finding::from(&m);
+            finding.category = Category::Docs;
+            finding
+                .help
+                .retain(|h| !h.starts_with("to override `-D warnings`"));
+            if let Some(location) = &mut finding.location {
+                location.file = manifests::relative(&context.root, &location.file);
+            }
+            if let Some(finding) = context.config.apply(finding) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// Measures the percentage of public items with documentation per package, via nightly rustdoc's JSON output.
+pub struct DocsCoverage;
+
+impl Analysis for DocsCoverage {
+    fn category(&self) -> Category {
+        Category::Docs
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Measuring documentation coverage via rustdoc JSON...");
+        }
+
+        let version = Command::new("cargo")
+            .args(["+nightly", "--version"])
+            .current_dir(&context.root)
+            .output()
+            .map_err(|e| Error::command("cargo +nightly", e))?;
+        if !version.status.success() {
+            return Err(Error::NotInstalled {
+                tool: "nightly toolchain",
+                hint: "run `rustup toolchain install nightly`",
+            });
+        }
+
+        let metadata = manifests::metadata(context)?;
+        let threshold = context.config.docs.threshold;
+        let mut findings = Vec::new();
+        for package in metadata
+            .packages
+            .iter()
+            .filter(|p| context.selected(&p.name))
+        {
+            let Some(library) = package
+                .targets
+                .iter()
+                .find(|t| t.kind.iter().any(|k| k == "lib" || k == "proc-macro"))
+            else {
+                continue;
+            };
+            let mut command = Command::new("cargo");
+            command.args(["+nightly", "rustdoc", "--lib", "--package", &package.name]);
+            if let Some(manifest_path) = &context.manifest_path {
+                command.arg("--manifest-path").arg(manifest_path);
+            }
+            command
+                .args(["--", "-Zunstable-options", "--output-format=json"])
+                .stdout(context.stdout())
+                .stderr(Stdio::piped());
+            let output = context
+                .output(&mut command, "docs")
+                .map_err(|e| Error::command("cargo rustdoc", e))?;
+            if !output.status.success() {
+                return Err(Error::failed("cargo rustdoc", &output.stderr));
+            }
+
+            let path = Path::new(&metadata.target_directory)
+                .join("doc")
+                .join(format!("{}.json", library.name.replace('-', "_")));
+            let contents = fs::read(&path).map_err(|source| Error::Io {
+                path: path.clone(),
+                source,
+            })?;
+            let krate: Crate =
+                serde_json::from_slice(&contents).map_err(|source| Error::Parse {
+                    command: "cargo rustdoc",
+                    source,
+                })?;
+
+            // Count the documented public items of the crate, by kind
+            let mut kinds: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+            for item in krate
+                .index
+                .values()
+                .filter(|i| i.crate_id == 0 && i.visibility.as_str() == Some("public"))
+            {
+                let Some(kind) = item.inner.keys().next().map(String::as_str) else {
+                    continue;
+                };
+                if matches!(kind, "impl" | "use") {
+                    continue;
+                }
+                let (documented, total) = kinds.entry(kind).or_default();
+                *total += 1;
+                if item.docs.as_deref().is_some_and(|d| !d.trim().is_empty()) {
+                    *documented += 1;
+                }
+            }
+            let (documented, total) = kinds.values().fold((0, 0), |(d, t), (documented, total)| {
+                (d + documented, t + total)
+            });
+            let percent = percent(documented, total);
+            if text {
+                println!(
+                    "{} {:.1}% ({}/{} public items)",
+                    package.name.cyan(),
+                    percent,
+                    documented,
+                    total
+                );
+                for (kind, (documented, total)) in &kinds {
+                    println!(
+                        "  {}: {:.1}% ({}/{})",
+                        kind,
+                        self::percent(*documented, *total),
+                        documented,
+                        total
+                    );
+                }
+            }
+            if percent < threshold {
+                let finding = Finding::new(
+                    Category::Docs,
+                    Severity::Warning,
+                    format!(
+                        "documentation coverage of {:.1}% is below the threshold of {}%",
+                        percent, threshold
+                    ),
+                )
+                .with_code("sbp::low_doc_coverage")
+                .with_package(&package.name);
+                if let Some(finding) = context.config.apply(finding) {
+                    if text {
+                        println!("  {} {}", finding.severity, finding.message);
+                    }
+                    findings.push(finding);
+                }
+            }
+        }
+        Ok(findings)
+    }
+}
+
+fn percent(documented: usize, total: usize) -> f64 {
+    match total {
+        0 => 100.0,
+        total => documented as f64 * 100.0 / total as f64,
+    }
+}
+
+/// The subset of the rustdoc JSON format required to measure documentation coverage.
+#[derive(Deserialize)]
+struct Crate {
+    index: HashMap<String, Item>,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    crate_id: u32,
+    visibility: serde_json::Value,
+    docs: Option<String>,
+    /// The kind of item, keyed by name.
+    inner: serde_json::Map<String, serde_json::Value>,
+}
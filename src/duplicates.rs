@@ -0,0 +1,114 @@
+use crate::{
+    findings::{Category, Finding, Location, Severity},
+    lockfile::Lockfile,
+    manifests, Analysis, Context, Error, Format,
+};
+use std::collections::BTreeMap;
+
+/// Detects crates resolved at multiple versions within `Cargo.lock`.
+pub struct Duplicates;
+
+impl Analysis for Duplicates {
+    fn category(&self) -> Category {
+        Category::Dependencies
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Checking for duplicate dependency versions...");
+        }
+
+        let path = context.root.join("Cargo.lock");
+        let lockfile = Lockfile::read(&path)?;
+        let packages = &lockfile.packages;
+
+        // The direct dependencies of the selected local packages, including those of any local (path) dependencies
+        let mut local = vec![false; packages.len()];
+        let mut stack: Vec<usize> = (0..packages.len())
+            .filter(|i| packages[*i].source.is_none() && context.selected(&packages[*i].name))
+            .collect();
+        let mut direct = Vec::new();
+        while let Some(index) = stack.pop() {
+            if std::mem::replace(&mut local[index], true) {
+                continue;
+            }
+            for &dependency in &packages[index].dependencies {
+                match packages[dependency].source {
+                    None => stack.push(dependency),
+                    Some(_) if !direct.contains(&dependency) => direct.push(dependency),
+                    Some(_) => {}
+                }
+            }
+        }
+        direct.sort_by(|a, b| packages[*a].name.cmp(&packages[*b].name));
+        let reachable: Vec<(usize, Vec<bool>)> = direct
+            .iter()
+            .map(|d| (*d, lockfile.reachable(*d)))
+            .collect();
+
+        // Group the versions of each reachable crate
+        let mut crates: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (index, package) in packages.iter().enumerate() {
+            if reachable.iter().any(|(_, r)| r[index]) {
+                crates.entry(&package.name).or_default().push(index);
+            }
+        }
+
+        let file = manifests::relative(&context.root, &path.to_string_lossy());
+        let mut findings = Vec::new();
+        for (name, versions) in crates.into_iter().filter(|(_, v)| v.len() > 1) {
+            let mut finding = Finding::new(
+                Category::Dependencies,
+                Severity::Warning,
+                format!(
+                    "'{}' is resolved at {} versions: {}",
+                    name,
+                    versions.len(),
+                    versions
+                        .iter()
+                        .map(|v| packages[*v].version.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )
+            .with_code("sbp::duplicate_dependency");
+            let line = u16::try_from(packages[versions[0]].line).unwrap_or(u16::MAX);
+            finding.location = Some(Location {
+                file: file.clone(),
+                line,
+                column: 1,
+                line_end: line,
+                column_end: 1,
+            });
+            for version in &versions {
+                let via: Vec<_> = reachable
+                    .iter()
+                    .filter(|(d, r)| r[*version] && d != version)
+                    .map(|(d, _)| format!("{} {}", packages[*d].name, packages[*d].version))
+                    .collect();
+                let usage = match (direct.contains(version), via.is_empty()) {
+                    (true, true) => "is a direct dependency".into(),
+                    (true, false) => format!(
+                        "is a direct dependency and is pulled in by {}",
+                        via.join(", ")
+                    ),
+                    (false, _) => format!("is pulled in by {}", via.join(", ")),
+                };
+                finding
+                    .help
+                    .push(format!("{} {} {}", name, packages[*version].version, usage));
+            }
+            if let Some(finding) = context.config.apply(finding) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        }
+        if text && findings.is_empty() {
+            println!("No duplicate dependency versions found");
+        }
+        Ok(findings)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    watchdog::Elapsed,
+};
+use std::{
+    fmt::{Display, Formatter},
+    io,
+    path::PathBuf,
+    time::Duration,
+};
+
+/// An error preventing an analysis from being completed.
+#[derive(Debug)]
+pub enum Error {
+    /// A command could not be executed.
+    Command {
+        command: &'static str,
+        source: io::Error,
+    },
+    /// A command completed unsuccessfully.
+    Failed {
+        command: &'static str,
+        stderr: String,
+    },
+    /// A required tool is not installed.
+    NotInstalled {
+        tool: &'static str,
+        hint: &'static str,
+    },
+    /// The output of a command could not be parsed.
+    Parse {
+        command: &'static str,
+        source: serde_json::Error,
+    },
+    /// A command did not complete within its timeout, so was terminated.
+    Timeout {
+        command: &'static str,
+        timeout: Duration,
+    },
+    /// A file could not be read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// The configuration is invalid.
+    Config(String),
+    /// The RustSec advisory database could not be fetched or read.
+    Advisories(rustsec::Error),
+}
+
+impl Error {
+    /// Creates an error for a command which could not be executed, identifying when cargo itself is not installed.
+    pub(crate) fn command(command: &'static str, source: io::Error) -> Self {
+        if let Some(Elapsed(timeout)) = source.get_ref().and_then(|e| e.downcast_ref()) {
+            return Error::Timeout {
+                command,
+                timeout: *timeout,
+            };
+        }
+        match source.kind() {
+            io::ErrorKind::NotFound => Error::NotInstalled {
+                tool: "cargo",
+                hint: "install it via https://rustup.rs",
+            },
+            _ => Error::Command { command, source },
+        }
+    }
+
+    /// Converts an error resulting from a command timing out into a finding of the category, so that any remaining
+    /// analyses can continue. Any other error is returned as is.
+    pub fn timed_out(self, category: Category) -> Result<Finding, Error> {
+        match self {
+            Error::Timeout { .. } => {
+                Ok(Finding::new(category, Severity::Error, self.to_string())
+                    .with_code("sbp::timeout"))
+            }
+            e => Err(e),
+        }
+    }
+
+    /// Creates an error for a command which completed unsuccessfully.
+    pub(crate) fn failed(command: &'static str, stderr: &[u8]) -> Self {
+        Error::Failed {
+            command,
+            stderr: String::from_utf8_lossy(stderr).trim().into(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Command { command, source } => {
+                write!(f, "could not execute {}: {}", command, source)
+            }
+            Error::Failed { command, stderr } => write!(f, "{} failed: {}", command, stderr),
+            Error::NotInstalled { tool, hint } => write!(f, "{} not installed, {}", tool, hint),
+            Error::Parse { command, source } => {
+                write!(f, "could not parse output of {}: {}", command, source)
+            }
+            Error::Timeout { command, timeout } => write!(
+                f,
+                "{} timed out after {}s and was terminated",
+                command,
+                timeout.as_secs()
+            ),
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Config(message) => f.write_str(message),
+            Error::Advisories(e) => {
+                write!(f, "could not fetch the RustSec advisory database: {}", e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Command { source, .. } | Error::Io { source, .. } => Some(source),
+            Error::Parse { source, .. } => Some(source),
+            Error::Advisories(source) => Some(source),
+            _ => None,
+        }
+    }
+}
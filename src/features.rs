@@ -0,0 +1,649 @@
+use crate::{
+    findings::{Category, Finding, Location, Severity},
+    manifests::{self, line_of, Metadata, Package},
+    pallets::{self, Source},
+    Analysis, Context, Error, Format,
+};
+use quote::ToTokens;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Component, Path},
+    process::Stdio,
+};
+use syn::{Attribute, Item};
+use toml_edit::ImDocument;
+
+/// Features which are expected to be propagated to all dependencies which also define them.
+const FEATURES: [&str; 3] = ["std", "runtime-benchmarks", "try-runtime"];
+
+/// The prefixes of the crates of the Polkadot SDK, whose default features (where supporting `no_std`) enable `std`.
+pub(crate) const SDK_PREFIXES: [&str; 8] = [
+    "sp-",
+    "sc-",
+    "frame-",
+    "pallet-",
+    "cumulus-",
+    "polkadot-",
+    "staging-xcm",
+    "substrate-",
+];
+
+/// The target for which runtime crates are built.
+const TARGET: &str = "wasm32-unknown-unknown";
+
+/// Crates which require `std` or the operating system, so break the WebAssembly build of the runtime or introduce
+/// non-determinism, along with the features which do so (any features when empty) and why.
+const STD_ONLY: [(&str, &[&str], &str); 10] = [
+    (
+        "chrono",
+        &["clock", "now", "std"],
+        "reads the system clock, which is non-deterministic",
+    ),
+    (
+        "rand",
+        &["std", "std_rng"],
+        "uses the operating system as a source of randomness",
+    ),
+    (
+        "rand_core",
+        &["std", "getrandom"],
+        "uses the operating system as a source of randomness",
+    ),
+    ("num_cpus", &[], "queries the operating system"),
+    ("rayon", &[], "spawns threads"),
+    ("tokio", &[], "requires the operating system"),
+    ("tempfile", &[], "accesses the file system"),
+    ("env_logger", &[], "reads the environment"),
+    (
+        "parking_lot",
+        &[],
+        "requires the synchronisation primitives of `std`",
+    ),
+    ("reqwest", &[], "performs network requests"),
+];
+
+/// A dependency of a runtime crate which requires `std` or the operating system.
+struct StdDependency {
+    name: String,
+    version: String,
+    /// Why the dependency is unsuitable for the runtime.
+    reason: String,
+    /// The packages through which the dependency is required, from the runtime crate to the dependency.
+    path: Vec<String>,
+}
+
+/// Checks that features such as `std` are propagated to the dependencies of each package, similar to
+/// [zepter](https://github.com/ggwpez/zepter).
+pub struct Features;
+
+impl Analysis for Features {
+    fn category(&self) -> Category {
+        Category::Manifests
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Checking feature propagation...");
+        }
+
+        let metadata = manifests::dependency_metadata(context)?;
+        let mut findings = Vec::new();
+        for package in metadata
+            .workspace_members
+            .iter()
+            .filter_map(|id| metadata.package(id))
+            .filter(|p| context.selected(&p.name))
+        {
+            let manifest = fs::read_to_string(&package.manifest_path).unwrap_or_default();
+            let document = ImDocument::parse(manifest.as_str()).ok();
+            let features = document
+                .as_ref()
+                .and_then(|d| d.get("features")?.as_table_like());
+            for feature in FEATURES {
+                // Only features defined by the package itself are propagated
+                let Some(enabled) = package.features.get(feature) else {
+                    continue;
+                };
+                let missing = unpropagated(
+                    feature,
+                    enabled,
+                    dependencies(&metadata, package)
+                        .into_iter()
+                        .filter(|(_, _, resolved)| resolved.features.contains_key(feature))
+                        .map(|(name, optional, _)| (name, optional)),
+                );
+                if missing.is_empty() {
+                    continue;
+                }
+
+                let line = features.and_then(|f| f.key(feature)?.span()).map_or_else(
+                    || line_of(&manifest, "[features]"),
+                    |span| manifests::line_at(&manifest, Some(span)),
+                );
+                let mut finding = Finding::new(
+                    Category::Manifests,
+                    Severity::Warning,
+                    format!(
+                        "feature '{}' is not propagated to {} dependency(s): {}",
+                        feature,
+                        missing.len(),
+                        missing
+                            .iter()
+                            .map(|(name, _)| name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                )
+                .with_code("sbp::missing_feature_propagation")
+                .with_package(&package.name)
+                .with_location(Location {
+                    file: manifests::relative(&context.root, &package.manifest_path),
+                    line,
+                    column: 1,
+                    line_end: line,
+                    column_end: 1,
+                });
+                finding.help.push(format!(
+                    "add {} to the '{}' feature",
+                    missing
+                        .iter()
+                        .map(|(_, value)| format!("\"{}\"", value))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    feature
+                ));
+                if let Some(finding) = context.config.apply(finding) {
+                    if text {
+                        finding.print(context);
+                    }
+                    findings.push(finding);
+                }
+            }
+        }
+
+        // Runtime crates (those with a `std` feature) are built for WebAssembly without default features
+        for package in metadata
+            .workspace_members
+            .iter()
+            .filter_map(|id| metadata.package(id))
+            .filter(|p| context.selected(&p.name) && p.features.contains_key("std"))
+        {
+            let manifest = fs::read_to_string(&package.manifest_path).unwrap_or_default();
+            let mut report = |code: &str, severity, message: String, help: String, line: u16| {
+                let mut finding = Finding::new(Category::Manifests, severity, message)
+                    .with_code(code)
+                    .with_package(&package.name)
+                    .with_location(Location {
+                        file: manifests::relative(&context.root, &package.manifest_path),
+                        line,
+                        column: 1,
+                        line_end: line,
+                        column_end: 1,
+                    });
+                finding.help.push(help);
+                if let Some(finding) = context.config.apply(finding) {
+                    if text {
+                        finding.print(context);
+                    }
+                    findings.push(finding);
+                }
+            };
+
+            // Polkadot SDK crates enable `std` by default, so are expected to be declared without default features
+            for dependency in package
+                .dependencies
+                .iter()
+                .filter(|d| d.kind.is_none() && SDK_PREFIXES.iter().any(|p| d.name.starts_with(p)))
+            {
+                let line = line_of(&manifest, &dependency.name);
+                if dependency.uses_default_features {
+                    report(
+                        "sbp::default_features",
+                        Severity::Error,
+                        format!(
+                            "'{}' is declared with its default features, which enable `std` within the runtime",
+                            dependency.name
+                        ),
+                        format!(
+                            "declare with `default-features = false`, adding \"{}/std\" to the 'std' feature",
+                            dependency.name
+                        ),
+                        line,
+                    );
+                } else if dependency.features.iter().any(|f| f == "std") {
+                    report(
+                        "sbp::default_features",
+                        Severity::Error,
+                        format!(
+                            "'{}' is declared with its `std` feature, which enables `std` within the runtime",
+                            dependency.name
+                        ),
+                        format!(
+                            "remove the `std` feature from the declaration, adding \"{}/std\" to the 'std' feature",
+                            dependency.name
+                        ),
+                        line,
+                    );
+                }
+            }
+
+            // Dependencies only used by tests are built into the runtime unnecessarily
+            let Some(directory) = Path::new(&package.manifest_path).parent() else {
+                continue;
+            };
+            let (used, tested) = references(&pallets::sources(directory, &context.root));
+            for dependency in package
+                .dependencies
+                .iter()
+                .filter(|d| d.kind.is_none() && !d.optional)
+            {
+                let name = dependency
+                    .rename
+                    .as_ref()
+                    .unwrap_or(&dependency.name)
+                    .replace('-', "_");
+                if tested.contains(&name) && !used.contains(&name) {
+                    report(
+                        "sbp::test_only_dependency",
+                        Severity::Warning,
+                        format!("'{}' is only used by tests", dependency.name),
+                        "declare it within [dev-dependencies] instead of [dependencies]".into(),
+                        line_of(&manifest, &dependency.name),
+                    );
+                }
+            }
+
+            for dependency in std_dependencies(context, package)? {
+                // Located at the direct dependency through which the crate is required
+                let line = line_of(
+                    &manifest,
+                    dependency.path.get(1).unwrap_or(&dependency.name),
+                );
+                report(
+                    "sbp::std_dependency",
+                    Severity::Error,
+                    format!(
+                        "'{}' ({}) is within the dependencies of the runtime, but {}",
+                        dependency.name, dependency.version, dependency.reason
+                    ),
+                    format!("required via {}", dependency.path.join(" -> ")),
+                    line,
+                );
+            }
+        }
+        if text && findings.is_empty() {
+            println!("All features are propagated");
+        }
+        Ok(findings)
+    }
+}
+
+/// Returns the dependencies (by name, along with whether each is optional) to which the feature is not propagated by
+/// the values it enables, along with the value which would propagate it.
+fn unpropagated(
+    feature: &str,
+    enabled: &[String],
+    dependencies: impl IntoIterator<Item = (String, bool)>,
+) -> Vec<(String, String)> {
+    dependencies
+        .into_iter()
+        .filter(|(name, _)| {
+            !enabled.iter().any(|e| {
+                *e == format!("{}/{}", name, feature) || *e == format!("{}?/{}", name, feature)
+            })
+        })
+        // Optional dependencies are only enabled by the feature when otherwise enabled
+        .map(|(name, optional)| {
+            let value = match optional {
+                true => format!("{}?/{}", name, feature),
+                false => format!("{}/{}", name, feature),
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+/// Returns the normal dependencies of the package, by the name with which they are referenced within features, along
+/// with whether each is optional and its resolved package.
+fn dependencies<'a>(
+    metadata: &'a Metadata,
+    package: &'a Package,
+) -> Vec<(String, bool, &'a Package)> {
+    let Some(node) = metadata
+        .resolve
+        .as_ref()
+        .and_then(|r| r.nodes.iter().find(|n| n.id == package.id))
+    else {
+        return Vec::new();
+    };
+    package
+        .dependencies
+        .iter()
+        .filter(|d| d.kind.is_none())
+        .filter_map(|dependency| {
+            let resolved = node
+                .deps
+                .iter()
+                .filter(|d| d.dep_kinds.iter().any(|k| k.kind.is_none()))
+                .filter_map(|d| metadata.package(&d.pkg))
+                .find(|p| p.name == dependency.name)?;
+            let name = dependency.rename.as_ref().unwrap_or(&dependency.name);
+            Some((name.clone(), dependency.optional, resolved))
+        })
+        .collect()
+}
+
+/// Returns the dependencies of the runtime crate which require `std` or the operating system, resolved as when building
+/// the crate for WebAssembly without default features (via `cargo tree`).
+fn std_dependencies(context: &Context, package: &Package) -> Result<Vec<StdDependency>, Error> {
+    let mut command = context.cargo("tree");
+    command
+        .args(["--package", &package.name, "--no-default-features"])
+        .args(["--target", TARGET, "--edges", "normal", "--prefix", "depth"])
+        .arg("--format={p}|{f}")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let output = context
+        .output(&mut command, "features")
+        .map_err(|e| Error::command("cargo tree", e))?;
+    if !output.status.success() {
+        return Err(Error::failed("cargo tree", &output.stderr));
+    }
+    Ok(std_only(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Returns the dependencies within the output of `cargo tree --prefix depth --format={p}|{f}` which require `std` or
+/// the operating system, given the features enabled for each.
+fn std_only(tree: &str) -> Vec<StdDependency> {
+    let mut dependencies = Vec::new();
+    let mut reported = HashSet::new();
+    // The packages through which the current line is required, by depth
+    let mut path: Vec<String> = Vec::new();
+    for line in tree.lines() {
+        // Lines are of the form `<depth><name> v<version> [(<source>)]|<features>`
+        let depth = line.chars().take_while(char::is_ascii_digit).count();
+        let (Ok(level), Some((package, features))) = (
+            line[..depth].parse::<usize>(),
+            line[depth..].split_once('|'),
+        ) else {
+            continue;
+        };
+        let mut parts = package.split_whitespace();
+        let (Some(name), Some(version)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        path.truncate(level);
+        path.push(name.to_string());
+        let features: Vec<_> = features
+            .split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .collect();
+        let reason = match name {
+            "getrandom" if !features.iter().any(|f| ["js", "custom", "wasm_js"].contains(f)) => {
+                Some(
+                    "uses the operating system as a source of randomness, which is unavailable without the `js` or \
+                     `custom` feature"
+                        .to_string(),
+                )
+            }
+            _ => STD_ONLY
+                .iter()
+                .find(|(n, requiring, _)| {
+                    *n == name
+                        && (requiring.is_empty() || features.iter().any(|f| requiring.contains(f)))
+                })
+                .map(|(_, requiring, reason)| match requiring.is_empty() {
+                    true => reason.to_string(),
+                    false => format!(
+                        "{} (via the {} feature(s))",
+                        reason,
+                        features
+                            .iter()
+                            .filter(|f| requiring.contains(f))
+                            .map(|f| format!("`{}`", f))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                }),
+        };
+        if let Some(reason) = reason {
+            if reported.insert((name.to_string(), version.to_string())) {
+                dependencies.push(StdDependency {
+                    name: name.to_string(),
+                    version: version.trim_start_matches('v').to_string(),
+                    reason,
+                    path: path.clone(),
+                });
+            }
+        }
+    }
+    dependencies
+}
+
+/// Returns the identifiers referenced within the code and the test code of the sources respectively. Test code is that
+/// within `#[cfg(test)]` items (including modules declared within other files, such as `mock.rs`) or within the
+/// `tests`, `benches` or `examples` directories.
+fn references(sources: &[Source]) -> (HashSet<String>, HashSet<String>) {
+    fn test(attributes: &[Attribute]) -> bool {
+        attributes.iter().any(|a| {
+            a.path().is_ident("cfg") && a.meta.to_token_stream().to_string().contains("test")
+        })
+    }
+    fn attributes(item: &Item) -> &[Attribute] {
+        match item {
+            Item::Const(item) => &item.attrs,
+            Item::Enum(item) => &item.attrs,
+            Item::Fn(item) => &item.attrs,
+            Item::Impl(item) => &item.attrs,
+            Item::Macro(item) => &item.attrs,
+            Item::Mod(item) => &item.attrs,
+            Item::Static(item) => &item.attrs,
+            Item::Struct(item) => &item.attrs,
+            Item::Trait(item) => &item.attrs,
+            Item::Type(item) => &item.attrs,
+            Item::Use(item) => &item.attrs,
+            _ => &[],
+        }
+    }
+    // Modules declared as test code, whose content is within other files
+    fn modules(items: &[Item], declared: &mut HashSet<String>) {
+        for item in items {
+            if let Item::Mod(module) = item {
+                match &module.content {
+                    None if test(&module.attrs) => {
+                        declared.insert(module.ident.to_string());
+                    }
+                    Some((_, items)) => modules(items, declared),
+                    None => {}
+                }
+            }
+        }
+    }
+    fn partition(items: &[Item], used: &mut HashSet<String>, tested: &mut HashSet<String>) {
+        for item in items {
+            match item {
+                _ if test(attributes(item)) => pallets::identifiers(item.to_token_stream(), tested),
+                Item::Mod(module) => {
+                    if let Some((_, content)) = &module.content {
+                        partition(content, used, tested);
+                    }
+                }
+                _ => pallets::identifiers(item.to_token_stream(), used),
+            }
+        }
+    }
+
+    let mut test_modules = HashSet::new();
+    for source in sources {
+        modules(&source.file.items, &mut test_modules);
+    }
+    let (mut used, mut tested) = (HashSet::new(), HashSet::new());
+    for source in sources {
+        let path = Path::new(&source.path);
+        let module = match path.file_stem().and_then(|s| s.to_str()) {
+            Some("mod") => path.parent().and_then(|p| p.file_name()),
+            _ => path.file_stem(),
+        };
+        let test_file = test(&source.file.attrs)
+            || module.is_some_and(|m| test_modules.contains(&*m.to_string_lossy()))
+            || path.components().any(|c| {
+                matches!(c, Component::Normal(c) if c == "tests" || c == "benches" || c == "examples")
+            });
+        match test_file {
+            true => pallets::identifiers(source.file.to_token_stream(), &mut tested),
+            false => partition(&source.file.items, &mut used, &mut tested),
+        }
+    }
+    (used, tested)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_unpropagated_features() {
+        let enabled = ["codec/std".to_string(), "serde?/std".to_string()];
+        let missing = unpropagated(
+            "std",
+            &enabled,
+            [
+                ("codec".to_string(), false),
+                ("serde".to_string(), true),
+                ("scale-info".to_string(), false),
+                ("log".to_string(), true),
+            ],
+        );
+        assert_eq!(
+            missing,
+            [
+                ("scale-info".to_string(), "scale-info/std".to_string()),
+                ("log".to_string(), "log?/std".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn propagated_features_are_accepted() {
+        // Optional dependencies may be propagated either way, where `dep/std` also enables the dependency
+        let enabled = [
+            "codec/std".to_string(),
+            "serde/std".to_string(),
+            "log?/std".to_string(),
+        ];
+        let dependencies = [
+            ("codec".to_string(), false),
+            ("serde".to_string(), true),
+            ("log".to_string(), true),
+        ];
+        assert!(unpropagated("std", &enabled, dependencies).is_empty());
+    }
+
+    #[test]
+    fn reports_std_only_dependencies() {
+        let tree = "\
+0runtime v0.1.0 (/runtime)|std
+1pallet-template v0.1.0 (/pallets/template)|
+2rand v0.8.5|getrandom,std,std_rng
+3getrandom v0.2.15|
+1chrono v0.4.38|alloc,clock
+1tokio v1.40.0|
+2num_cpus v1.16.0|
+1rand v0.8.5|getrandom,std,std_rng
+";
+        let dependencies = std_only(tree);
+        let summary: Vec<_> = dependencies
+            .iter()
+            .map(|d| (d.name.as_str(), d.version.as_str(), d.path.join(" -> ")))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("rand", "0.8.5", "runtime -> pallet-template -> rand".into()),
+                (
+                    "getrandom",
+                    "0.2.15",
+                    "runtime -> pallet-template -> rand -> getrandom".into()
+                ),
+                ("chrono", "0.4.38", "runtime -> chrono".into()),
+                ("tokio", "1.40.0", "runtime -> tokio".into()),
+                ("num_cpus", "1.16.0", "runtime -> tokio -> num_cpus".into()),
+            ]
+        );
+        assert_eq!(
+            dependencies[0].reason,
+            "uses the operating system as a source of randomness (via the `std`, `std_rng` feature(s))"
+        );
+        assert_eq!(
+            dependencies[2].reason,
+            "reads the system clock, which is non-deterministic (via the `clock` feature(s))"
+        );
+    }
+
+    #[test]
+    fn no_std_dependencies_are_accepted() {
+        let tree = "\
+0runtime v0.1.0 (/runtime)|
+1rand v0.8.5|alloc
+1getrandom v0.2.15|js
+1getrandom v0.3.1|wasm_js
+1chrono v0.4.38|alloc
+1parity-scale-codec v3.6.12|max-encoded-len
+";
+        assert!(std_only(tree).is_empty());
+    }
+
+    fn source(path: &str, code: &str) -> Source {
+        Source {
+            path: path.into(),
+            file: syn::parse_file(code).unwrap(),
+        }
+    }
+
+    #[test]
+    fn partitions_references_of_tests() {
+        let sources = [
+            source(
+                "src/lib.rs",
+                r#"
+                #[cfg(test)]
+                mod mock;
+                use codec::Encode;
+                #[cfg(test)]
+                mod tests {
+                    use hex_literal::hex;
+                }
+                "#,
+            ),
+            source("src/mock.rs", "use sp_io::TestExternalities;"),
+            source("tests/integration.rs", "use pretty_assertions::assert_eq;"),
+        ];
+        let (used, tested) = references(&sources);
+        assert!(used.contains("codec"));
+        for name in ["hex_literal", "sp_io", "pretty_assertions"] {
+            assert!(tested.contains(name) && !used.contains(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn references_outside_tests_are_used() {
+        let sources = [
+            source(
+                "src/lib.rs",
+                r#"
+                mod weights;
+                pub mod pallet {
+                    use frame_support::pallet_prelude::*;
+                }
+                "#,
+            ),
+            source("src/weights/mod.rs", "use sp_weights::Weight;"),
+        ];
+        let (used, tested) = references(&sources);
+        assert!(used.contains("frame_support") && used.contains("sp_weights"));
+        assert!(tested.is_empty());
+    }
+}
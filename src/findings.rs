@@ -0,0 +1,323 @@
+use crate::{link, Context, GroupBy};
+use clap::ValueEnum;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    fs,
+    path::Path,
+};
+
+/// The category of analysis which produced a finding.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Code,
+    Manifests,
+    Tests,
+    Benchmarks,
+    Coverage,
+    Pallets,
+    Docs,
+    Fmt,
+    Dependencies,
+    Build,
+    Migrations,
+    Runtime,
+    Node,
+}
+
+impl Category {
+    pub const ALL: [Category; 13] = [
+        Category::Code,
+        Category::Manifests,
+        Category::Tests,
+        Category::Benchmarks,
+        Category::Coverage,
+        Category::Pallets,
+        Category::Docs,
+        Category::Fmt,
+        Category::Dependencies,
+        Category::Build,
+        Category::Migrations,
+        Category::Runtime,
+        Category::Node,
+    ];
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Category::Code => "code",
+            Category::Manifests => "manifests",
+            Category::Tests => "tests",
+            Category::Benchmarks => "benchmarks",
+            Category::Coverage => "coverage",
+            Category::Pallets => "pallets",
+            Category::Docs => "docs",
+            Category::Fmt => "fmt",
+            Category::Dependencies => "dependencies",
+            Category::Build => "build",
+            Category::Migrations => "migrations",
+            Category::Runtime => "runtime",
+            Category::Node => "node",
+        })
+    }
+}
+
+/// The severity of a finding, ordered from most to least severe.
+#[derive(Clone, Copy, PartialEq, PartialOrd, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Severity::Error, Severity::Warning, Severity::Info];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "{}", self.as_str().red()),
+            Severity::Warning => write!(f, "{}", self.as_str().yellow()),
+            Severity::Info => write!(f, "{}", self.as_str().normal()),
+        }
+    }
+}
+
+/// An issue identified by an analysis.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub category: Category,
+    pub severity: Severity,
+    pub code: Option<String>,
+    pub message: String,
+    pub package: Option<String>,
+    /// The kind of target within which the finding was found, e.g. `lib`, `test` or `bench`, if applicable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(flatten)]
+    pub location: Option<Location>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub help: Vec<String>,
+}
+
+impl Finding {
+    pub fn new(category: Category, severity: Severity, message: String) -> Self {
+        Finding {
+            category,
+            severity,
+            code: None,
+            message,
+            package: None,
+            target: None,
+            location: None,
+            help: Vec::new(),
+        }
+    }
+
+    pub fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn with_package(mut self, package: &str) -> Self {
+        self.package = Some(package.into());
+        self
+    }
+
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Outputs a finding as text, followed by the surrounding source if configured.
+    pub fn print(&self, context: &Context) {
+        let root = &context.root;
+        print!(
+            "{} {} {}",
+            self.severity,
+            self.code.as_ref().map_or("".into(), |c| {
+                match self.help_uri() {
+                    Some(url) => link(c, &url).cyan(),
+                    None => c.as_str().into(),
+                }
+            }),
+            self.message,
+        );
+        // add help
+        for help in &self.help {
+            print!(" {} {}", "help:".bold(), help)
+        }
+        // Findings within targets other than those built by default, such as tests, are tagged with the target
+        let target = match self.target.as_deref() {
+            None | Some("lib" | "bin") => String::new(),
+            Some(target) => format!(" ({})", target).dimmed().to_string(),
+        };
+        match &self.location {
+            None => println!("{}", target),
+            Some(location) => {
+                let text = format!("./{}:{}:{}", location.file, location.line, location.column);
+                let path = root.join(&location.file);
+                let link = context.source_link(&text, &path, location.line, location.column);
+                println!(" at {}{}", link.cyan(), target);
+                if let Some(lines) = context.snippet {
+                    location.print_snippet(root, lines, self.severity);
+                }
+            }
+        }
+    }
+
+    /// A link to documentation describing the finding, if any.
+    pub fn help_uri(&self) -> Option<String> {
+        let code = self.code.as_ref()?;
+        match code.strip_prefix("clippy::") {
+            Some(lint) => Some(format!(
+                "https://rust-lang.github.io/rust-clippy/master/#/{}",
+                lint
+            )),
+            None if code.starts_with("rustdoc::") => Some(format!(
+                "https://doc.rust-lang.org/rustdoc/lints.html#{}",
+                &code["rustdoc::".len()..]
+            )),
+            None if code == "missing_docs" => Some(
+                "https://doc.rust-lang.org/rustc/lints/listing/allowed-by-default.html#missing-docs"
+                    .into(),
+            ),
+            None if code.starts_with("sbp::missing_") => Some("https://rust-lang.github.io/api-guidelines/documentation.html#cargotoml-includes-all-common-metadata-c-metadata".into()),
+            None => None,
+        }
+    }
+}
+
+/// The findings to be output when limited to those of the most frequent lints and/or a maximum number of findings.
+pub(crate) fn limit(findings: &[Finding], top: Option<usize>, max: Option<usize>) -> Vec<&Finding> {
+    let mut lints: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+    for finding in findings {
+        *lints.entry(finding.code.as_deref()).or_default() += 1;
+    }
+    let mut lints: Vec<_> = lints.into_iter().collect();
+    // Sorting is stable, so lints of equal frequency remain ordered by name
+    lints.sort_by(|(_, a), (_, b)| b.cmp(a));
+    lints.truncate(top.unwrap_or(usize::MAX));
+    findings
+        .iter()
+        .filter(|f| lints.iter().any(|(lint, _)| *lint == f.code.as_deref()))
+        .take(max.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Outputs the findings as text, as a tree of groups with the findings of each group nested under it.
+pub(crate) fn print_grouped(findings: &[&Finding], group_by: GroupBy, context: &Context) {
+    let mut groups: BTreeMap<(u8, String), Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        // Findings without a file, lint or package are grouped last
+        let key = match group_by {
+            GroupBy::File => finding
+                .location
+                .as_ref()
+                .map_or((1, "(no location)".into()), |l| {
+                    (0, format!("./{}", l.file))
+                }),
+            GroupBy::Lint => finding
+                .code
+                .as_ref()
+                .map_or((1, "(no lint)".into()), |c| (0, c.clone())),
+            GroupBy::Package => finding
+                .package
+                .as_ref()
+                .map_or((1, "(no package)".into()), |p| (0, p.clone())),
+            GroupBy::Severity => (
+                match finding.severity {
+                    Severity::Error => 0,
+                    Severity::Warning => 1,
+                    Severity::Info => 2,
+                },
+                finding.severity.to_string(),
+            ),
+        };
+        groups.entry(key).or_default().push(finding);
+    }
+    for ((_, group), findings) in groups {
+        println!("{} ({})", group.bold(), findings.len());
+        for finding in findings {
+            print!("  ");
+            finding.print(context);
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub file: String,
+    pub line: u16,
+    pub column: u16,
+    pub line_end: u16,
+    pub column_end: u16,
+}
+
+impl Location {
+    /// Outputs the source of the location along with the number of lines surrounding it, underlining the columns
+    /// located in the colour of the severity. Nothing is output if the source cannot be read.
+    fn print_snippet(&self, root: &Path, lines: usize, severity: Severity) {
+        let Ok(source) = fs::read_to_string(root.join(&self.file)) else {
+            return;
+        };
+        let source: Vec<&str> = source.lines().collect();
+        let (start, end) = (self.line as usize, self.line_end.max(self.line) as usize);
+        if start == 0 || start > source.len() {
+            return;
+        }
+        let end = end.min(source.len());
+        let last = (end + lines).min(source.len());
+        let width = last.to_string().len();
+        let gutter = |number: &str| format!("{:>width$} |", number, width = width).blue().bold();
+        println!("{}", gutter(""));
+        for number in start.saturating_sub(lines).max(1)..=last {
+            let text = source[number - 1];
+            match text.is_empty() {
+                true => println!("{}", gutter(&number.to_string())),
+                false => println!("{} {}", gutter(&number.to_string()), text),
+            }
+            if number < start || number > end {
+                continue;
+            }
+            // Columns are one-based character offsets, with the end column being exclusive
+            let indent = text.chars().take_while(|c| c.is_whitespace()).count() + 1;
+            let first = match number == start {
+                true => (self.column as usize).max(1),
+                false => indent,
+            };
+            let until = match number == end && self.column_end > 0 {
+                true => self.column_end as usize,
+                false => text.chars().count() + 1,
+            };
+            // Tabs are retained, so that the underline aligns with the source
+            let padding: String = text
+                .chars()
+                .chain(std::iter::repeat(' '))
+                .take(first - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let underline = "^".repeat(until.saturating_sub(first).max(1));
+            let underline = match severity {
+                Severity::Error => underline.red(),
+                Severity::Warning => underline.yellow(),
+                Severity::Info => underline.normal(),
+            };
+            println!("{} {}{}", gutter(""), padding, underline.bold());
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    clippy::{self, Message},
+    findings::{Category, Finding},
+    manifests,
+    progress::Progress,
+    Analysis, Context, Error, Format,
+};
+use colored::Colorize;
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+/// Applies the machine-applicable suggestions of clippy for the configured lints, reporting any findings which cannot
+/// be fixed automatically.
+pub struct Fix {
+    /// Prints the changes which would be made, without applying them.
+    pub dry_run: bool,
+}
+
+/// A replacement of a range of bytes within a file.
+#[derive(Clone, PartialEq)]
+struct Edit {
+    start: usize,
+    end: usize,
+    replacement: String,
+}
+
+impl Edit {
+    /// Whether the edit conflicts with another, by replacing an overlapping range or inserting at the same position.
+    fn overlaps(&self, other: &Edit) -> bool {
+        self != other
+            && ((self.start < other.end && other.start < self.end) || self.start == other.start)
+    }
+}
+
+impl Analysis for Fix {
+    fn category(&self) -> Category {
+        Category::Code
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Fixing code via clippy...");
+        }
+
+        let args = clippy::lint_args(&context.config)?;
+        let metadata = manifests::metadata(context)?;
+        let packages: Vec<_> = metadata
+            .packages
+            .iter()
+            .filter(|p| context.selected(&p.name))
+            .map(|p| p.name.as_str())
+            .collect();
+        let mut suggestions = Vec::new();
+        let progress = Progress::new(context, "clippy", Some(packages.len()));
+        let linted = clippy::lint(context, &packages, &args, &progress, &mut |m, finding| {
+            if let (Some(message), Some(finding)) =
+                (&m.message, context.config.apply(finding.clone()))
+            {
+                suggestions.push((finding, edits(context, message)));
+            }
+        });
+        drop(progress);
+        // No fixes are applied when clippy times out, as its suggestions may be incomplete
+        if let Err(e) = linted {
+            let finding = e.timed_out(Category::Code)?;
+            if text {
+                finding.print(context);
+            }
+            return Ok(vec![finding]);
+        }
+
+        // Suggestions overlapping those already accepted are left for a subsequent run, as their result is ambiguous
+        let mut files: BTreeMap<PathBuf, Vec<Edit>> = BTreeMap::new();
+        let mut remaining = Vec::new();
+        let mut fixed = 0;
+        for (mut finding, edits) in suggestions {
+            if edits.is_empty() {
+                remaining.push(finding);
+                continue;
+            }
+            let conflicts = edits.iter().any(|(file, edits)| {
+                files.get(file).is_some_and(|accepted| {
+                    edits.iter().any(|e| accepted.iter().any(|a| a.overlaps(e)))
+                })
+            });
+            if conflicts {
+                finding
+                    .help
+                    .push("overlaps another fix, run `sbp-review fix` again once applied".into());
+                remaining.push(finding);
+                continue;
+            }
+            for (file, edits) in edits {
+                let accepted = files.entry(file).or_default();
+                for edit in edits {
+                    if !accepted.contains(&edit) {
+                        accepted.push(edit);
+                    }
+                }
+            }
+            fixed += 1;
+        }
+
+        for (path, edits) in &mut files {
+            let io = |source| Error::Io {
+                path: path.clone(),
+                source,
+            };
+            let original = fs::read_to_string(path).map_err(io)?;
+            edits.sort_by_key(|e| (e.start, e.end));
+            // Edits not corresponding to the file (e.g. as it has since changed) are discarded
+            edits.retain(|e| original.get(e.start..e.end).is_some());
+            let file = manifests::relative(&context.root, &path.to_string_lossy());
+            if text {
+                print!("{}", diff(&file, &original, edits));
+            }
+            if !self.dry_run {
+                fs::write(path, apply(&original, edits)).map_err(io)?;
+            }
+        }
+        if text {
+            println!(
+                "{} {} fix(es) to {} file(s)",
+                match self.dry_run {
+                    true => "Would apply",
+                    false => "Applied",
+                },
+                fixed,
+                files.len()
+            );
+        }
+
+        let mut findings = Vec::new();
+        for finding in remaining {
+            if text {
+                finding.print(context);
+            }
+            findings.push(finding);
+        }
+        Ok(findings)
+    }
+}
+
+/// The machine-applicable edits suggested by the message, keyed by file. Only files within the workspace are edited,
+/// rather than those of any dependencies.
+fn edits(context: &Context, message: &Message) -> BTreeMap<PathBuf, Vec<Edit>> {
+    let mut edits: BTreeMap<PathBuf, Vec<Edit>> = BTreeMap::new();
+    for span in message
+        .spans
+        .iter()
+        .chain(message.children.iter().flat_map(|c| &c.spans))
+    {
+        let (Some(replacement), Some("MachineApplicable")) = (
+            &span.suggested_replacement,
+            span.suggestion_applicability.as_deref(),
+        ) else {
+            continue;
+        };
+        let file = context.root.join(&span.file_name);
+        if !file.starts_with(&context.root) {
+            continue;
+        }
+        edits.entry(file).or_default().push(Edit {
+            start: span.byte_start,
+            end: span.byte_end,
+            replacement: replacement.clone(),
+        });
+    }
+    edits
+}
+
+/// Applies the (sorted) edits to the source.
+fn apply(source: &str, edits: &[Edit]) -> String {
+    let mut result = source.to_string();
+    for edit in edits.iter().rev() {
+        result.replace_range(edit.start..edit.end, &edit.replacement);
+    }
+    result
+}
+
+/// Formats the changes of the (sorted) edits to the source as a unified diff without context lines.
+fn diff(file: &str, source: &str, edits: &[Edit]) -> String {
+    // The byte offset at which each line starts
+    let starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .filter(|i| *i == 0 || *i < source.len())
+        .collect();
+    let line = |offset: usize| starts.partition_point(|s| *s <= offset).max(1) - 1;
+
+    // Edits are grouped into hunks of the lines they span, merging those which share a line
+    let mut hunks: Vec<(usize, usize, Vec<&Edit>)> = Vec::new();
+    for edit in edits {
+        let (first, last) = (line(edit.start), line(edit.end.max(edit.start + 1) - 1));
+        match hunks.last_mut() {
+            Some((_, end, edits)) if first <= *end => {
+                *end = (*end).max(last);
+                edits.push(edit);
+            }
+            _ => hunks.push((first, last, vec![edit])),
+        }
+    }
+
+    let mut diff = format!(
+        "{}\n{}\n",
+        format!("--- a/{}", file).bold(),
+        format!("+++ b/{}", file).bold()
+    );
+    let mut delta = 0isize;
+    for (first, last, edits) in hunks {
+        let start = starts[first];
+        let end = starts.get(last + 1).copied().unwrap_or(source.len());
+        let old = &source[start..end];
+        let mut new = old.to_string();
+        for edit in edits.iter().rev() {
+            new.replace_range(edit.start - start..edit.end - start, &edit.replacement);
+        }
+        let (old_lines, new_lines) = (old.lines().count(), new.lines().count());
+        diff.push_str(
+            &format!(
+                "@@ -{},{} +{},{} @@\n",
+                first + 1,
+                old_lines,
+                // Deletions are located at the preceding line
+                first as isize + isize::from(new_lines > 0) + delta,
+                new_lines
+            )
+            .cyan()
+            .to_string(),
+        );
+        for line in old.lines() {
+            diff.push_str(&format!("{}\n", format!("-{}", line).red()));
+        }
+        for line in new.lines() {
+            diff.push_str(&format!("{}\n", format!("+{}", line).green()));
+        }
+        delta += new_lines as isize - old_lines as isize;
+    }
+    diff
+}
@@ -0,0 +1,132 @@
+use crate::{
+    findings::{Category, Finding, Location, Severity},
+    manifests, Analysis, Context, Error, Format,
+};
+use colored::Colorize;
+use std::{collections::BTreeSet, path::Path, process::Stdio};
+
+/// Checks the formatting of code, via `cargo fmt --check`.
+pub struct Fmt;
+
+impl Analysis for Fmt {
+    fn category(&self) -> Category {
+        Category::Fmt
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Checking formatting via rustfmt...");
+        }
+
+        // `cargo fmt` does not support `--workspace`/`--exclude`, so excluded packages are filtered afterwards
+        let metadata = manifests::metadata(context)?;
+        let mut command = context.cargo("fmt");
+        match context.packages.is_empty() {
+            true => {
+                command.arg("--all");
+            }
+            false => {
+                for package in &context.packages {
+                    command.arg("--package").arg(package);
+                }
+            }
+        }
+        command
+            .args(["--check", "--", "--color=never"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let output = context
+            .output(&mut command, "fmt")
+            .map_err(|e| Error::command("cargo fmt", e))?;
+        // `cargo fmt --check` exits with 1 when code is unformatted, so only fail when no diff is reported
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() && !stdout.contains("Diff in ") {
+            return Err(Error::failed("cargo fmt", &output.stderr));
+        }
+
+        let mut findings = Vec::new();
+        let mut files = BTreeSet::new();
+        let mut hunks = diff(&stdout).into_iter().peekable();
+        while let Some(hunk) = hunks.next() {
+            let package = metadata.package_of(Path::new(&hunk.file));
+            if package.is_some_and(|p| !context.selected(&p.name)) {
+                continue;
+            }
+            let file = manifests::relative(&context.root, &hunk.file);
+            let mut finding = Finding::new(
+                Category::Fmt,
+                Severity::Warning,
+                "code is not formatted".into(),
+            )
+            .with_code("sbp::unformatted")
+            .with_location(Location {
+                file: file.clone(),
+                line: hunk.line,
+                column: 1,
+                line_end: hunk.line,
+                column_end: 1,
+            });
+            if let Some(package) = package {
+                finding = finding.with_package(&package.name);
+            }
+            let Some(finding) = context.config.apply(finding) else {
+                continue;
+            };
+            if text {
+                let location = format!("./{}:{}", file, hunk.line);
+                let link = context.source_link(&location, Path::new(&hunk.file), hunk.line, 1);
+                println!("{} {}", "Diff in".bold(), link.cyan());
+                for line in &hunk.lines {
+                    match line.chars().next() {
+                        Some('-') => println!("{}", line.red()),
+                        Some('+') => println!("{}", line.green()),
+                        _ => println!("{}", line.dimmed()),
+                    }
+                }
+                if hunks.peek().is_none_or(|h| h.file != hunk.file) {
+                    println!();
+                }
+            }
+            files.insert(file);
+            findings.push(finding);
+        }
+        if text {
+            match files.len() {
+                0 => println!("All files are formatted"),
+                count => println!("{} {} unformatted", count, "file(s)".yellow()),
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// A contiguous change to the formatting of a file.
+struct Hunk {
+    file: String,
+    line: u16,
+    lines: Vec<String>,
+}
+
+/// Parses the diff output of `rustfmt --check`, where each hunk is introduced by either `Diff in <file>:<line>:` or
+/// `Diff in <file> at line <line>:`.
+fn diff(output: &str) -> Vec<Hunk> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for line in output.lines() {
+        let header = line
+            .strip_prefix("Diff in ")
+            .and_then(|l| l.strip_suffix(':'))
+            .and_then(|l| l.rsplit_once(" at line ").or_else(|| l.rsplit_once(':')))
+            .and_then(|(file, line)| Some((file, line.parse().ok()?)));
+        match (header, hunks.last_mut()) {
+            (Some((file, line)), _) => hunks.push(Hunk {
+                file: file.into(),
+                line,
+                lines: Vec::new(),
+            }),
+            (None, Some(hunk)) => hunk.lines.push(line.into()),
+            (None, None) => {}
+        }
+    }
+    hunks
+}
@@ -0,0 +1,115 @@
+//! Git operations on the repository containing the workspace, via `git`.
+
+use crate::Error;
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// Executes git within the directory with the arguments, returning its (successful) output.
+pub(crate) fn git(directory: &Path, args: &[&str]) -> Result<Output, Error> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .output()
+        .map_err(|source| match source.kind() {
+            io::ErrorKind::NotFound => Error::NotInstalled {
+                tool: "git",
+                hint: "install it via https://git-scm.com",
+            },
+            _ => Error::Command {
+                command: "git",
+                source,
+            },
+        })?;
+    match output.status.success() {
+        true => Ok(output),
+        false => Err(Error::failed("git", &output.stderr)),
+    }
+}
+
+/// Checks out the reference within a (detached) worktree at the path, replacing any existing worktree, and returns the
+/// path of the workspace root within it.
+pub(crate) fn add_worktree(root: &Path, path: &Path, reference: &str) -> Result<PathBuf, Error> {
+    // The workspace may be within a subdirectory of the repository
+    let output = git(root, &["rev-parse", "--show-prefix"])?;
+    let prefix = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    remove_worktree(root, path);
+    git(
+        root,
+        &[
+            "worktree",
+            "add",
+            "--detach",
+            &path.to_string_lossy(),
+            reference,
+        ],
+    )?;
+    Ok(path.join(prefix))
+}
+
+/// Removes the worktree at the path, if any.
+pub(crate) fn remove_worktree(root: &Path, path: &Path) {
+    let _ = git(
+        root,
+        &["worktree", "remove", "--force", &path.to_string_lossy()],
+    );
+}
+
+/// Returns the URLs of the remotes of the repository containing the directory, if any.
+pub(crate) fn remotes(directory: &Path) -> Vec<String> {
+    git(directory, &["config", "--get-regexp", r"^remote\..*\.url$"])
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| Some(line.split_once(' ')?.1.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the line ranges (inclusive) of each file changed since the reference, including uncommitted changes, keyed
+/// by the path of the file relative to the directory.
+pub(crate) fn changes(
+    directory: &Path,
+    reference: &str,
+) -> Result<BTreeMap<String, Vec<(u16, u16)>>, Error> {
+    let output = git(
+        directory,
+        &[
+            "diff",
+            "--unified=0",
+            "--no-color",
+            "--relative",
+            reference,
+            "--",
+        ],
+    )?;
+    let mut changes: BTreeMap<String, Vec<(u16, u16)>> = BTreeMap::new();
+    let mut file = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            // Deleted files are reported as `/dev/null`
+            file = path.strip_prefix("b/").map(str::to_string);
+        } else if let Some(hunk) = line.strip_prefix("@@ ") {
+            // Hunks are of the form `@@ -start,count +start,count @@`, with the count omitted when one
+            let Some(added) = hunk.split(' ').find_map(|r| r.strip_prefix('+')) else {
+                continue;
+            };
+            let (start, count) = added.split_once(',').unwrap_or((added, "1"));
+            let (Ok(start), Ok(count)) = (start.parse::<u16>(), count.parse::<u16>()) else {
+                continue;
+            };
+            if let Some(file) = file.as_ref().filter(|_| count > 0) {
+                changes
+                    .entry(file.clone())
+                    .or_default()
+                    .push((start, start.saturating_add(count - 1)));
+            }
+        }
+    }
+    Ok(changes)
+}
@@ -0,0 +1,171 @@
+//! Requests to the GitHub REST API, via `curl`.
+
+use crate::Error;
+use serde_json::Value;
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// The base URL of the GitHub REST API.
+const API: &str = "https://api.github.com";
+/// The number of items requested per page of a paginated resource, which is the maximum permitted.
+const PAGE: usize = 100;
+
+/// Requests the resource at the path (e.g. `/repos/paritytech/polkadot-sdk/releases`), returning the HTTP status code
+/// and body of the response, or `None` when GitHub could not be reached.
+pub(crate) fn get(path: &str) -> Option<(u16, Vec<u8>)> {
+    request("GET", path, None)
+}
+
+/// Posts the (JSON) body to the resource at the path, returning the HTTP status code and body of the response, or
+/// `None` when GitHub could not be reached.
+pub(crate) fn post(path: &str, body: &str) -> Option<(u16, Vec<u8>)> {
+    request("POST", path, Some(body))
+}
+
+/// Patches the resource at the path with the (JSON) body, returning the HTTP status code and body of the response, or
+/// `None` when GitHub could not be reached.
+pub(crate) fn patch(path: &str, body: &str) -> Option<(u16, Vec<u8>)> {
+    request("PATCH", path, Some(body))
+}
+
+/// Requests all pages of the (array) resource at the path, e.g. `/repos/{owner}/{repo}/pulls/{number}/comments`.
+pub(crate) fn get_all(path: &str, request: &'static str) -> Result<Vec<Value>, Error> {
+    let mut items = Vec::new();
+    for page in 1.. {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let response = get(&format!(
+            "{}{}per_page={}&page={}",
+            path, separator, PAGE, page
+        ));
+        let Value::Array(page) = json(response, request)? else {
+            break;
+        };
+        let last = page.len() < PAGE;
+        items.extend(page);
+        if last {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+/// Downloads the file at the URL (e.g. a release asset), returning `None` when it could not be downloaded.
+pub(crate) fn download(url: &str) -> Option<Vec<u8>> {
+    let output = Command::new("curl")
+        .args(["--silent", "--fail", "--location", "--max-time", "300"])
+        .arg(url)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+/// Parses the JSON body of a response, returning an error for the request if GitHub could not be reached or responded
+/// unsuccessfully.
+pub(crate) fn json(
+    response: Option<(u16, Vec<u8>)>,
+    request: &'static str,
+) -> Result<Value, Error> {
+    let Some((status, body)) = response else {
+        return Err(Error::Failed {
+            command: request,
+            stderr: "GitHub could not be reached".into(),
+        });
+    };
+    if !(200..300).contains(&status) {
+        // Errors are described by a message, e.g. `{"message": "Bad credentials", ..}`
+        let message = serde_json::from_slice::<Value>(&body)
+            .ok()
+            .and_then(|b| b.get("message").and_then(Value::as_str).map(str::to_string))
+            .unwrap_or_default();
+        return Err(Error::Failed {
+            command: request,
+            stderr: format!("{} {}", status, message).trim().into(),
+        });
+    }
+    serde_json::from_slice(&body).map_err(|source| Error::Parse {
+        command: request,
+        source,
+    })
+}
+
+/// Requests the resource at the path via the method. The token and any body are provided to curl via a configuration
+/// read from stdin, so that the token is not exposed within the arguments of the process (visible to other users via
+/// `ps`) and the body is not subject to argument size limits.
+fn request(method: &str, path: &str, body: Option<&str>) -> Option<(u16, Vec<u8>)> {
+    let mut config = String::new();
+    // Authenticated requests are subject to a considerably higher rate limit
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        config.push_str(&format!(
+            "header = \"{}\"\n",
+            quote(&format!("Authorization: Bearer {}", token))
+        ));
+    }
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--location", "--max-time", "10"])
+        .args(["--request", method])
+        .args(["--header", "Accept: application/vnd.github+json"])
+        .args(["--write-out", "\n%{http_code}"])
+        .args(["--config", "-"]);
+    if let Some(body) = body {
+        command.args(["--header", "Content-Type: application/json"]);
+        config.push_str(&format!("data-binary = \"{}\"\n", quote(body)));
+    }
+    let mut child = command
+        .arg(format!("{}{}", API, path))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // Dropping stdin once written signals the end of the configuration
+    let mut stdin = child.stdin.take()?;
+    stdin.write_all(config.as_bytes()).ok()?;
+    drop(stdin);
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // The status code is written on the final line, following the body
+    let mut body = output.stdout;
+    let position = body.iter().rposition(|b| *b == b'\n')?;
+    let status = String::from_utf8_lossy(&body[position + 1..])
+        .trim()
+        .parse()
+        .ok()?;
+    body.truncate(position);
+    Some((status, body))
+}
+
+/// Quotes the value for use within a curl configuration, escaping backslashes, quotes and line breaks.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_escapes_configuration_values() {
+        assert_eq!(quote("Bearer abc"), "Bearer abc");
+        assert_eq!(
+            quote("{\"body\": \"a\\b\nc\"}\r\t"),
+            "{\\\"body\\\": \\\"a\\\\b\\nc\\\"}\\r\\t"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! A local store of the findings of each run, used to report whether findings are trending up or down over time.
+
+use crate::{git, Context, Error, Finding, Format, Severity};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The directory of the store, relative to the workspace root.
+const DIRECTORY: &str = ".sbp-review";
+/// The file of the store, containing a record per line.
+const FILE: &str = "history.jsonl";
+
+/// The findings of a run.
+#[derive(Serialize, Deserialize)]
+pub struct Record {
+    /// The time of the run, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The git commit analysed, if any.
+    pub commit: Option<String>,
+    /// The subcommand executed, e.g. `code`.
+    pub command: String,
+    /// The number of findings of each severity, keyed by category.
+    pub counts: BTreeMap<String, BTreeMap<String, usize>>,
+    pub findings: Vec<Finding>,
+}
+
+impl Record {
+    /// The total number of findings of the severity, across all categories.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.counts
+            .values()
+            .filter_map(|counts| counts.get(severity.as_str()))
+            .sum()
+    }
+}
+
+/// Appends a record of the findings of the run of the subcommand to the store.
+pub fn record(context: &Context, command: &str, findings: &[Finding]) -> Result<(), Error> {
+    let mut counts: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    for finding in findings {
+        *counts
+            .entry(finding.category.to_string())
+            .or_default()
+            .entry(finding.severity.as_str().into())
+            .or_default() += 1;
+    }
+    let record = Record {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        commit: git::git(&context.root, &["rev-parse", "HEAD"])
+            .ok()
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string()),
+        command: command.into(),
+        counts,
+        findings: findings.to_vec(),
+    };
+
+    let directory = context.root.join(DIRECTORY);
+    let path = directory.join(FILE);
+    let io = |source| Error::Io {
+        path: path.clone(),
+        source,
+    };
+    fs::create_dir_all(&directory).map_err(io)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(io)?;
+    let line = serde_json::to_string(&record).expect("findings are serializable");
+    writeln!(file, "{}", line).map_err(io)
+}
+
+/// Loads the records of the store, in the order in which they were recorded.
+pub fn load(root: &Path) -> Result<Vec<Record>, Error> {
+    let path = path(root);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => return Err(Error::Io { path, source }),
+    };
+    // Lines which cannot be parsed (e.g. of a run which was interrupted) are skipped
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// The path of the store within the workspace.
+fn path(root: &Path) -> PathBuf {
+    root.join(DIRECTORY).join(FILE)
+}
+
+/// Prints the latest records of each subcommand, along with whether the number of errors and warnings is trending up or
+/// down.
+pub fn print(context: &Context, limit: usize) -> Result<(), Error> {
+    let records = load(&context.root)?;
+    let mut commands: BTreeMap<&str, Vec<&Record>> = BTreeMap::new();
+    for record in &records {
+        commands.entry(&record.command).or_default().push(record);
+    }
+    for records in commands.values_mut() {
+        let skip = records.len().saturating_sub(limit);
+        records.drain(..skip);
+    }
+
+    if context.format != Format::Text {
+        println!("{}", serde_json::to_string_pretty(&commands).unwrap());
+        return Ok(());
+    }
+    if records.is_empty() {
+        println!("No runs recorded within {}", path(&context.root).display());
+        return Ok(());
+    }
+    println!("{}", "History".bold());
+    for (command, records) in &commands {
+        println!("  {}", command.bold());
+        let mut previous: Option<&Record> = None;
+        for record in records {
+            let count = |severity| {
+                let count = record.count(severity);
+                let trend = previous.map_or(String::new(), |p| trend(p.count(severity), count));
+                format!("{} {}(s){}", count, severity, trend)
+            };
+            println!(
+                "    {}  {}  {}, {}",
+                date(record.timestamp),
+                record
+                    .commit
+                    .as_deref()
+                    .map_or("-------", |c| &c[..c.len().min(7)]),
+                count(Severity::Error),
+                count(Severity::Warning)
+            );
+            previous = Some(record);
+        }
+        if let (Some(first), Some(last)) = (records.first(), records.last()) {
+            if records.len() > 1 {
+                println!(
+                    "    trend: errors{}, warnings{}",
+                    trend_or_steady(first.count(Severity::Error), last.count(Severity::Error)),
+                    trend_or_steady(
+                        first.count(Severity::Warning),
+                        last.count(Severity::Warning)
+                    )
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Formats the change between two counts, e.g. ` ↑2` (red) or ` ↓3` (green), or nothing when unchanged.
+fn trend(previous: usize, current: usize) -> String {
+    match current.cmp(&previous) {
+        std::cmp::Ordering::Greater => format!(" ↑{}", current - previous).red().to_string(),
+        std::cmp::Ordering::Less => format!(" ↓{}", previous - current).green().to_string(),
+        std::cmp::Ordering::Equal => String::new(),
+    }
+}
+
+/// Formats the change between two counts, or ` steady` when unchanged.
+fn trend_or_steady(previous: usize, current: usize) -> String {
+    match trend(previous, current) {
+        trend if trend.is_empty() => " steady".into(),
+        trend => trend,
+    }
+}
+
+/// Formats the timestamp as a UTC date and time, e.g. `2024-01-01 12:00`.
+fn date(timestamp: u64) -> String {
+    let (year, month, day) = civil(timestamp);
+    let seconds = timestamp % 86_400;
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60
+    )
+}
+
+/// Converts the timestamp (in seconds since the Unix epoch) to a UTC civil date (proleptic Gregorian calendar),
+/// returning its year, month and day.
+pub(crate) fn civil(timestamp: u64) -> (i64, i64, i64) {
+    let z = (timestamp / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
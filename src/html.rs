@@ -0,0 +1,154 @@
+use crate::{
+    findings::{Finding, Location, Severity},
+    score::Score,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    fs,
+    path::Path,
+};
+
+/// The number of source lines shown either side of a finding.
+const CONTEXT: u16 = 2;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+    summary{font-size:1.2em;font-weight:bold;cursor:pointer}\
+    .finding{margin:.8em 0 .8em 1.5em}\
+    .error .severity{color:#c00}.warning .severity{color:#b80}.info .severity{color:#666}\
+    .help{color:#555;margin-left:1em}.location{font-family:monospace;color:#06c}\
+    pre{background:#f6f8fa;padding:.5em;overflow-x:auto}pre .highlight{background:#fff3b0}\
+    .score td,.score th{padding:.2em 1em;text-align:left}";
+
+const SCRIPT: &str =
+    "document.querySelectorAll('.filters input').forEach(i=>i.addEventListener('change',()=>\
+    document.querySelectorAll('.finding.'+i.dataset.severity).forEach(f=>f.hidden=!i.checked)));";
+
+/// Renders the findings as a single-file HTML report, with a collapsible section per package, preceded by the score of
+/// a review.
+pub fn render(findings: &[Finding], score: Option<&Score>, root: &Path) -> String {
+    let mut packages: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        packages
+            .entry(finding.package.as_deref().unwrap_or("workspace"))
+            .or_default()
+            .push(finding);
+    }
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>SBP Review</title>\n\
+        <style>{}</style>\n</head>\n<body>\n<h1>SBP Review</h1>\n",
+        STYLE
+    );
+    if let Some(score) = score {
+        let _ = write!(
+            html,
+            "<h2>Score: {}</h2>\n<table class=\"score\">\n<tr><th>Category</th><th>Score</th></tr>\n",
+            score
+        );
+        for (category, category_score) in &score.categories {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                category, category_score
+            );
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("<div class=\"filters\">Show:");
+    for severity in Severity::ALL {
+        let count = findings.iter().filter(|f| f.severity == severity).count();
+        let _ = write!(
+            html,
+            " <label><input type=\"checkbox\" data-severity=\"{0}\" checked> {0} ({1})</label>",
+            severity.as_str(),
+            count
+        );
+    }
+    html.push_str("</div>\n");
+
+    let mut sources = HashMap::new();
+    for (package, findings) in packages {
+        let _ = writeln!(
+            html,
+            "<details open>\n<summary>{} ({})</summary>",
+            escape(package),
+            findings.len()
+        );
+        for finding in findings {
+            let _ = write!(
+                html,
+                "<div class=\"finding {0}\"><div><span class=\"severity\">{0}</span> ",
+                finding.severity.as_str()
+            );
+            if let Some(code) = &finding.code {
+                let _ = match finding.help_uri() {
+                    Some(url) => write!(
+                        html,
+                        "<a href=\"{}\"><code>{}</code></a> ",
+                        escape(&url),
+                        escape(code)
+                    ),
+                    None => write!(html, "<code>{}</code> ", escape(code)),
+                };
+            }
+            let _ = write!(html, "{}</div>", escape(&finding.message));
+            for help in &finding.help {
+                let _ = write!(html, "<div class=\"help\">help: {}</div>", escape(help));
+            }
+            if let Some(location) = &finding.location {
+                let _ = write!(
+                    html,
+                    "<div class=\"location\">{}:{}:{}</div>",
+                    escape(&location.file),
+                    location.line,
+                    location.column
+                );
+                let source = sources
+                    .entry(location.file.as_str())
+                    .or_insert_with(|| fs::read_to_string(root.join(&location.file)).ok());
+                if let Some(source) = source {
+                    html.push_str(&snippet(source, location));
+                }
+            }
+            html.push_str("</div>\n");
+        }
+        html.push_str("</details>\n");
+    }
+    let _ = write!(html, "<script>{}</script>\n</body>\n</html>\n", SCRIPT);
+    html
+}
+
+/// Renders the source lines surrounding the location, highlighting the lines it spans.
+fn snippet(source: &str, location: &Location) -> String {
+    let first = location.line.saturating_sub(CONTEXT).max(1);
+    let last = location.line_end.max(location.line).saturating_add(CONTEXT);
+    let mut snippet = String::from("<pre>");
+    for (number, line) in (1..).zip(source.lines()) {
+        if number < first || number > last {
+            continue;
+        }
+        let highlight = number >= location.line && number <= location.line_end;
+        let _ = writeln!(
+            snippet,
+            "<span{}>{:>5} | {}</span>",
+            if highlight {
+                " class=\"highlight\""
+            } else {
+                ""
+            },
+            number,
+            escape(line)
+        );
+    }
+    snippet.push_str("</pre>");
+    snippet
+}
+
+/// Escapes the text for inclusion within HTML/XML content or attribute values.
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
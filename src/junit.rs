@@ -0,0 +1,98 @@
+use crate::{
+    html::escape,
+    score::Score,
+    tests::{Outcome, Suite},
+};
+use std::fmt::Write;
+
+/// Renders the test results as a JUnit XML report, with a test suite per test target.
+pub fn render(suites: &[Suite]) -> String {
+    let count = |outcome: fn(&Outcome) -> bool| {
+        suites
+            .iter()
+            .flat_map(|s| &s.cases)
+            .filter(|c| outcome(&c.outcome))
+            .count()
+    };
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <testsuites name=\"sbp-review\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+        count(|_| true),
+        count(|o| matches!(o, Outcome::Failed(_))),
+        count(|o| matches!(o, Outcome::Ignored)),
+        suites.iter().map(|s| s.time).sum::<f64>()
+    );
+    for suite in suites {
+        let failures = suite
+            .cases
+            .iter()
+            .filter(|c| matches!(c.outcome, Outcome::Failed(_)))
+            .count();
+        let skipped = suite
+            .cases
+            .iter()
+            .filter(|c| matches!(c.outcome, Outcome::Ignored))
+            .count();
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+            escape(&suite.name),
+            suite.cases.len(),
+            failures,
+            skipped,
+            suite.time
+        );
+        for case in &suite.cases {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape(&case.name),
+                escape(&suite.name),
+                case.time
+            );
+            match &case.outcome {
+                Outcome::Passed => xml.push_str("/>\n"),
+                Outcome::Ignored => xml.push_str(">\n      <skipped/>\n    </testcase>\n"),
+                Outcome::Failed(output) => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n      <failure message=\"test failed\">{}</failure>\n    </testcase>",
+                        escape(output.trim())
+                    );
+                }
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Adds the score of a review to a JUnit XML report, as properties of each test suite.
+pub fn with_score(xml: &str, score: &Score) -> String {
+    let mut properties = String::from("    <properties>\n");
+    let mut property = |name: &str, value: &str| {
+        let _ = writeln!(
+            properties,
+            "      <property name=\"{}\" value=\"{}\"/>",
+            escape(name),
+            escape(value)
+        );
+    };
+    property("score", &score.overall.to_string());
+    property("grade", &score.grade().to_string());
+    for (category, category_score) in &score.categories {
+        property(&format!("score.{}", category), &category_score.to_string());
+    }
+    properties.push_str("    </properties>\n");
+
+    let mut scored = String::new();
+    for line in xml.lines() {
+        scored.push_str(line);
+        scored.push('\n');
+        if line.trim_start().starts_with("<testsuite ") {
+            scored.push_str(&properties);
+        }
+    }
+    scored
+}
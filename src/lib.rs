@@ -0,0 +1,309 @@
+//! Tooling to assist with Substrate Builders Program (SBP) reviews.
+//!
+//! Each analysis implements [`Analysis`], producing [`Finding`]s for the workspace described by a [`Context`].
+
+use crate::config::Config;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::{
+    env,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+use terminal_link::Link;
+
+pub use error::Error;
+pub use findings::{Category, Finding, Location, Severity};
+
+pub mod audit;
+pub mod benchmarks;
+pub mod build;
+pub mod cache;
+pub mod chainspec;
+pub mod checkstyle;
+pub mod clippy;
+pub mod compare;
+pub mod config;
+pub mod coverage;
+mod deprecated;
+pub mod docs;
+pub mod duplicates;
+mod error;
+pub mod features;
+pub mod findings;
+pub mod fix;
+pub mod fmt;
+mod git;
+mod github;
+pub mod history;
+pub mod html;
+pub mod junit;
+pub mod licenses;
+mod lockfile;
+pub mod manifests;
+pub mod markdown;
+pub mod metadata;
+pub mod migrations;
+pub mod node;
+pub mod pallets;
+mod progress;
+pub mod publish;
+pub mod publish_check;
+mod registry;
+mod releases;
+pub mod runtime;
+pub mod sarif;
+pub mod sbom;
+pub mod score;
+pub mod spdx;
+pub mod srtool;
+pub mod tests;
+pub mod tui;
+pub mod unsafe_code;
+pub mod update;
+pub mod vet;
+mod watchdog;
+pub mod weights;
+pub mod xcm;
+
+/// An analysis of a workspace, producing findings.
+pub trait Analysis {
+    /// The category of findings produced by the analysis.
+    fn category(&self) -> Category;
+
+    /// Executes the analysis.
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error>;
+}
+
+#[derive(Clone, Copy, Default, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    /// Coloured, human-readable text.
+    Text,
+    /// Structured JSON findings.
+    Json,
+    /// SARIF 2.1.0, for GitHub code scanning.
+    Sarif,
+    /// A Markdown report, for pasting into review issues.
+    Markdown,
+    /// A self-contained HTML report, including source snippets.
+    Html,
+    /// Checkstyle XML, for reviewdog, Jenkins and other aggregators.
+    Checkstyle,
+    /// A CycloneDX software bill of materials, output by `sbom`.
+    Cyclonedx,
+    /// An SPDX software bill of materials, output by `sbom`.
+    Spdx,
+}
+
+/// How findings are grouped when output as text.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum GroupBy {
+    /// By the file in which they are located.
+    File,
+    /// By lint (or finding code).
+    Lint,
+    /// By package.
+    Package,
+    /// By severity, most severe first.
+    Severity,
+}
+
+/// When coloured output and hyperlinks are used.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Color {
+    #[default]
+    /// Only when stdout is a terminal and `NO_COLOR` is not set.
+    Auto,
+    /// Always, including when output is piped or redirected.
+    Always,
+    /// Never.
+    Never,
+}
+
+impl Color {
+    /// Applies the choice to all subsequent output.
+    pub fn apply(self) {
+        let enabled = match self {
+            Color::Auto => {
+                env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && std::io::stdout().is_terminal()
+            }
+            Color::Always => true,
+            Color::Never => false,
+        };
+        colored::control::set_override(enabled);
+    }
+}
+
+/// Formats a (terminal) hyperlink, falling back to the plain text when coloured output is disabled or the terminal does
+/// not support hyperlinks.
+pub(crate) fn link(text: &str, url: &str) -> String {
+    match colored::control::SHOULD_COLORIZE.should_colorize()
+        && env::var_os("TERM").is_none_or(|term| term != "dumb")
+    {
+        true => Link::new(text, url).to_string(),
+        false => text.into(),
+    }
+}
+
+/// The context of an analysis run.
+pub struct Context {
+    pub format: Format,
+    pub config: Config,
+    pub manifest_path: Option<PathBuf>,
+    pub packages: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Whether cached results may be used for unchanged packages.
+    pub cache: bool,
+    /// The path to which a JUnit XML report of test results is written, if any.
+    pub junit: Option<PathBuf>,
+    /// Whether all targets (including tests, examples and benchmarks) are linted or tested.
+    pub all_targets: bool,
+    /// Whether test targets are linted, in addition to the default targets.
+    pub tests: bool,
+    /// Whether benchmark targets are linted, in addition to the default targets.
+    pub benches: bool,
+    /// Features enabled when linting, such as `runtime-benchmarks`, in addition to the default features.
+    pub features: Vec<String>,
+    /// Whether all features are enabled when linting.
+    pub all_features: bool,
+    /// Whether documentation tests are executed, in addition to any other targets.
+    pub doc: bool,
+    /// Whether checks requiring network access are skipped.
+    pub offline: bool,
+    /// Whether the minimum supported rust version of each package is verified by compiling with the toolchain.
+    pub verify_msrv: bool,
+    /// Whether the URLs declared within manifests are verified to respond, along with the repository matching the git
+    /// remote of the workspace.
+    pub online: bool,
+    /// How findings are grouped when output as text, rather than being output as a flat list as they are found.
+    pub group_by: Option<GroupBy>,
+    /// The maximum number of findings output as text, with any others hidden.
+    pub max_results: Option<usize>,
+    /// The number of most frequent lints whose findings are output as text, with those of any others hidden.
+    pub top: Option<usize>,
+    /// The number of lines of source output around the location of each finding output as text, if any.
+    pub snippet: Option<usize>,
+    /// The root directory of the workspace being analysed.
+    pub root: PathBuf,
+}
+
+impl Context {
+    /// Creates a context for the workspace containing the manifest (or current directory), loading any configuration
+    /// file at its root.
+    pub fn new(manifest_path: Option<PathBuf>) -> Result<Self, Error> {
+        let root = workspace_root(manifest_path.as_deref())?;
+        let config = config::load(&root)?;
+        Ok(Context {
+            format: config.output.format.unwrap_or_default(),
+            config,
+            manifest_path,
+            packages: Vec::new(),
+            exclude: Vec::new(),
+            cache: true,
+            junit: None,
+            all_targets: false,
+            tests: false,
+            benches: false,
+            features: Vec::new(),
+            all_features: false,
+            doc: false,
+            offline: false,
+            verify_msrv: false,
+            online: false,
+            group_by: None,
+            max_results: None,
+            top: None,
+            snippet: None,
+            root,
+        })
+    }
+
+    /// Formats a link opening the source location within the configured editor, falling back to the plain text when
+    /// links are disabled.
+    pub(crate) fn source_link(&self, text: &str, path: &Path, line: u16, column: u16) -> String {
+        match self.config.output.editor() {
+            Ok(Some(template)) => {
+                let url = template
+                    .replace("{path}", &path.display().to_string())
+                    .replace("{line}", &line.to_string())
+                    .replace("{col}", &column.to_string());
+                link(text, &url)
+            }
+            _ => text.into(),
+        }
+    }
+
+    /// Restricts findings to those on lines changed since the git reference, so that only changes are reviewed.
+    pub fn diff(&mut self, reference: &str) -> Result<(), Error> {
+        self.config.paths.changed = Some(git::changes(&self.root, reference)?);
+        Ok(())
+    }
+
+    /// Creates a cargo command for the subcommand, targeting the workspace being analysed.
+    pub(crate) fn cargo(&self, subcommand: &str) -> Command {
+        let mut command = Command::new("cargo");
+        command.arg(subcommand);
+        if let Some(manifest_path) = &self.manifest_path {
+            command.arg("--manifest-path").arg(manifest_path);
+        }
+        command
+    }
+
+    /// Creates a cargo command for the subcommand, targeting the selected packages of the workspace being analysed.
+    pub(crate) fn cargo_packages(&self, subcommand: &str) -> Command {
+        let mut command = self.cargo(subcommand);
+        for package in &self.packages {
+            command.arg("--package").arg(package);
+        }
+        if !self.exclude.is_empty() {
+            command.arg("--workspace");
+            for package in &self.exclude {
+                command.arg("--exclude").arg(package);
+            }
+        }
+        command
+    }
+
+    /// Executes the command spawned by the analysis of the subcommand (e.g. `docs`) to completion, terminating it once
+    /// the timeout of the subcommand elapses. Only output which is piped is captured.
+    pub(crate) fn output(&self, command: &mut Command, subcommand: &str) -> io::Result<Output> {
+        watchdog::output(command, self.config.timeout(subcommand))
+    }
+
+    /// Whether the package has been selected for analysis.
+    pub fn selected(&self, package: &str) -> bool {
+        (self.packages.is_empty() || self.packages.iter().any(|p| p == package))
+            && !self.exclude.iter().any(|p| p == package)
+    }
+
+    /// Child process output is redirected to stderr when emitting structured output, to keep stdout parsable.
+    pub(crate) fn stdout(&self) -> Stdio {
+        match self.format {
+            Format::Text => Stdio::inherit(),
+            _ => std::io::stderr().into(),
+        }
+    }
+}
+
+/// Locates the root directory of the workspace containing the manifest (or current directory).
+fn workspace_root(manifest_path: Option<&Path>) -> Result<PathBuf, Error> {
+    let mut command = Command::new("cargo");
+    command.args(["locate-project", "--workspace", "--message-format=plain"]);
+    if let Some(manifest_path) = manifest_path {
+        command.arg("--manifest-path").arg(manifest_path);
+    }
+    let output = command
+        .output()
+        .map_err(|e| Error::command("cargo locate-project", e))?;
+    if !output.status.success() {
+        return Err(Error::failed("cargo locate-project", &output.stderr));
+    }
+    let manifest = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(manifest
+        .parent()
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf))
+}
@@ -0,0 +1,292 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    manifests::{self, Package},
+    Analysis, Context, Error, Format,
+};
+use ::spdx::{Expression, LicenseItem, LicenseReq, ParseMode};
+use std::collections::BTreeMap;
+
+/// Checks the licenses of the dependencies of the workspace against the configured license policy.
+pub struct Licenses;
+
+impl Analysis for Licenses {
+    fn category(&self) -> Category {
+        Category::Dependencies
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Checking dependency licenses...");
+        }
+
+        // Collect the (normal) dependencies of the selected workspace members, along with the members using them
+        let metadata = manifests::dependency_metadata(context)?;
+        let mut dependencies: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for member in metadata
+            .workspace_members
+            .iter()
+            .filter_map(|id| metadata.package(id))
+            .filter(|p| context.selected(&p.name))
+        {
+            for dependency in metadata.normal_dependencies(&member.id) {
+                if !metadata.workspace_members.iter().any(|m| m == dependency) {
+                    dependencies
+                        .entry(dependency)
+                        .or_default()
+                        .push(&member.name);
+                }
+            }
+        }
+
+        let policy = Policy::new(&context.config.licenses)?;
+        let mut findings = Vec::new();
+        for (dependency, members) in dependencies {
+            let Some(package) = metadata.package(dependency) else {
+                continue;
+            };
+            let Some((severity, code, reason)) = check(package, &policy) else {
+                continue;
+            };
+            let mut finding = Finding::new(
+                Category::Dependencies,
+                severity,
+                format!("{} {} {}", package.name, package.version, reason),
+            )
+            .with_code(code);
+            finding.help.push(format!("used by {}", members.join(", ")));
+            if let Some(finding) = context.config.apply(finding) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        }
+        if text && findings.is_empty() {
+            println!("All dependency licenses are allowed");
+        }
+        Ok(findings)
+    }
+}
+
+/// The license policy, with its allowed and denied licenses parsed as SPDX license requirements.
+struct Policy {
+    allow: Vec<LicenseReq>,
+    deny: Vec<LicenseReq>,
+}
+
+impl Policy {
+    fn new(licenses: &crate::config::Licenses) -> Result<Self, Error> {
+        let parse = |licenses: &[String]| -> Result<Vec<LicenseReq>, Error> {
+            let mut requirements = Vec::new();
+            for license in licenses {
+                let expression = Expression::parse_mode(license, ParseMode::LAX).map_err(|e| {
+                    Error::Config(format!(
+                        "invalid license '{}' within [licenses]: {}",
+                        license, e.reason
+                    ))
+                })?;
+                requirements.extend(expression.requirements().map(|r| r.req.clone()));
+            }
+            Ok(requirements)
+        };
+        Ok(Policy {
+            allow: parse(&licenses.allow)?,
+            deny: parse(&licenses.deny)?,
+        })
+    }
+
+    fn allowed(&self, license: &LicenseReq) -> bool {
+        self.allow.iter().any(|a| matches(a, license)) && !self.denied(license)
+    }
+
+    fn denied(&self, license: &LicenseReq) -> bool {
+        self.deny.iter().any(|d| matches(d, license))
+    }
+}
+
+/// Whether a license of a dependency matches a license of the policy. As exceptions only grant additional permissions,
+/// a license with an exception matches either the license alone or the license with the same exception (e.g.
+/// `GPL-3.0-only WITH Classpath-exception-2.0`). Versions are not distinguished, so `Apache-2.0+` matches `Apache-2.0`.
+fn matches(policy: &LicenseReq, license: &LicenseReq) -> bool {
+    let matches = match (&policy.license, &license.license) {
+        (LicenseItem::Spdx { id: p, .. }, LicenseItem::Spdx { id: l, .. }) => p == l,
+        (p, l) => p == l,
+    };
+    matches && (policy.exception.is_none() || policy.exception == license.exception)
+}
+
+/// Checks the license of the package against the policy, returning the severity, code and reason of any violation.
+fn check(package: &Package, policy: &Policy) -> Option<(Severity, &'static str, String)> {
+    match &package.license {
+        Some(license) => evaluate(license, policy),
+        None => Some(match &package.license_file {
+            Some(_) => (
+                Severity::Warning,
+                "sbp::unknown_license",
+                "has a custom license file".into(),
+            ),
+            None => (
+                Severity::Warning,
+                "sbp::unknown_license",
+                "has no license".into(),
+            ),
+        }),
+    }
+}
+
+/// Evaluates a license expression against the policy, returning the severity, code and reason of any violation. The
+/// expression is parsed leniently, as older crates commonly use the legacy `/` separator.
+fn evaluate(license: &str, policy: &Policy) -> Option<(Severity, &'static str, String)> {
+    let expression = match Expression::parse_mode(license, ParseMode::LAX) {
+        Ok(expression) => expression,
+        Err(e) => {
+            return Some((
+                Severity::Warning,
+                "sbp::unknown_license",
+                format!(
+                    "has an invalid license expression '{}': {}",
+                    license, e.reason
+                ),
+            ))
+        }
+    };
+    if expression.evaluate(|l| policy.allowed(l)) {
+        return None;
+    }
+    let licenses: Vec<_> = expression.requirements().map(|r| &r.req).collect();
+    if licenses.iter().any(|l| policy.denied(l)) {
+        Some((
+            Severity::Error,
+            "sbp::denied_license",
+            format!("is licensed under a denied license ({})", license),
+        ))
+    } else if licenses.iter().any(|l| copyleft(&l.license)) {
+        Some((
+            Severity::Warning,
+            "sbp::copyleft_license",
+            format!("is licensed under a copyleft license ({})", license),
+        ))
+    } else {
+        Some((
+            Severity::Warning,
+            "sbp::unknown_license",
+            format!(
+                "is licensed under a license which is not allowed ({})",
+                license
+            ),
+        ))
+    }
+}
+
+/// Whether the license is a (strong or weak) copyleft license.
+fn copyleft(license: &LicenseItem) -> bool {
+    const COPYLEFT: [&str; 10] = [
+        "GPL", "LGPL", "AGPL", "MPL", "EPL", "EUPL", "CDDL", "OSL", "SSPL", "CC-BY-SA",
+    ];
+    match license {
+        LicenseItem::Spdx { id, .. } => COPYLEFT.iter().any(|c| id.name.starts_with(c)),
+        LicenseItem::Other { .. } => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allow: &[&str], deny: &[&str]) -> Policy {
+        Policy::new(&crate::config::Licenses {
+            allow: allow.iter().map(|l| l.to_string()).collect(),
+            deny: deny.iter().map(|l| l.to_string()).collect(),
+        })
+        .unwrap()
+    }
+
+    fn code(license: &str, policy: &Policy) -> Option<&'static str> {
+        evaluate(license, policy).map(|(_, code, _)| code)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let policy = policy(&["MIT"], &[]);
+        assert_eq!(code("MIT OR GPL-2.0-only AND LGPL-2.1-only", &policy), None);
+        assert_eq!(code("GPL-2.0-only AND LGPL-2.1-only OR MIT", &policy), None);
+        assert_eq!(
+            code("MIT AND Apache-2.0 OR GPL-2.0-only", &policy),
+            Some("sbp::copyleft_license")
+        );
+    }
+
+    #[test]
+    fn parentheses_group_expressions() {
+        let policy = policy(&["MIT", "Apache-2.0"], &[]);
+        assert_eq!(code("MIT AND (Apache-2.0 OR GPL-2.0-only)", &policy), None);
+        assert_eq!(
+            code("(MIT OR GPL-2.0-only) AND LGPL-2.1-only", &policy),
+            Some("sbp::copyleft_license")
+        );
+        assert_eq!(code("((MIT))", &policy), None);
+    }
+
+    #[test]
+    fn exceptions_match_license_or_license_with_exception() {
+        let policy = Policy::new(&crate::config::Licenses::default()).unwrap();
+        assert_eq!(code("Apache-2.0 WITH LLVM-exception", &policy), None);
+        assert_eq!(
+            code("GPL-3.0-only WITH Classpath-exception-2.0", &policy),
+            None
+        );
+        assert_eq!(
+            code("GPL-3.0-or-later WITH Classpath-exception-2.0", &policy),
+            None
+        );
+        assert_eq!(code("GPL-3.0-only", &policy), Some("sbp::copyleft_license"));
+        assert_eq!(
+            code("GPL-2.0-only WITH Classpath-exception-2.0", &policy),
+            Some("sbp::copyleft_license")
+        );
+    }
+
+    #[test]
+    fn or_later_matches_license() {
+        let policy = policy(&["Apache-2.0"], &[]);
+        assert_eq!(code("Apache-2.0+", &policy), None);
+        assert_eq!(code("GPL-2.0+", &policy), Some("sbp::copyleft_license"));
+    }
+
+    #[test]
+    fn denied_licenses_take_precedence() {
+        let policy = policy(&["MIT", "Apache-2.0"], &["MIT"]);
+        assert_eq!(code("MIT OR Apache-2.0", &policy), None);
+        assert_eq!(code("MIT", &policy), Some("sbp::denied_license"));
+        assert_eq!(
+            code("MIT AND Apache-2.0", &policy),
+            Some("sbp::denied_license")
+        );
+        assert!(matches!(
+            evaluate("MIT", &policy),
+            Some((Severity::Error, ..))
+        ));
+    }
+
+    #[test]
+    fn reports_unknown_and_invalid_licenses() {
+        let policy = policy(&["MIT"], &[]);
+        assert_eq!(code("MIT/Apache-2.0", &policy), None);
+        assert_eq!(code("ISC", &policy), Some("sbp::unknown_license"));
+        assert_eq!(
+            code("LicenseRef-Proprietary", &policy),
+            Some("sbp::unknown_license")
+        );
+        assert_eq!(code("MIT OR", &policy), Some("sbp::unknown_license"));
+    }
+
+    #[test]
+    fn rejects_invalid_policy() {
+        let licenses = crate::config::Licenses {
+            allow: vec!["MIT AND".into()],
+            deny: Vec::new(),
+        };
+        assert!(Policy::new(&licenses).is_err());
+    }
+}
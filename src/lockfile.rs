@@ -0,0 +1,172 @@
+//! Reading of `Cargo.lock` files, resolving the dependencies of each locked package.
+
+use crate::{manifests, Error};
+use std::{fs, io, path::Path};
+use toml_edit::{ImDocument, Item, Table};
+
+/// The packages locked within a `Cargo.lock` file.
+pub(crate) struct Lockfile {
+    pub(crate) packages: Vec<Package>,
+}
+
+pub(crate) struct Package {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    /// The source of the package, or `None` for local (path) packages.
+    pub(crate) source: Option<String>,
+    /// The SHA-256 checksum of the packaged crate, for packages from a registry.
+    pub(crate) checksum: Option<String>,
+    /// The indices of the dependencies of the package.
+    pub(crate) dependencies: Vec<usize>,
+    /// The (one-based) line on which the package is defined.
+    pub(crate) line: usize,
+}
+
+impl Lockfile {
+    /// Reads and parses the lock file.
+    pub(crate) fn read(path: &Path) -> Result<Lockfile, Error> {
+        let invalid = |message: String| Error::Io {
+            path: path.into(),
+            source: io::Error::new(io::ErrorKind::InvalidData, message),
+        };
+        let contents = fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.into(),
+            source,
+        })?;
+        let root = ImDocument::parse(contents).map_err(|e| invalid(e.to_string()))?;
+        let string = |table: &Table, key| table.get(key)?.as_str().map(String::from);
+
+        let tables: Vec<&Table> = root
+            .get("package")
+            .and_then(Item::as_array_of_tables)
+            .map(|packages| packages.iter().collect())
+            .unwrap_or_default();
+        let mut packages = Vec::new();
+        for table in &tables {
+            packages.push(Package {
+                name: string(table, "name")
+                    .ok_or_else(|| invalid("package without name".into()))?,
+                version: string(table, "version").unwrap_or_default(),
+                source: string(table, "source"),
+                checksum: string(table, "checksum"),
+                dependencies: Vec::new(),
+                line: manifests::entries(root.raw(), *table)
+                    .first()
+                    .map_or(1, |(.., line)| (*line).into()),
+            });
+        }
+
+        // Dependencies are identified by name, qualified by version and source only when ambiguous
+        for (index, table) in tables.iter().enumerate() {
+            let Some(dependencies) = table.get("dependencies").and_then(Item::as_array) else {
+                continue;
+            };
+            for dependency in dependencies.iter().filter_map(|d| d.as_str()) {
+                let mut parts = dependency.split(' ');
+                let name = parts.next().unwrap_or_default();
+                let version = parts.next();
+                if let Some(position) = packages
+                    .iter()
+                    .position(|p| p.name == name && version.is_none_or(|v| p.version == v))
+                {
+                    packages[index].dependencies.push(position);
+                }
+            }
+        }
+        Ok(Lockfile { packages })
+    }
+
+    /// Returns whether each package is reachable from the package (including itself), by index.
+    pub(crate) fn reachable(&self, index: usize) -> Vec<bool> {
+        let mut reachable = vec![false; self.packages.len()];
+        let mut stack = vec![index];
+        while let Some(index) = stack.pop() {
+            if !std::mem::replace(&mut reachable[index], true) {
+                stack.extend(&self.packages[index].dependencies);
+            }
+        }
+        reachable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_packages_and_dependencies() {
+        let path = std::env::temp_dir().join(format!("sbp-review-{}.lock", std::process::id()));
+        fs::write(
+            &path,
+            r#"version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "serde 1.0.0",
+ "serde 0.9.0",
+]
+
+[[package]]
+name = "serde"
+version = "0.9.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "aaaa"
+
+[[package]]
+name = "serde"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "bbbb"
+dependencies = [
+ "unused",
+]
+
+[[package]]
+name = "unused"
+version = "0.1.0"
+"#,
+        )
+        .unwrap();
+        let lockfile = Lockfile::read(&path);
+        fs::remove_file(&path).unwrap();
+        let lockfile = lockfile.unwrap();
+
+        let packages: Vec<_> = lockfile
+            .packages
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.version.as_str(),
+                    p.checksum.as_deref(),
+                    p.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            packages,
+            [
+                ("app", "0.1.0", None, 4),
+                ("serde", "0.9.0", Some("aaaa"), 12),
+                ("serde", "1.0.0", Some("bbbb"), 18),
+                ("unused", "0.1.0", None, 27),
+            ]
+        );
+        assert_eq!(lockfile.packages[0].dependencies, [2, 1]);
+        assert!(lockfile.packages[0].source.is_none());
+        assert_eq!(lockfile.reachable(2), [false, false, true, true]);
+        assert_eq!(lockfile.reachable(0), [true; 4]);
+    }
+
+    #[test]
+    fn rejects_packages_without_names() {
+        let path =
+            std::env::temp_dir().join(format!("sbp-review-{}-invalid.lock", std::process::id()));
+        fs::write(&path, "[[package]]\nversion = \"0.1.0\"\n").unwrap();
+        let lockfile = Lockfile::read(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(lockfile.is_err());
+    }
+}
@@ -1,454 +1,907 @@
-use crate::clippy::Message;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 use colored::Colorize;
-use std::{fs, process::Command};
-use terminal_link::Link;
+use sbp_review::{
+    audit::Audit,
+    benchmarks::{Benchmarks, FrameBenchmarks},
+    build::Build,
+    chainspec::ChainSpec,
+    checkstyle,
+    clippy::Clippy,
+    compare,
+    coverage::Coverage,
+    docs::{Docs, DocsCoverage},
+    duplicates::Duplicates,
+    features::Features,
+    fix::Fix,
+    fmt::Fmt,
+    history, html, junit,
+    licenses::Licenses,
+    manifests::Manifests,
+    markdown,
+    metadata::MetadataDiff,
+    migrations::Migrations,
+    node::Node,
+    pallets::Pallets,
+    publish,
+    publish_check::PublishCheck,
+    runtime::Runtime,
+    sarif, sbom,
+    score::Score,
+    srtool::Srtool,
+    tests::Tests,
+    tui,
+    unsafe_code::Unsafe,
+    update::{self, Update},
+    vet::Vet,
+    weights::Weights,
+    xcm::Xcm,
+    Analysis, Category, Color, Context, Error, Finding, Format, GroupBy, Severity,
+};
+use std::{collections::BTreeSet, fs, io, path::PathBuf, process::ExitCode};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    /// Browses the findings within an interactive terminal user interface, rather than printing them.
+    #[arg(long, global = true, conflicts_with = "format")]
+    tui: bool,
+    /// The output format [default: text].
+    #[arg(long, global = true, value_enum)]
+    format: Option<Format>,
+    /// Path to the Cargo.toml of the project to be analysed [default: the current directory].
+    #[arg(long, global = true, value_name = "PATH")]
+    manifest_path: Option<PathBuf>,
+    /// Package(s) to analyse [default: all workspace members].
+    #[arg(short, long = "package", global = true, value_name = "SPEC")]
+    packages: Vec<String>,
+    /// Package(s) to exclude from analysis.
+    #[arg(long, global = true, value_name = "SPEC")]
+    exclude: Vec<String>,
+    /// Path(s) to exclude from analysis, as prefixes or globs (e.g. `**/mock.rs`), in addition to any within the
+    /// configuration file.
+    #[arg(long, global = true, value_name = "GLOB")]
+    exclude_path: Vec<String>,
+    /// The lint profile to use: strict, default, relaxed or a profile defined within the configuration file.
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// Re-analyse all packages, ignoring any cached results.
+    #[arg(long, global = true)]
+    no_cache: bool,
+    /// When to use coloured output and hyperlinks.
+    #[arg(long, global = true, value_enum, default_value_t = Color::Auto)]
+    color: Color,
+    /// Disables coloured output and hyperlinks, equivalent to `--color never`.
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Skips checks requiring network access, such as outdated dependency versions.
+    #[arg(long, global = true)]
+    offline: bool,
+    /// The editor opened by links to source locations, e.g. `vscode`, `idea`, `none` or a link template such as
+    /// `vscode://file/{path}:{line}:{col}`, overriding that within the configuration file [default: file].
+    #[arg(long, global = true)]
+    editor: Option<String>,
+    /// Outputs the source of each finding, along with the number of lines surrounding it, underlining the code located.
+    #[arg(long, global = true, value_name = "LINES")]
+    snippet: Option<usize>,
+    /// Only reports findings on lines changed since the git reference, e.g. `main`.
+    #[arg(long, global = true, value_name = "REF")]
+    diff: Option<String>,
+    /// The minimum severity of findings which results in a non-zero exit code.
+    #[arg(long, global = true, value_enum, default_value_t = FailOn::Never)]
+    fail_on: FailOn,
+    /// The maximum duration of spawned cargo commands (e.g. clippy, tests, docs and builds) in seconds, after which they
+    /// are terminated, overriding the default timeout and that of the subcommand within the configuration file.
+    #[arg(long, global = true, value_name = "SECONDS")]
+    timeout: Option<u64>,
+    /// The maximum number of errors before failing, overriding any budget within the configuration file.
+    #[arg(long, global = true, value_name = "N")]
+    max_errors: Option<usize>,
+    /// The maximum number of warnings before failing, overriding any budget within the configuration file.
+    #[arg(long, global = true, value_name = "N")]
+    max_warnings: Option<usize>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FailOn {
+    /// Fail when any errors are found.
+    Error,
+    /// Fail when any errors or warnings are found.
+    Warning,
+    /// Never fail as a result of findings.
+    Never,
+}
+
+impl FailOn {
+    fn fails(&self, severity: Severity) -> bool {
+        match self {
+            FailOn::Error => severity == Severity::Error,
+            FailOn::Warning => matches!(severity, Severity::Error | Severity::Warning),
+            FailOn::Never => false,
+        }
+    }
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Analyses code for known issues.
-    Code,
+    Code {
+        /// Groups the findings, outputting them as a tree once all have been found.
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
+        /// Only reports findings whose lint matches the pattern, where `*` matches any characters, e.g. `clippy::panic`.
+        #[arg(long, value_name = "PATTERN")]
+        lint: Vec<String>,
+        /// Only reports findings of at least the severity.
+        #[arg(long, value_enum)]
+        level: Option<Severity>,
+        /// Only reports findings within files matching the glob, e.g. `pallets/**`.
+        #[arg(long, value_name = "GLOB")]
+        path: Vec<String>,
+        /// Outputs at most the number of findings, hiding any others.
+        #[arg(long, value_name = "N")]
+        max_results: Option<usize>,
+        /// Only outputs the findings of the most frequent lints, hiding any others.
+        #[arg(long, value_name = "N")]
+        top: Option<usize>,
+        /// Lints all targets, including tests, examples and benchmarks.
+        #[arg(long)]
+        all_targets: bool,
+        /// Lints test targets, in addition to the default targets.
+        #[arg(long, conflicts_with = "all_targets")]
+        tests: bool,
+        /// Lints benchmark targets, in addition to the default targets.
+        #[arg(long, conflicts_with = "all_targets")]
+        benches: bool,
+        /// Feature(s) enabled when linting, so that feature-gated code is also linted, e.g.
+        /// `runtime-benchmarks,try-runtime`.
+        #[arg(short = 'F', long, value_delimiter = ',')]
+        features: Vec<String>,
+        /// Enables all features when linting.
+        #[arg(long, conflicts_with = "features")]
+        all_features: bool,
+    },
+    /// Applies the machine-applicable suggestions of clippy, reporting any findings which cannot be fixed automatically.
+    Fix {
+        /// Prints the changes which would be made, without applying them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Checks code formatting.
+    Fmt,
     /// Analyses manifest(s) for known issues.
-    Manifests,
+    Manifests {
+        /// Verifies the minimum supported rust version of each package by compiling with the toolchain.
+        #[arg(long)]
+        verify_msrv: bool,
+        /// Verifies the `repository`, `homepage` and `documentation` URLs of each package respond and the repository
+        /// matches the git remote of the workspace.
+        #[arg(long)]
+        online: bool,
+    },
+    /// Checks packages intended for release are ready to be published to crates.io.
+    PublishCheck {
+        /// Also packages and verifies each package via `cargo publish --dry-run`.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Checks features such as `std` are propagated to dependencies.
+    Features,
     /// Executes available tests.
-    Tests,
+    Tests {
+        /// Writes the test results as a JUnit XML report to the path.
+        #[arg(long, value_name = "PATH")]
+        junit: Option<PathBuf>,
+        /// Tests all targets, including examples and benchmarks (excluding doc tests unless `--doc` is also used).
+        #[arg(long)]
+        all_targets: bool,
+        /// Executes documentation tests.
+        #[arg(long)]
+        doc: bool,
+    },
     /// Executes available benchmarks as tests.
-    Benchmarks,
+    Benchmarks {
+        /// Executes the benchmarks via the `benchmark pallet` command of the node, capturing the resulting weights.
+        #[arg(long)]
+        run: bool,
+        /// Regenerates the weights of the selected pallet(s) into a temporary directory and compares them with the
+        /// committed weights, flagging any which appear stale or hand-edited.
+        #[arg(long, conflicts_with = "run")]
+        check_weights: bool,
+        /// The node binary, built with the `runtime-benchmarks` feature.
+        #[arg(long, value_name = "PATH")]
+        binary: Option<PathBuf>,
+        /// The chain specification [default: dev].
+        #[arg(long)]
+        chain: Option<String>,
+        /// The pallet(s) to benchmark [default: *].
+        #[arg(long)]
+        pallet: Option<String>,
+        /// The extrinsic(s) to benchmark [default: *].
+        #[arg(long)]
+        extrinsic: Option<String>,
+        /// The number of samples taken across the range of each component [default: 50].
+        #[arg(long)]
+        steps: Option<u32>,
+        /// The number of times each sample is repeated [default: 20].
+        #[arg(long)]
+        repeat: Option<u32>,
+    },
+    /// Verifies no_std crates build for WebAssembly and crates build with each of the `runtime-benchmarks` and
+    /// `try-runtime` features.
+    Build,
+    /// Checks dependencies for security advisories, via the RustSec advisory database.
+    Audit,
+    /// Reports which dependencies are covered by cargo vet audits or crev reviews, and which large or critical
+    /// dependencies are unaudited.
+    Vet,
+    /// Checks dependency licenses against the license policy.
+    Licenses,
+    /// Detects crates resolved at multiple versions within `Cargo.lock`.
+    Duplicates,
+    /// Counts and locates unsafe code within the workspace and its direct dependencies.
+    Unsafe,
+    /// Measures test coverage, via `cargo llvm-cov`.
+    Coverage,
+    /// Builds documentation and reports any warnings.
+    Docs {
+        /// Also measures documentation coverage, via nightly rustdoc.
+        #[arg(long)]
+        coverage: bool,
+    },
+    /// Analyses pallet source for known issues.
+    Pallets,
+    /// Inspects the XCM configuration of runtimes for known dangerous settings.
+    Xcm,
+    /// Inspects the node (CLI and service) of a parachain for unhelpful or insecure defaults.
+    Node,
+    /// Validates a chain specification.
+    Chainspec {
+        /// The path of the chain specification (JSON).
+        path: PathBuf,
+        /// Verifies that the boot nodes are reachable.
+        #[arg(long)]
+        check_boot_nodes: bool,
+    },
+    /// Builds a runtime and reports the size of its Wasm blob, its runtime version and its pallets.
+    Runtime {
+        /// The runtime package.
+        #[arg(long, value_name = "SPEC")]
+        runtime: Option<String>,
+    },
+    /// Compares the metadata of a runtime with that of a base git reference.
+    MetadataDiff {
+        /// The base git reference, e.g. `main`.
+        #[arg(long)]
+        base: String,
+        /// The runtime package.
+        #[arg(long, value_name = "SPEC")]
+        runtime: Option<String>,
+    },
+    /// Builds a runtime deterministically via srtool, reporting its proposal and blob hashes.
+    Srtool {
+        /// The runtime package.
+        #[arg(long, value_name = "SPEC")]
+        runtime: Option<String>,
+        /// The srtool container image [default: paritytech/srtool:latest].
+        #[arg(long)]
+        image: Option<String>,
+        /// The expected proposal hash, verified against that of the build.
+        #[arg(long, value_name = "HASH")]
+        proposal_hash: Option<String>,
+    },
+    /// Executes the migrations of a runtime against live (or snapshot) state, via `try-runtime`.
+    Migrations {
+        /// The runtime package whose migrations are executed.
+        #[arg(long, value_name = "SPEC")]
+        runtime: Option<String>,
+        /// The RPC endpoint of a live chain from which state is fetched.
+        #[arg(long)]
+        uri: Option<String>,
+        /// A state snapshot, as an alternative to fetching live state.
+        #[arg(long, value_name = "PATH", conflicts_with = "uri")]
+        snapshot: Option<PathBuf>,
+        /// The checks executed: none, all, pre-and-post or try-state [default: all].
+        #[arg(long)]
+        checks: Option<String>,
+    },
+    /// Executes all analyses and summarises the findings.
+    Review {
+        /// Writes the test results as a JUnit XML report to the path, including the score of the review.
+        #[arg(long, value_name = "PATH")]
+        junit: Option<PathBuf>,
+    },
+    /// Compares two reports exported via `--format json`, showing the findings which were fixed, introduced or
+    /// unchanged.
+    Compare {
+        /// The previous report.
+        previous: PathBuf,
+        /// The current report.
+        current: PathBuf,
+    },
+    /// Publishes the findings of a report exported via `--format json` to GitHub, as inline review comments on a pull
+    /// request and/or as a check run, using the token within `GITHUB_TOKEN`.
+    Publish {
+        /// The report.
+        report: PathBuf,
+        /// The repository.
+        #[arg(long, value_name = "OWNER/REPO")]
+        github: String,
+        /// The number of the pull request commented upon.
+        #[arg(long, value_name = "NUMBER", required_unless_present = "check")]
+        pr: Option<u64>,
+        /// Publishes the findings as a check run, concluding as failed according to `--fail-on`.
+        #[arg(long)]
+        check: bool,
+        /// The commit of the check run [default: HEAD].
+        #[arg(long, requires = "check")]
+        sha: Option<String>,
+    },
+    /// Generates a software bill of materials of the workspace and its dependencies, including the commits of git
+    /// dependencies, output as CycloneDX (default) or SPDX via `--format cyclonedx|spdx`.
+    Sbom,
+    /// Shows whether the number of findings of each subcommand is trending up or down across recorded runs.
+    History {
+        /// The number of latest runs shown per subcommand.
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Updates sbp-review to the latest release, verifying the checksum of the release binary before it is installed.
+    SelfUpdate {
+        /// Only checks whether a newer release is available.
+        #[arg(long)]
+        check: bool,
+    },
+    /// Generates a completion script for the shell, e.g. `sbp-review completions bash > /etc/bash_completion.d/sbp-review`.
+    Completions {
+        /// The shell.
+        shell: Shell,
+    },
 }
 
-fn main() {
-    match &Cli::parse().command {
-        None => {}
-        Some(Commands::Code) => lint(),
-        Some(Commands::Manifests) => metadata(),
-        Some(Commands::Tests) => test(),
-        Some(Commands::Benchmarks) => benchmark(),
-    }
-}
+/// The exit code used when an analysis could not be completed.
+const EXIT_ERROR: u8 = 2;
 
-fn lint() {
-    println!("Analysing code via clippy...");
-
-    const CLIPPY_CONFIG: &str = "clippy.toml";
-    let clippy_config_exists = std::fs::metadata(CLIPPY_CONFIG).is_ok();
-    if !clippy_config_exists {
-        const CONFIG: &str = "too-many-lines-threshold=30";
-        std::fs::write(CLIPPY_CONFIG, CONFIG).unwrap();
+fn main() -> ExitCode {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    match cli.no_color {
+        true => Color::Never,
+        false => cli.color,
     }
-
-    // Set all configured lints as warning
-    let args = clippy::LINTS.map(|l| format!("-W{}", l));
-    let output = Command::new("cargo")
-        .arg("clippy")
-        .arg("--message-format=json")
-        .arg("--")
-        .args(args)
-        .output()
-        .unwrap();
-
-    // if output.stderr.len() > 0 {
-    //     println!("{}", String::from_utf8_lossy(&output.stderr))
-    // }
-
-    let mut matches = Vec::new();
-    let output = String::from_utf8_lossy(&output.stdout);
-    for line in output.lines() {
-        match serde_json::from_str::<clippy::Match>(line) {
-            Ok(m) => matches.push(m),
+    .apply();
+    let Some(command) = &cli.command else {
+        return ExitCode::SUCCESS;
+    };
+    // Completions are generated independently of any workspace
+    if let Commands::Completions { shell } = command {
+        clap_complete::generate(*shell, &mut Cli::command(), "sbp-review", &mut io::stdout());
+        return ExitCode::SUCCESS;
+    }
+    if let Commands::SelfUpdate { check } = command {
+        return match update::update(*check) {
+            Ok(Update::UpToDate(version)) => {
+                println!("sbp-review {} is the latest release", version);
+                ExitCode::SUCCESS
+            }
+            Ok(Update::Available { version, url }) => {
+                println!(
+                    "sbp-review {} is available ({}), run `sbp-review self-update` to install it",
+                    version, url
+                );
+                ExitCode::SUCCESS
+            }
+            Ok(Update::Updated { version, url }) => {
+                println!("Updated sbp-review to {} ({})", version, url);
+                ExitCode::SUCCESS
+            }
             Err(e) => {
-                println!("{} {}", e, line)
+                eprintln!("{} {}", Severity::Error, e);
+                ExitCode::from(EXIT_ERROR)
             }
-        }
+        };
     }
-
-    if !clippy_config_exists {
-        fs::remove_file(CLIPPY_CONFIG).unwrap();
+    let mut context = match Context::new(cli.manifest_path.clone()) {
+        Ok(context) => context,
+        Err(e) => {
+            eprintln!("{} {}", Severity::Error, e);
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+    if let Some(format) = cli.format {
+        context.format = format;
     }
-
-    // Filter and sort matches
-    let mut matches: Vec<_> = matches
-        .iter()
-        .filter_map(|m| m.message.as_ref())
-        .filter(|m| m.code.is_some() && !ignored(m))
-        .collect();
-    matches.sort_by_key(|m| {
-        m.spans
-            .get(0)
-            .map(|s| (&s.file_name, s.line_start, s.column_start))
-    });
-    // Output results
-    for message in matches {
-        print!(
-            "{} {} {}",
-            match message.level.as_str() {
-                "warning" => message.level.yellow(),
-                "error" => message.level.red(),
-                _ => message.level.normal(),
-            },
-            message.code.as_ref().map_or("".into(), |c| {
-                match c.code.starts_with("clippy::") {
-                    true => {
-                        let url = format!(
-                            "https://rust-lang.github.io/rust-clippy/master/#/{}",
-                            c.code.replace("clippy::", "")
-                        );
-                        Link::new(&c.code, &url).to_string().cyan()
-                    }
-                    false => c.code.as_str().into(),
-                }
-            }),
-            message.message,
+    if matches!(context.format, Format::Cyclonedx | Format::Spdx)
+        && !matches!(command, Commands::Sbom)
+    {
+        eprintln!(
+            "{} `--format cyclonedx` and `--format spdx` are only supported by `sbom`",
+            Severity::Error
         );
-        // add help
-        for item in message
-            .children
-            .iter()
-            .filter(|m| m.level == "help" && !m.message.starts_with("for further information"))
-        {
-            print!(" {} {}", "help:".bold(), item.message)
+        return ExitCode::from(EXIT_ERROR);
+    }
+    // Findings are collected silently, to be browsed once all analyses have completed
+    if cli.tui {
+        context.format = Format::Json;
+    }
+    context.packages = cli.packages.clone();
+    context.exclude = cli.exclude.clone();
+    context
+        .config
+        .paths
+        .ignore
+        .extend(cli.exclude_path.iter().cloned());
+    context.cache = !cli.no_cache;
+    context.offline = cli.offline;
+    context.snippet = cli.snippet;
+    if let Some(editor) = &cli.editor {
+        context.config.output.editor = Some(editor.clone());
+    }
+    if let Err(e) = context.config.output.editor() {
+        eprintln!("{} {}", Severity::Error, e);
+        return ExitCode::from(EXIT_ERROR);
+    }
+    if let Some(timeout) = cli.timeout {
+        // The timeouts of other subcommands are retained, as they apply to the analyses of a review
+        let timeouts = &mut context.config.timeout;
+        timeouts.insert("default".into(), timeout);
+        if let Some(subcommand) = matches.subcommand_name() {
+            timeouts.insert(subcommand.into(), timeout);
         }
-        match message.spans.get(0) {
-            None => {}
-            Some(span) => {
-                let text = format!(
-                    "./{}:{}:{}",
-                    span.file_name, span.line_start, span.column_start
-                );
-                let url = format!(
-                    "file:///{}/{}:{}:{}",
-                    std::env::current_dir()
-                        .unwrap()
-                        .into_os_string()
-                        .into_string()
-                        .unwrap(),
-                    span.file_name,
-                    span.line_start,
-                    span.column_start
-                );
-                println!(" at {}", Link::new(&text, &url).to_string().cyan())
-            }
+    }
+    if let Some(reference) = &cli.diff {
+        if let Err(e) = context.diff(reference) {
+            eprintln!("{} {}", Severity::Error, e);
+            return ExitCode::from(EXIT_ERROR);
         }
     }
-}
-
-fn ignored(message: &Message) -> bool {
-    const IGNORED: [&str; 7] = [
-        "construct_runtime!",
-        "#[frame_support::pallet]",
-        "#[pallet::call]",
-        "#[pallet::error]",
-        "#[pallet::event]",
-        "#[pallet::pallet]",
-        "#[pallet::storage]",
-    ];
-    message.spans.iter().any(|s| {
-        s.text
-            .iter()
-            .any(|t| IGNORED.iter().any(|i| t.text.contains(i)))
-    })
-}
-
-fn metadata() {
-    println!("Analysing manifest(s) via metadata...");
-
-    let output = Command::new("cargo")
-        .arg("metadata")
-        .arg("--no-deps")
-        .output()
-        .unwrap();
-
-    let output = String::from_utf8_lossy(&output.stdout);
-    match serde_json::from_str::<manifests::Metadata>(&output) {
-        Ok(metadata) => {
-            for package in metadata.packages {
-                println!(
-                    "{}",
-                    Link::new(&package.name, &format!("file:///{}", package.manifest_path))
-                        .to_string()
-                        .cyan()
-                );
-
-                // Check for common metadata: https://rust-lang.github.io/api-guidelines/documentation.html#cargotoml-includes-all-common-metadata-c-metadata
-                match package.authors.len() {
-                    0 => println!("  {} no 'authors' found", "warning".yellow()),
-                    _ => println!("  authors: {}", package.authors.join(", ")),
+    if let Commands::Tests {
+        junit,
+        all_targets,
+        doc,
+    } = command
+    {
+        context.junit = junit.clone();
+        context.all_targets = *all_targets;
+        context.doc = *doc;
+    }
+    if let Commands::Review { junit } = command {
+        context.junit = junit.clone();
+    }
+    if let Commands::Code {
+        group_by,
+        lint,
+        level,
+        path,
+        max_results,
+        top,
+        all_targets,
+        tests,
+        benches,
+        features,
+        all_features,
+    } = command
+    {
+        context.features = features.clone();
+        context.all_features = *all_features;
+        context.group_by = *group_by;
+        context.all_targets = *all_targets;
+        context.tests = *tests;
+        context.benches = *benches;
+        context.max_results = *max_results;
+        context.top = *top;
+        let filter = &mut context.config.filter;
+        filter.lints = lint.clone();
+        filter.level = *level;
+        filter.paths = path.clone();
+    }
+    if let Commands::Manifests {
+        verify_msrv,
+        online,
+    } = command
+    {
+        context.verify_msrv = *verify_msrv;
+        context.online = *online;
+    }
+    if let Commands::Benchmarks {
+        binary,
+        chain,
+        pallet,
+        extrinsic,
+        steps,
+        repeat,
+        ..
+    } = command
+    {
+        let benchmarks = &mut context.config.benchmarks;
+        if let Some(binary) = binary {
+            benchmarks.binary = Some(binary.clone());
+        }
+        if let Some(chain) = chain {
+            benchmarks.chain = chain.clone();
+        }
+        if let Some(pallet) = pallet {
+            benchmarks.pallet = pallet.clone();
+        }
+        if let Some(extrinsic) = extrinsic {
+            benchmarks.extrinsic = extrinsic.clone();
+        }
+        benchmarks.steps = steps.unwrap_or(benchmarks.steps);
+        benchmarks.repeat = repeat.unwrap_or(benchmarks.repeat);
+    }
+    if let Commands::Migrations {
+        runtime,
+        uri,
+        snapshot,
+        checks,
+    } = command
+    {
+        let migrations = &mut context.config.migrations;
+        if runtime.is_some() {
+            migrations.runtime = runtime.clone();
+        }
+        if uri.is_some() {
+            migrations.uri = uri.clone();
+        }
+        if snapshot.is_some() {
+            migrations.snapshot = snapshot.clone();
+        }
+        if let Some(checks) = checks {
+            migrations.checks = checks.clone();
+        }
+    }
+    if let Commands::Runtime {
+        runtime: Some(runtime),
+    }
+    | Commands::MetadataDiff {
+        runtime: Some(runtime),
+        ..
+    }
+    | Commands::Srtool {
+        runtime: Some(runtime),
+        ..
+    } = command
+    {
+        context.config.runtime.package = Some(runtime.clone());
+    }
+    if let Commands::Srtool {
+        image: Some(image), ..
+    } = command
+    {
+        context.config.srtool.image = image.clone();
+    }
+    if cli.max_errors.is_some() {
+        context.config.budget.max_errors = cli.max_errors;
+    }
+    if cli.max_warnings.is_some() {
+        context.config.budget.max_warnings = cli.max_warnings;
+    }
+    if let Some(profile) = &cli.profile {
+        context.config.lints.profile = Some(profile.clone());
+    }
+    // The score of a review, included within the report
+    let mut score = None;
+    let result = match command {
+        Commands::Code { .. } => Clippy.run(&context),
+        Commands::Fix { dry_run } => Fix { dry_run: *dry_run }.run(&context),
+        Commands::Fmt => Fmt.run(&context),
+        Commands::Manifests { .. } => Manifests.run(&context),
+        Commands::PublishCheck { dry_run } => PublishCheck { dry_run: *dry_run }.run(&context),
+        Commands::Features => Features.run(&context),
+        Commands::Tests { .. } => Tests.run(&context),
+        Commands::Benchmarks { run: true, .. } => FrameBenchmarks.run(&context),
+        Commands::Benchmarks {
+            check_weights: true,
+            ..
+        } => Weights.run(&context),
+        Commands::Benchmarks { .. } => Benchmarks.run(&context),
+        Commands::Build => Build.run(&context),
+        Commands::Audit => Audit.run(&context),
+        Commands::Vet => Vet.run(&context),
+        Commands::Licenses => Licenses.run(&context),
+        Commands::Duplicates => Duplicates.run(&context),
+        Commands::Unsafe => Unsafe.run(&context),
+        Commands::Coverage => Coverage.run(&context),
+        Commands::Docs { coverage } => Docs.run(&context).and_then(|mut findings| {
+            if *coverage {
+                findings.extend(DocsCoverage.run(&context)?);
+            }
+            Ok(findings)
+        }),
+        Commands::Pallets => Pallets.run(&context),
+        Commands::Xcm => Xcm.run(&context),
+        Commands::Node => Node.run(&context),
+        Commands::Chainspec {
+            path,
+            check_boot_nodes,
+        } => ChainSpec {
+            path: path.clone(),
+            boot_nodes: *check_boot_nodes,
+        }
+        .run(&context),
+        Commands::Runtime { .. } => Runtime.run(&context),
+        Commands::MetadataDiff { base, .. } => MetadataDiff { base: base.clone() }.run(&context),
+        Commands::Srtool { proposal_hash, .. } => Srtool {
+            proposal_hash: proposal_hash.clone(),
+        }
+        .run(&context),
+        Commands::Migrations { .. } => Migrations.run(&context),
+        Commands::Review { .. } => review(&context).map(|(findings, review_score)| {
+            score = Some(review_score);
+            findings
+        }),
+        // Comparisons are output directly, failing only as a result of introduced findings
+        Commands::Compare { previous, current } => {
+            return match compare::print(&context, previous, current) {
+                Ok(comparison) => match comparison
+                    .introduced
+                    .iter()
+                    .any(|f| cli.fail_on.fails(f.severity))
+                {
+                    true => ExitCode::FAILURE,
+                    false => ExitCode::SUCCESS,
+                },
+                Err(e) => {
+                    eprintln!("{} {}", Severity::Error, e);
+                    ExitCode::from(EXIT_ERROR)
                 }
-
-                match package.description {
-                    None => println!("  {} no 'description' found", "warning".yellow()),
-                    Some(description) => println!("  description: {}", description),
+            }
+        }
+        // Publishing is reported directly, as the findings are those of an existing report
+        Commands::Publish {
+            report,
+            github,
+            pr,
+            check,
+            sha,
+        } => {
+            let published = compare::load(report).and_then(|findings| {
+                let published = pr
+                    .map(|pr| publish::publish(&context, github, pr, &findings))
+                    .transpose()?;
+                let failed = findings.iter().any(|f| cli.fail_on.fails(f.severity));
+                let check = check
+                    .then(|| publish::check(&context, github, sha.as_deref(), &findings, failed))
+                    .transpose()?;
+                Ok((published, check))
+            });
+            return match published {
+                Ok((published, check)) => {
+                    match context.format {
+                        Format::Text => {
+                            if let (Some(published), Some(pr)) = (published, pr) {
+                                println!(
+                                    "Published {} comment(s) to {}#{} within {} review(s), skipping {} duplicate(s), {} outside the diff and {} without a location",
+                                    published.comments,
+                                    github,
+                                    pr,
+                                    published.reviews,
+                                    published.duplicates,
+                                    published.outside_diff,
+                                    published.unlocated
+                                );
+                            }
+                            if let Some(check) = check {
+                                println!(
+                                    "Published check run {} with {} annotation(s) within {} request(s)",
+                                    check.url, check.annotations, check.requests
+                                );
+                            }
+                        }
+                        _ => println!(
+                            "{}",
+                            serde_json::to_string_pretty(
+                                &serde_json::json!({ "review": published, "check": check })
+                            )
+                            .unwrap()
+                        ),
+                    }
+                    ExitCode::SUCCESS
                 }
-
-                match package.license {
-                    None => println!("  {} no 'license' found", "warning".yellow()),
-                    Some(license) => println!("  license: {}", license),
+                Err(e) => {
+                    eprintln!("{} {}", Severity::Error, e);
+                    ExitCode::from(EXIT_ERROR)
                 }
-
-                match package.repository {
-                    None => println!("  {} no 'repository' found", "warning".yellow()),
-                    Some(repository) => println!("  repository: {}", repository),
+            };
+        }
+        // Bills of materials are output directly, rather than as findings
+        Commands::Sbom => {
+            let sbom = match cli.format {
+                None | Some(Format::Cyclonedx) => sbom::cyclonedx(&context),
+                Some(Format::Spdx) => sbom::spdx(&context),
+                Some(_) => {
+                    eprintln!(
+                        "{} `sbom` only supports `--format cyclonedx` or `--format spdx`",
+                        Severity::Error
+                    );
+                    return ExitCode::from(EXIT_ERROR);
                 }
-
-                // check dependencies
-                const SUBSTRATE_REPO: &str = "git+https://github.com/paritytech/substrate";
-                for (name, source) in package.dependencies.iter().filter_map(|d| {
-                    d.source
-                        .as_ref()
-                        .and_then(|s| s.starts_with(SUBSTRATE_REPO).then(|| (&d.name, s)))
-                }) {
-                    // todo: collect substrate, cumulus, polkadot versions and ensure all match
-                    let url = url::Url::parse(&source[4..]).unwrap();
-                    for (_, value) in url
-                        .query_pairs()
-                        .filter(|(parameter, _)| parameter == "branch")
-                    {
-                        // temp: use last few versions
-                        if !["polkadot-v0.9.42", "polkadot-v0.9.43", "polkadot-v1.0.0"]
-                            .contains(&value.as_ref())
-                        {
-                            println!(
-                                "  {} {} for '{}' is out of date",
-                                "warning".yellow(),
-                                value,
-                                name
-                            )
-                        }
-                    }
+            };
+            return match sbom {
+                Ok(sbom) => {
+                    println!("{}", serde_json::to_string_pretty(&sbom).unwrap());
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("{} {}", Severity::Error, e);
+                    ExitCode::from(EXIT_ERROR)
+                }
+            };
+        }
+        // History is output directly, rather than as findings
+        Commands::History { limit } => {
+            return match history::print(&context, *limit) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("{} {}", Severity::Error, e);
+                    ExitCode::from(EXIT_ERROR)
                 }
-                // TODO: check minimum rust version
             }
         }
-        Err(e) => println!("{} could not deserialise: {}", "error".red(), e),
+        Commands::Completions { .. } | Commands::SelfUpdate { .. } => {
+            unreachable!("handled before analysis")
+        }
+    };
+    let findings = match result {
+        Ok(findings) => findings,
+        Err(e) => {
+            eprintln!("{} {}", Severity::Error, e);
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+    if context.config.history.record {
+        let name = matches.subcommand_name().unwrap_or_default();
+        if let Err(e) = history::record(&context, name, &findings) {
+            eprintln!("{} could not record history: {}", Severity::Warning, e);
+        }
     }
-}
-
-fn test() {
-    println!("Executing available tests...");
-
-    let _output = Command::new("cargo")
-        .arg("test")
-        .arg("--no-fail-fast")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap();
-}
 
-fn benchmark() {
-    println!("Executing available benchmarks...");
+    match context.format {
+        _ if cli.tui => {
+            if let Err(e) = tui::browse(&context.root, &findings) {
+                eprintln!("{} {}", Severity::Error, e);
+                return ExitCode::from(EXIT_ERROR);
+            }
+        }
+        Format::Text => {}
+        Format::Json => {
+            let report = match &score {
+                Some(score) => serde_json::json!({ "findings": findings, "score": score }),
+                None => serde_json::json!(findings),
+            };
+            println!("{}", serde_json::to_string_pretty(&report).unwrap())
+        }
+        Format::Sarif => {
+            let mut log = sarif::Log::from(&findings[..]);
+            if let Some(score) = &score {
+                log = log.with_score(score);
+            }
+            println!("{}", serde_json::to_string_pretty(&log).unwrap())
+        }
+        Format::Markdown => print!("{}", markdown::render(&findings, score.as_ref())),
+        Format::Html => print!("{}", html::render(&findings, score.as_ref(), &context.root)),
+        Format::Checkstyle => print!("{}", checkstyle::render(&findings)),
+        Format::Cyclonedx | Format::Spdx => unreachable!("only supported by sbom"),
+    }
 
-    let _output = Command::new("cargo")
-        .arg("test")
-        .arg("--no-default-features")
-        .arg("--features=runtime-benchmarks")
-        .arg("--no-fail-fast")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap();
+    // Budgets are reported to stderr, to keep structured output parsable
+    let exceeded = context.config.budget.exceeded(&findings);
+    for budget in &exceeded {
+        eprintln!("{} budget exceeded: {}", Severity::Error, budget);
+    }
+    match !exceeded.is_empty() || findings.iter().any(|f| cli.fail_on.fails(f.severity)) {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
 }
 
-mod clippy {
-    use serde::{Deserialize, Serialize};
-
-    // Source: https://rust-lang.github.io/rust-clippy/master/
-    pub(super) const LINTS: [&str; 124] = [
-        "clippy::alloc_instead_of_core",
-        "clippy::allow_attributes_without_reason",
-        "clippy::arithmetic_side_effects",
-        "clippy::as_underscore",
-        "clippy::assertions_on_result_states",
-        "clippy::bool_to_int_with_if",
-        "clippy::branches_sharing_code",
-        "clippy::cargo_common_metadata",
-        "clippy::cast_lossless",
-        "clippy::cast_possible_truncation",
-        "clippy::cast_possible_wrap",
-        "clippy::cast_precision_loss",
-        "clippy::cast_sign_loss",
-        "clippy::checked_conversions",
-        "clippy::cloned_instead_of_copied",
-        "clippy::cognitive_complexity",
-        "clippy::dbg_macro",
-        "clippy::default_trait_access",
-        "clippy::derive_partial_eq_without_eq",
-        "clippy::else_if_without_else",
-        "clippy::empty_structs_with_brackets",
-        "clippy::enum_glob_use",
-        "clippy::equatable_if_let",
-        "clippy::exit",
-        "clippy::expect_used",
-        "clippy::explicit_into_iter_loop",
-        "clippy::explicit_iter_loop",
-        "clippy::fallible_impl_from",
-        "clippy::filter_map_next",
-        "clippy::flat_map_option",
-        "clippy::float_arithmetic",
-        "clippy::float_cmp",
-        "clippy::float_cmp_const",
-        "clippy::format_push_string",
-        "clippy::get_unwrap",
-        "clippy::if_not_else",
-        "clippy::if_then_some_else_none",
-        "clippy::indexing_slicing",
-        "clippy::integer_division",
-        "clippy::implicit_clone",
-        "clippy::inconsistent_struct_constructor",
-        "clippy::inefficient_to_string",
-        "clippy::invalid_upcast_comparisons",
-        "clippy::items_after_statements",
-        "clippy::iter_on_empty_collections",
-        "clippy::iter_on_single_items",
-        "clippy::iter_with_drain",
-        "clippy::large_digit_groups",
-        "clippy::large_include_file",
-        "clippy::large_stack_arrays",
-        "clippy::large_types_passed_by_value",
-        "clippy::let_underscore_must_use",
-        "clippy::linkedlist",
-        "clippy::lossy_float_literal",
-        "clippy::manual_clamp",
-        "clippy::manual_ok_or",
-        "clippy::manual_string_new",
-        "clippy::many_single_char_names",
-        "clippy::map_err_ignore",
-        "clippy::map_unwrap_or",
-        "clippy::match_bool",
-        "clippy::match_on_vec_items",
-        "clippy::match_same_arms",
-        "clippy::match_wild_err_arm",
-        "clippy::match_wildcard_for_single_variants",
-        "clippy::maybe_infinite_iter",
-        "clippy::mismatching_type_param_order",
-        "clippy::mixed_read_write_in_expression",
-        "clippy::module_name_repetitions",
-        "clippy::multiple_crate_versions",
-        "clippy::multiple_inherent_impl",
-        "clippy::needless_collect",
-        "clippy::needless_continue",
-        "clippy::needless_for_each",
-        "clippy::needless_pass_by_value",
-        "clippy::no_effect_underscore_binding",
-        "clippy::nonstandard_macro_braces",
-        "clippy::option_if_let_else",
-        "clippy::option_option",
-        "clippy::or_fun_call",
-        "clippy::panic",
-        "clippy::panic_in_result_fn",
-        "clippy::partial_pub_fields",
-        "clippy::print_stderr",
-        "clippy::print_stdout",
-        "clippy::pub_use",
-        "clippy::range_minus_one",
-        "clippy::range_plus_one",
-        "clippy::redundant_clone",
-        "clippy::redundant_closure_for_method_calls",
-        "clippy::redundant_pub_crate",
-        "clippy::ref_binding_to_reference",
-        "clippy::ref_option_ref",
-        "clippy::rest_pat_in_fully_bound_structs",
-        "clippy::same_functions_in_if_condition",
-        "clippy::same_name_method",
-        "clippy::similar_names",
-        "clippy::string_slice",
-        "clippy::string_to_string",
-        "clippy::struct_excessive_bools",
-        "clippy::suspicious_operation_groupings",
-        "clippy::todo",
-        "clippy::too-many-lines",
-        "clippy::trait_duplication_in_bounds",
-        "clippy::trivial_regex",
-        "clippy::trivially_copy_pass_by_ref",
-        "clippy::try_err",
-        "clippy::type_repetition_in_bounds",
-        "clippy::unimplemented",
-        "clippy::uninlined_format_args",
-        "clippy::unnecessary_join",
-        "clippy::unnecessary_self_imports",
-        "clippy::unnecessary_wraps",
-        "clippy::unneeded_field_pattern",
-        "clippy::unnested_or_patterns",
-        "clippy::unreachable",
-        "clippy::unreadable_literal",
-        "clippy::unused_self",
-        "clippy::unwrap_in_result",
-        "clippy::unwrap_used",
-        "clippy::use_debug",
-        "clippy::use_self",
-        "clippy::useless_let_if_seq",
-        "clippy::wildcard_enum_match_arm",
+/// Executes every analysis which requires no arguments. Those which are excluded are:
+/// - `fix`, which modifies the workspace;
+/// - `publish-check`, `chainspec`, `metadata-diff` and `srtool`, which require arguments (or Docker);
+/// - `docs --coverage`, which requires a nightly toolchain;
+/// - `benchmarks --run` and `benchmarks --check-weights`, unless a node binary is configured, in which case the
+///   weights are checked;
+/// - `migrations`, unless the state to execute them against is configured.
+fn review(context: &Context) -> Result<(Vec<Finding>, Score), Error> {
+    let mut analyses: Vec<&dyn Analysis> = vec![
+        &Clippy,
+        &Fmt,
+        &Manifests,
+        &Features,
+        &Pallets,
+        &Xcm,
+        &Node,
+        &Docs,
+        &Tests,
+        &Coverage,
+        &Benchmarks,
+        &Build,
+        &Runtime,
+        &Audit,
+        &Vet,
+        &Licenses,
+        &Duplicates,
+        &Unsafe,
     ];
-
-    #[derive(Serialize, Deserialize)]
-    pub(crate) struct Match {
-        pub(crate) reason: String,
-        pub(crate) message: Option<Message>,
+    if context.config.benchmarks.binary.is_some() {
+        analyses.push(&Weights);
     }
-
-    #[derive(Serialize, Deserialize)]
-    pub(crate) struct Message {
-        pub(crate) code: Option<Code>,
-        pub(crate) level: String,
-        pub(crate) message: String,
-        pub(crate) spans: Vec<Span>,
-        pub(crate) children: Vec<Message>,
+    let migrations = &context.config.migrations;
+    if migrations.uri.is_some() || migrations.snapshot.is_some() {
+        analyses.push(&Migrations);
     }
-
-    #[derive(Serialize, Deserialize)]
-    pub(crate) struct Code {
-        pub(crate) code: String,
-    }
-
-    #[derive(Serialize, Deserialize)]
-    pub(crate) struct Span {
-        pub(crate) file_name: String,
-        pub(crate) line_start: u16,
-        pub(crate) column_start: u16,
-        pub(crate) line_end: u16,
-        pub(crate) column_end: u16,
-        pub(crate) text: Vec<Text>,
+    let mut findings = Vec::new();
+    for analysis in &analyses {
+        match analysis.run(context) {
+            Ok(analysis_findings) => findings.extend(analysis_findings),
+            // A failed analysis is reported as a finding, so that the remaining analyses still run
+            Err(e) => {
+                let category = analysis.category();
+                let finding = e.timed_out(category).unwrap_or_else(|e| {
+                    Finding::new(category, Severity::Error, e.to_string())
+                        .with_code("sbp::analysis_failed")
+                });
+                if let Some(finding) = context.config.apply(finding) {
+                    if context.format == Format::Text {
+                        finding.print(context);
+                    }
+                    findings.push(finding);
+                }
+            }
+        }
     }
 
-    #[derive(Serialize, Deserialize)]
-    pub(crate) struct Text {
-        pub(crate) text: String,
+    let mut categories: Vec<Category> = Vec::new();
+    for category in analyses.iter().map(|a| a.category()) {
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
     }
-}
-
-mod manifests {
-    use serde::{Deserialize, Serialize};
-
-    #[derive(Serialize, Deserialize)]
-    pub(crate) struct Metadata {
-        pub(crate) packages: Vec<Package>,
+    let score = Score::new(&context.config.score, &categories, &findings);
+    // The report is written by the tests, unless they could not be executed
+    if let Some(path) = &context.junit {
+        if let Ok(report) = fs::read_to_string(path) {
+            fs::write(path, junit::with_score(&report, &score)).map_err(|source| Error::Io {
+                path: path.clone(),
+                source,
+            })?;
+        }
     }
 
-    #[derive(Serialize, Deserialize)]
-    pub(crate) struct Package {
-        pub(crate) name: String,
-        pub(crate) manifest_path: String,
-        pub(crate) version: String,
-        pub(crate) license: Option<String>,
-        pub(crate) license_file: Option<String>,
-        pub(crate) description: Option<String>,
-        pub(crate) authors: Vec<String>,
-        pub(crate) repository: Option<String>,
-        pub(crate) categories: Vec<String>,
-        pub(crate) keywords: Vec<String>,
-        pub(crate) edition: String,
-        pub(crate) dependencies: Vec<Dependency>,
+    if context.format != Format::Text {
+        return Ok((findings, score));
     }
-
-    #[derive(Serialize, Deserialize)]
-    pub(crate) struct Dependency {
-        pub(crate) name: String,
-        pub(crate) source: Option<String>,
+    println!("{}", "Summary".bold());
+    for (category, category_score) in score.categories.iter().copied() {
+        let count = |severity| {
+            findings
+                .iter()
+                .filter(|f| f.category == category && f.severity == severity)
+                .count()
+        };
+        print!(
+            "  {}: {} {}, {} {}, score {}",
+            category,
+            count(Severity::Error),
+            "error(s)".red(),
+            count(Severity::Warning),
+            "warning(s)".yellow(),
+            category_score
+        );
+        match category {
+            Category::Fmt => {
+                let files: BTreeSet<_> = findings
+                    .iter()
+                    .filter(|f| f.category == category)
+                    .filter_map(|f| f.location.as_ref().map(|l| &l.file))
+                    .collect();
+                println!(" ({} file(s) unformatted)", files.len())
+            }
+            _ => println!(),
+        }
     }
+    println!("  {}: {}", "Score".bold(), score);
+    Ok((findings, score))
 }
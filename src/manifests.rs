@@ -0,0 +1,2048 @@
+use crate::{
+    features,
+    findings::{Category, Finding, Location, Severity},
+    git, github, link, pallets,
+    registry::{Published, Registry, Version},
+    releases, spdx, Analysis, Context, Error, Format,
+};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use syn::{punctuated::Punctuated, Token};
+use toml_edit::{ImDocument, Item, Key, TableLike};
+
+/// The names of the repositories of the Polkadot SDK (and those it superseded), whose crates are forked when sourced
+/// from a repository of the same name outside of the `paritytech` organisation.
+const SDK_REPOSITORIES: [&str; 4] = ["polkadot-sdk", "substrate", "cumulus", "polkadot"];
+
+/// Analyses manifest(s) for known issues, via `cargo metadata`.
+pub struct Manifests;
+
+/// The git repositories of known ecosystems, whose releases are expected to match.
+const ECOSYSTEMS: [(&str, &str); 5] = [
+    (
+        "polkadot-sdk",
+        "git+https://github.com/paritytech/polkadot-sdk",
+    ),
+    ("substrate", "git+https://github.com/paritytech/substrate"),
+    ("polkadot", "git+https://github.com/paritytech/polkadot"),
+    ("cumulus", "git+https://github.com/paritytech/cumulus"),
+    (
+        "orml",
+        "git+https://github.com/open-web3-stack/open-runtime-module-library",
+    ),
+];
+
+/// The sections expected within the README of a pallet, along with the headings accepted for each (in lowercase).
+const README_SECTIONS: [(&str, &[&str]); 3] = [
+    ("Overview", &["overview"]),
+    ("Interface", &["interface", "dispatchable"]),
+    ("License", &["license", "licence"]),
+];
+
+/// Licenses identified within license files, along with phrases (in lowercase, with whitespace collapsed) which are all
+/// contained within the license text. More specific licenses are listed first, as licenses (e.g. the GPL) reference
+/// others.
+const LICENSE_TEXTS: [(&str, &[&str]); 12] = [
+    ("AGPL-3.0", &["gnu affero general public license version 3"]),
+    ("LGPL-3.0", &["gnu lesser general public license version 3"]),
+    (
+        "LGPL-2.1",
+        &["gnu lesser general public license version 2.1"],
+    ),
+    ("GPL-3.0", &["gnu general public license version 3"]),
+    ("GPL-2.0", &["gnu general public license version 2"]),
+    ("Apache-2.0", &["apache license version 2.0"]),
+    ("MPL-2.0", &["mozilla public license version 2.0"]),
+    ("MIT", &["permission is hereby granted, free of charge"]),
+    (
+        "BSD-3-Clause",
+        &[
+            "redistribution and use in source and binary forms",
+            "neither the name",
+        ],
+    ),
+    (
+        "BSD-2-Clause",
+        &["redistribution and use in source and binary forms"],
+    ),
+    (
+        "ISC",
+        &["permission to use, copy, modify, and/or distribute this software for any purpose"],
+    ),
+    (
+        "Unlicense",
+        &["this is free and unencumbered software released into the public domain"],
+    ),
+];
+
+/// A git dependency on a known ecosystem.
+struct GitDependency {
+    package: String,
+    manifest_path: String,
+    name: String,
+    ecosystem: &'static str,
+    /// The branch or tag of the dependency.
+    reference: String,
+    /// The release corresponding to the reference, e.g. `0.9.43` for the `polkadot-v0.9.43` or `release-v0.9.43`
+    /// branches.
+    release: String,
+    line: u16,
+}
+
+/// A dependency declared by a package, rather than inherited from the workspace.
+struct Declaration {
+    package: String,
+    manifest_path: String,
+    name: String,
+    /// The version requirement (or git repository) of the dependency.
+    requirement: String,
+    line: u16,
+}
+
+/// The state shared by the checks of the packages of the workspace: the responses of remote services, which are cached
+/// across packages, along with the dependencies collected for the checks across the workspace.
+#[derive(Default)]
+struct State {
+    /// The crates.io index, unless offline or unavailable.
+    registry: Option<Registry>,
+    /// The Polkadot SDK releases published to crates.io, only queried once required.
+    sdk: Option<releases::Published>,
+    /// The current release branches, only determined once required.
+    branches: Option<Vec<String>>,
+    /// The git remotes of the workspace, when online.
+    remotes: Vec<String>,
+    /// The HTTP status of each URL declared within the manifests, or `None` when unreachable.
+    statuses: HashMap<String, Option<u16>>,
+    /// The divergence of each fork from upstream, keyed by repository and reference.
+    forks: HashMap<(String, Option<String>), Option<Divergence>>,
+    /// Whether each git revision exists upstream, keyed by repository and revision.
+    revisions: HashMap<(String, String), bool>,
+    git_dependencies: Vec<GitDependency>,
+    declarations: Vec<Declaration>,
+}
+
+impl Analysis for Manifests {
+    fn category(&self) -> Category {
+        Category::Manifests
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Analysing manifest(s) via metadata...");
+        }
+
+        let mut findings = Vec::new();
+        let mut report = |checked: Vec<Finding>, indent: bool| {
+            for finding in checked {
+                if let Some(finding) = context.config.apply(finding) {
+                    match (text, indent) {
+                        (true, true) => println!("  {} {}", finding.severity, finding.message),
+                        (true, false) => finding.print(context),
+                        (false, _) => {}
+                    }
+                    findings.push(finding)
+                }
+            }
+        };
+        let metadata = metadata(context)?;
+
+        let root = context.root.join("Cargo.toml");
+        let workspace = ImDocument::parse(fs::read_to_string(&root).unwrap_or_default()).ok();
+        let licenses = license_files(&context.root);
+        report(root_manifest(context, workspace.as_ref(), &licenses), false);
+
+        let online = context.online && !context.offline;
+        let mut state = State {
+            registry: (!context.offline).then(Registry::default),
+            remotes: match online {
+                true => git::remotes(&context.root),
+                false => Vec::new(),
+            },
+            ..State::default()
+        };
+        let target_directory = Path::new(&metadata.target_directory);
+        for package in metadata
+            .packages
+            .iter()
+            .filter(|p| context.selected(&p.name))
+        {
+            if text {
+                println!(
+                    "{}",
+                    link(&package.name, &format!("file:///{}", package.manifest_path)).cyan()
+                );
+            }
+            let manifest = fs::read_to_string(&package.manifest_path).unwrap_or_default();
+            report(common_metadata(context, package, &manifest), true);
+            if online {
+                report(urls(context, package, &manifest, &mut state), true);
+            }
+            report(license(context, package, &manifest, &licenses), true);
+            report(edition(context, package, &manifest), true);
+            report(
+                forbid_unsafe(context, package, &manifest, workspace.as_deref()),
+                true,
+            );
+            report(
+                release_branches(context, package, &manifest, target_directory, &mut state),
+                true,
+            );
+            report(source_policy(context, package, &manifest), true);
+            report(sdk_forks(context, package, &manifest, &mut state), true);
+            report(
+                pinned_revisions(context, package, &manifest, &mut state),
+                true,
+            );
+            report(
+                dependency_style(context, package, &manifest, &mut state),
+                true,
+            );
+            report(
+                outdated_versions(context, package, &manifest, &mut state),
+                true,
+            );
+            report(rust_version(context, package, &manifest)?, true);
+            report(readme(context, package, &manifest), true);
+        }
+
+        report(mixed_releases(context, &state.git_dependencies), false);
+        report(
+            uninherited_dependencies(context, &state.declarations),
+            false,
+        );
+        Ok(findings)
+    }
+}
+
+/// Checks the root manifest of the workspace: that it uses version 2 (or later) of the feature resolver, that the
+/// workspace includes the text of its license(s) and that crates of the Polkadot SDK are not patched.
+fn root_manifest(
+    context: &Context,
+    workspace: Option<&ImDocument<String>>,
+    licenses: &[PathBuf],
+) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    if let Some(line) = workspace.and_then(outdated_resolver) {
+        issues.push((
+            "sbp::outdated_resolver",
+            "workspace does not use resolver = \"2\"".to_string(),
+            line,
+        ));
+    }
+    if licenses.is_empty() {
+        issues.push((
+            "sbp::missing_license_file",
+            "workspace has no license file (e.g. LICENSE)".into(),
+            1,
+        ));
+    }
+    issues.extend(workspace.map(patched_sdk_crates).unwrap_or_default());
+    let manifest = context.root.join("Cargo.toml");
+    issues
+        .into_iter()
+        .map(|(code, message, line)| {
+            let mut finding =
+                Finding::new(Category::Manifests, Severity::Warning, message).with_code(code);
+            finding.location = Some(location(context, &manifest.to_string_lossy(), line));
+            finding
+        })
+        .collect()
+}
+
+/// Checks for crates of the Polkadot SDK patched across the workspace, which hides forks from reviewers. Returns the
+/// code, message and line of each issue.
+fn patched_sdk_crates(workspace: &ImDocument<String>) -> Vec<(&'static str, String, u16)> {
+    let mut issues = Vec::new();
+    let Some(patches) = workspace.get("patch").and_then(Item::as_table_like) else {
+        return issues;
+    };
+    for (registry, patch) in patches
+        .iter()
+        .filter_map(|(registry, patch)| Some((registry, patch.as_table_like()?)))
+    {
+        for (name, value, line) in entries(workspace.raw(), patch)
+            .into_iter()
+            .filter(|(name, ..)| features::SDK_PREFIXES.iter().any(|p| name.starts_with(p)))
+        {
+            let string = |key| value.get(key).and_then(Item::as_str);
+            let source = match (string("git"), string("path")) {
+                (Some(git), _) => format!("git repository {}", git),
+                (None, Some(path)) => format!("path '{}'", path),
+                (None, None) => "another version".into(),
+            };
+            issues.push((
+                "sbp::patched_sdk_crate",
+                format!(
+                    "'{}' is patched within [patch.{}] with {}, replacing it throughout the dependency graph",
+                    name, registry, source
+                ),
+                line,
+            ));
+        }
+    }
+    issues
+}
+
+/// Checks for common metadata: https://rust-lang.github.io/api-guidelines/documentation.html#cargotoml-includes-all-common-metadata-c-metadata
+fn common_metadata(context: &Context, package: &Package, manifest: &str) -> Vec<Finding> {
+    let authors = (!package.authors.is_empty()).then(|| package.authors.join(", "));
+    let mut findings = Vec::new();
+    for (field, value) in [
+        ("authors", authors.as_ref()),
+        ("description", package.description.as_ref()),
+        ("license", package.license.as_ref()),
+        ("repository", package.repository.as_ref()),
+        ("readme", package.readme.as_ref()),
+    ] {
+        match value {
+            None => findings.push(finding(
+                context,
+                package,
+                Severity::Warning,
+                &format!("sbp::missing_{}", field),
+                format!("no '{}' found", field),
+                line_of(manifest, "[package]"),
+            )),
+            Some(value) if context.format == Format::Text => println!("  {}: {}", field, value),
+            Some(_) => {}
+        }
+    }
+    findings
+}
+
+/// Checks the URLs of the package respond, and that the repository is that of the workspace.
+fn urls(context: &Context, package: &Package, manifest: &str, state: &mut State) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (field, url) in [
+        ("repository", &package.repository),
+        ("homepage", &package.homepage),
+        ("documentation", &package.documentation),
+    ] {
+        let Some(url) = url else {
+            continue;
+        };
+        let status = *state
+            .statuses
+            .entry(url.clone())
+            .or_insert_with(|| status(url));
+        if let Some(reason) = match status {
+            None => Some("could not be reached".to_string()),
+            Some(status) if status >= 400 => Some(format!("responded with HTTP {}", status)),
+            Some(_) => None,
+        } {
+            findings.push(finding(
+                context,
+                package,
+                Severity::Warning,
+                "sbp::dead_link",
+                format!("'{}' ({}) {}", field, url, reason),
+                line_of(manifest, field),
+            ));
+        }
+    }
+    let remotes = &state.remotes;
+    if let Some(repository) = package.repository.as_ref().filter(|r| {
+        !remotes.is_empty()
+            && !remotes.iter().any(|remote| {
+                let (repository, remote) = (normalize(r), normalize(remote));
+                repository == remote || repository.starts_with(&format!("{}/", remote))
+            })
+    }) {
+        findings.push(finding(
+            context,
+            package,
+            Severity::Warning,
+            "sbp::mismatched_repository",
+            format!(
+                "'repository' ({}) does not match the git remote(s) of the workspace: {}",
+                repository,
+                remotes.join(", ")
+            ),
+            line_of(manifest, "repository"),
+        ));
+    }
+    findings
+}
+
+/// Checks the license is a valid SPDX expression, as required by crates.io, and that the license files of the package
+/// (or otherwise those of the workspace) correspond to it.
+fn license(
+    context: &Context,
+    package: &Package,
+    manifest: &str,
+    workspace_licenses: &[PathBuf],
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut report = |severity, code: &str, message: String, line: u16| {
+        findings.push(finding(context, package, severity, code, message, line))
+    };
+    let directory = Path::new(&package.manifest_path)
+        .parent()
+        .unwrap_or(Path::new(""));
+    let mut files = license_files(directory);
+    match (&package.license, &package.license_file) {
+        (Some(_), Some(_)) => report(
+            Severity::Warning,
+            "sbp::conflicting_license",
+            "both 'license' and 'license-file' are declared, only one is required".into(),
+            line_of(manifest, "license-file"),
+        ),
+        (_, Some(license_file)) if !directory.join(license_file).is_file() => report(
+            Severity::Error,
+            "sbp::missing_license_file",
+            format!("license-file '{}' does not exist", license_file),
+            line_of(manifest, "license-file"),
+        ),
+        _ => {}
+    }
+    // Published packages are expected to include the text of their license(s) within the crate
+    let published = package.publish.as_ref().is_none_or(|r| !r.is_empty());
+    if files.is_empty() && package.license_file.is_none() && directory != context.root {
+        if published {
+            report(
+                Severity::Warning,
+                "sbp::missing_license_file",
+                "published package has no license file (e.g. LICENSE), so the license text is not included within \
+                 the crate"
+                    .into(),
+                line_of(manifest, "license"),
+            );
+        }
+        files = workspace_licenses.to_vec();
+    }
+    let expression = match &package.license {
+        Some(license) => match spdx::validate(license) {
+            Ok(expression) => Some(expression),
+            Err(e) => {
+                report(
+                    Severity::Error,
+                    "sbp::invalid_license",
+                    format!(
+                        "'license' ({}) is not a valid SPDX expression: {}",
+                        license, e
+                    ),
+                    line_of(manifest, "license"),
+                );
+                None
+            }
+        },
+        None => None,
+    };
+    if let Some(declared) = expression {
+        let declared = spdx::licenses(&declared);
+        for (file, license) in files.iter().filter_map(|f| {
+            let license = identify(&fs::read_to_string(f).ok()?)?;
+            Some((f, license))
+        }) {
+            if !declares(&declared, license) {
+                report(
+                    Severity::Warning,
+                    "sbp::mismatched_license_file",
+                    format!(
+                        "license file '{}' contains the {} license, which is not declared by 'license' ({})",
+                        relative(&context.root, &file.to_string_lossy()),
+                        license,
+                        declared.join(", ")
+                    ),
+                    line_of(manifest, "license"),
+                );
+            }
+        }
+    }
+    findings
+}
+
+/// Checks the edition is current.
+fn edition(context: &Context, package: &Package, manifest: &str) -> Vec<Finding> {
+    if context.format == Format::Text {
+        println!("  edition: {}", package.edition);
+    }
+    if !package.edition.parse::<u16>().is_ok_and(|e| e < 2021) {
+        return Vec::new();
+    }
+    vec![finding(
+        context,
+        package,
+        Severity::Warning,
+        "sbp::outdated_edition",
+        format!("edition {} is older than 2021", package.edition),
+        match manifest.contains("edition") {
+            true => line_of(manifest, "edition"),
+            false => line_of(manifest, "[package]"),
+        },
+    )]
+}
+
+/// Checks runtime crates forbid unsafe code, as undefined behaviour within the runtime can compromise the chain.
+fn forbid_unsafe(
+    context: &Context,
+    package: &Package,
+    manifest: &str,
+    workspace: Option<&toml_edit::Table>,
+) -> Vec<Finding> {
+    let Some(library) = package
+        .targets
+        .iter()
+        .find(|t| t.kind.iter().any(|k| k == "lib"))
+        .filter(|_| package.features.contains_key("std"))
+    else {
+        return Vec::new();
+    };
+    if forbids_unsafe(&library.src_path, manifest, workspace) {
+        return Vec::new();
+    }
+    vec![finding(
+        context,
+        package,
+        Severity::Warning,
+        "sbp::missing_forbid_unsafe",
+        "runtime crate does not forbid unsafe code, via `#![forbid(unsafe_code)]` or `unsafe_code = \"forbid\"` \
+         within [lints.rust]"
+            .into(),
+        line_of(manifest, "[package]"),
+    )]
+}
+
+/// Checks git dependencies on the Polkadot SDK (or its former repositories) use a current release branch, collecting
+/// the releases of all known ecosystems, which are checked for consistency across the workspace.
+fn release_branches(
+    context: &Context,
+    package: &Package,
+    manifest: &str,
+    target_directory: &Path,
+    state: &mut State,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (name, source, ecosystem) in package.dependencies.iter().filter_map(|d| {
+        let source = d.source.as_ref()?;
+        Some((&d.name, source, ecosystem(source)?))
+    }) {
+        let Ok(url) = url::Url::parse(&source[4..]) else {
+            continue;
+        };
+        if let Some((_, reference)) = url
+            .query_pairs()
+            .find(|(parameter, _)| parameter == "branch" || parameter == "tag")
+        {
+            state.git_dependencies.push(GitDependency {
+                package: package.name.clone(),
+                manifest_path: package.manifest_path.clone(),
+                name: name.clone(),
+                ecosystem,
+                release: release(&reference),
+                reference: reference.into_owned(),
+                line: line_of(manifest, name),
+            });
+        }
+        if ecosystem == "orml" {
+            continue;
+        }
+        let branches = state.branches.get_or_insert_with(|| {
+            let mut branches = releases::branches(
+                target_directory,
+                context.config.dependencies.releases,
+                context.offline,
+            );
+            branches.extend(context.config.dependencies.branches.iter().cloned());
+            if branches.is_empty() {
+                eprintln!(
+                    "{} unable to determine the latest release branches, skipping release branch checks",
+                    Severity::Warning
+                );
+            }
+            branches
+        });
+        for (_, value) in url
+            .query_pairs()
+            .filter(|(parameter, _)| parameter == "branch")
+        {
+            if !branches.is_empty() && !branches.iter().any(|b| b == value.as_ref()) {
+                findings.push(finding(
+                    context,
+                    package,
+                    Severity::Warning,
+                    "sbp::outdated_dependency",
+                    format!(
+                        "{} for '{}' is out of date, latest: {}",
+                        value,
+                        name,
+                        branches.join(", ")
+                    ),
+                    line_of(manifest, name),
+                ))
+            }
+        }
+    }
+    findings
+}
+
+/// Checks dependencies are sourced from the allowed registries and git hosts/organisations, rather than forks.
+fn source_policy(context: &Context, package: &Package, manifest: &str) -> Vec<Finding> {
+    let policy = &context.config.dependencies;
+    let mut findings = Vec::new();
+    for dependency in &package.dependencies {
+        let Some(source) = &dependency.source else {
+            continue;
+        };
+        let disallowed = match source.split_once('+') {
+            Some(("registry" | "sparse", registry)) => (!policy
+                .registries
+                .iter()
+                .any(|r| r.trim_end_matches('/') == registry.trim_end_matches('/')))
+            .then(|| format!("registry {}", registry)),
+            Some(("git", repository)) => {
+                let repository = repository.split(['?', '#']).next().unwrap_or(repository);
+                let normalized = normalize(repository);
+                (!policy.git.iter().any(|allowed| {
+                    let allowed = normalize(allowed);
+                    normalized == allowed || normalized.starts_with(&format!("{}/", allowed))
+                }))
+                .then(|| format!("git repository {}", repository))
+            }
+            _ => None,
+        };
+        if let Some(disallowed) = disallowed {
+            findings.push(finding(
+                context,
+                package,
+                Severity::Warning,
+                "sbp::disallowed_source",
+                format!(
+                    "'{}' is sourced from {}, which is not allowed by the dependency source policy (e.g. a fork or \
+                     unknown host)",
+                    dependency.name, disallowed
+                ),
+                line_of(manifest, &dependency.name),
+            ));
+        }
+    }
+    findings
+}
+
+/// Checks for forked or vendored crates of the Polkadot SDK, which diverge from upstream.
+fn sdk_forks(
+    context: &Context,
+    package: &Package,
+    manifest: &str,
+    state: &mut State,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for dependency in package
+        .dependencies
+        .iter()
+        .filter(|d| features::SDK_PREFIXES.iter().any(|p| d.name.starts_with(p)))
+    {
+        let line = line_of(manifest, &dependency.name);
+        if let Some(url) = dependency
+            .source
+            .as_ref()
+            .and_then(|s| s.strip_prefix("git+"))
+            .and_then(|s| url::Url::parse(s).ok())
+        {
+            let repository = url
+                .path()
+                .trim_matches('/')
+                .trim_end_matches(".git")
+                .to_string();
+            let github = url.host_str() == Some("github.com");
+            let Some((owner, name)) = repository.split_once('/') else {
+                continue;
+            };
+            if !SDK_REPOSITORIES.contains(&name.to_lowercase().as_str())
+                || (github && owner.eq_ignore_ascii_case("paritytech"))
+            {
+                continue;
+            }
+            let mut message = format!(
+                "'{}' is sourced from a fork of the Polkadot SDK: {}",
+                dependency.name,
+                url.as_str().split(['?', '#']).next().unwrap_or_default()
+            );
+            if github && context.online && !context.offline {
+                let reference = ["rev", "tag", "branch"].iter().find_map(|name| {
+                    url.query_pairs()
+                        .find(|(parameter, _)| parameter == name)
+                        .map(|(_, value)| value.into_owned())
+                });
+                let divergence = state
+                    .forks
+                    .entry((repository.clone(), reference.clone()))
+                    .or_insert_with(|| divergence(&repository, reference.as_deref()));
+                if let Some(divergence) = divergence {
+                    message.push_str(&format!(
+                        ", which is {} commit(s) ahead of and {} behind {}",
+                        divergence.ahead, divergence.behind, divergence.upstream
+                    ));
+                }
+            }
+            findings.push(finding(
+                context,
+                package,
+                Severity::Warning,
+                "sbp::forked_sdk_crate",
+                message,
+                line,
+            ));
+        } else if let Some(path) = &dependency.path {
+            if vendored(&Path::new(path).join("Cargo.toml")) {
+                findings.push(finding(
+                    context,
+                    package,
+                    Severity::Warning,
+                    "sbp::vendored_sdk_crate",
+                    format!(
+                        "'{}' is a vendored copy of a Polkadot SDK crate at '{}', so may diverge from upstream",
+                        dependency.name,
+                        relative(&context.root, path)
+                    ),
+                    line,
+                ));
+            }
+        }
+    }
+    findings
+}
+
+/// Checks git dependencies are pinned to a revision, so that builds are reproducible, optionally verifying that the
+/// revision exists upstream.
+fn pinned_revisions(
+    context: &Context,
+    package: &Package,
+    manifest: &str,
+    state: &mut State,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for dependency in &package.dependencies {
+        let Some(url) = dependency
+            .source
+            .as_ref()
+            .and_then(|s| s.strip_prefix("git+"))
+            .and_then(|s| url::Url::parse(s).ok())
+        else {
+            continue;
+        };
+        let line = line_of(manifest, &dependency.name);
+        let parameter = |name: &str| {
+            url.query_pairs()
+                .find(|(parameter, _)| parameter == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        let Some(revision) = parameter("rev") else {
+            if parameter("tag").is_none() {
+                findings.push(finding(
+                    context,
+                    package,
+                    Severity::Warning,
+                    "sbp::unpinned_git_dependency",
+                    format!(
+                        "'{}' tracks {} without a pinned 'rev'",
+                        dependency.name,
+                        parameter("branch")
+                            .map_or("the default branch".into(), |b| format!("branch '{}'", b))
+                    ),
+                    line,
+                ));
+            }
+            continue;
+        };
+        if !context.config.dependencies.verify_revisions || context.offline {
+            continue;
+        }
+        // Revisions can only be verified for repositories hosted on GitHub
+        let repository = url
+            .path()
+            .trim_matches('/')
+            .trim_end_matches(".git")
+            .to_string();
+        if url.host_str() != Some("github.com") {
+            continue;
+        }
+        let exists = state
+            .revisions
+            .entry((repository.clone(), revision.clone()))
+            .or_insert_with(|| {
+                !matches!(
+                    github::get(&format!("/repos/{}/commits/{}", repository, revision)),
+                    Some((404 | 422, _))
+                )
+            });
+        if !*exists {
+            findings.push(finding(
+                context,
+                package,
+                Severity::Error,
+                "sbp::missing_git_revision",
+                format!(
+                    "revision '{}' of '{}' does not exist within {}",
+                    revision, dependency.name, repository
+                ),
+                line,
+            ));
+        }
+    }
+    findings
+}
+
+/// Checks the style of dependency declarations, collecting the dependencies declared rather than inherited, which are
+/// checked across the workspace.
+fn dependency_style(
+    context: &Context,
+    package: &Package,
+    manifest: &str,
+    state: &mut State,
+) -> Vec<Finding> {
+    let Ok(document) = ImDocument::parse(manifest) else {
+        return Vec::new();
+    };
+    for (_, dependencies) in sections(&document)
+        .into_iter()
+        .filter(|(section, _)| section != "workspace.dependencies")
+    {
+        for (name, value, line) in entries(manifest, dependencies) {
+            if let Some(requirement) = declared(value) {
+                state.declarations.push(Declaration {
+                    package: package.name.clone(),
+                    manifest_path: package.manifest_path.clone(),
+                    name: name.into(),
+                    requirement,
+                    line,
+                });
+            }
+        }
+    }
+    style(manifest, &document)
+        .into_iter()
+        .map(|(code, message, line)| {
+            finding(context, package, Severity::Warning, code, message, line)
+        })
+        .collect()
+}
+
+/// Checks registry dependencies against the latest versions published to crates.io, where crates of the Polkadot SDK
+/// are expected to be from one of the latest releases, and from the same release.
+fn outdated_versions(
+    context: &Context,
+    package: &Package,
+    manifest: &str,
+    state: &mut State,
+) -> Vec<Finding> {
+    const CRATES_IO: &str = "registry+https://github.com/rust-lang/crates.io-index";
+    let mut findings = Vec::new();
+    let Some(registry) = &mut state.registry else {
+        return findings;
+    };
+    let mut unavailable = None;
+    let mut releases = Vec::new();
+    for dependency in package
+        .dependencies
+        .iter()
+        .filter(|d| d.source.as_deref() == Some(CRATES_IO))
+    {
+        let Some(current) = Version::minimum(&dependency.req) else {
+            continue;
+        };
+        let sdk = match &state.sdk {
+            Some(sdk) => sdk,
+            None => match releases::Published::query(registry) {
+                Ok(published) => state.sdk.insert(published),
+                Err(e) => {
+                    unavailable = Some(e);
+                    break;
+                }
+            },
+        };
+
+        if sdk.contains(&dependency.name) {
+            let compatible = sdk.compatible(&dependency.name, &current);
+            let count = context.config.dependencies.releases.max(1);
+            if !compatible.iter().any(|i| *i < count) {
+                if let Some(latest) = sdk.release(0) {
+                    findings.push(finding(
+                        context,
+                        package,
+                        Severity::Warning,
+                        "sbp::outdated_dependency",
+                        format!(
+                            "'{}' ({}) is {}out of date, latest: {} ({})",
+                            dependency.name,
+                            dependency.req,
+                            compatible
+                                .first()
+                                .and_then(|i| sdk.release(*i))
+                                .map_or("".into(), |r| format!("from {}, which is ", r.name)),
+                            latest.name,
+                            sdk.version(0, &dependency.name)
+                                .map_or("-".into(), |v| v.to_string())
+                        ),
+                        line_of(manifest, &dependency.name),
+                    ));
+                }
+            }
+            releases.push((dependency, compatible));
+            continue;
+        }
+
+        let versions = match registry.versions(&dependency.name) {
+            Ok(versions) => versions,
+            Err(e) => {
+                unavailable = Some(e);
+                break;
+            }
+        };
+        let Some((severity, behind, kind, latest)) = outdated(&current, versions) else {
+            continue;
+        };
+        findings.push(finding(
+            context,
+            package,
+            severity,
+            "sbp::outdated_version",
+            format!(
+                "'{}' ({}) is {} {} version(s) behind the latest version ({})",
+                dependency.name, dependency.req, behind, kind, latest
+            ),
+            line_of(manifest, &dependency.name),
+        ));
+    }
+
+    if releases.len() > 1
+        && !releases
+            .iter()
+            .flat_map(|(_, compatible)| compatible)
+            .any(|i| releases.iter().all(|(_, c)| c.contains(i)))
+    {
+        if let Some(sdk) = &state.sdk {
+            let crates: Vec<_> = releases
+                .iter()
+                .map(|(dependency, compatible)| {
+                    format!(
+                        "{} ({})",
+                        dependency.name,
+                        compatible
+                            .first()
+                            .and_then(|i| sdk.release(*i))
+                            .map_or("unknown", |r| r.name.as_str())
+                    )
+                })
+                .collect();
+            findings.push(finding(
+                context,
+                package,
+                Severity::Warning,
+                "sbp::mixed_releases",
+                format!(
+                    "Polkadot SDK crates are from mixed releases: {}",
+                    crates.join(", ")
+                ),
+                line_of(manifest, &releases[0].0.name),
+            ));
+        }
+    }
+    if let Some(e) = unavailable {
+        eprintln!(
+            "{} unable to query the crates.io index, use --offline to skip checking for outdated versions: {}",
+            Severity::Warning,
+            e
+        );
+        state.registry = None;
+    }
+    findings
+}
+
+/// Checks the minimum supported rust version, optionally verifying it by compiling with the toolchain.
+fn rust_version(
+    context: &Context,
+    package: &Package,
+    manifest: &str,
+) -> Result<Vec<Finding>, Error> {
+    let text = context.format == Format::Text;
+    let Some(rust_version) = &package.rust_version else {
+        return Ok(vec![finding(
+            context,
+            package,
+            Severity::Warning,
+            "sbp::missing_rust_version",
+            "no 'rust-version' found".into(),
+            line_of(manifest, "[package]"),
+        )]);
+    };
+    if text {
+        println!("  rust-version: {}", rust_version);
+    }
+    if !context.verify_msrv {
+        return Ok(Vec::new());
+    }
+    let line = line_of(manifest, "rust-version");
+    let toolchain = format!("+{}", rust_version);
+    let installed = Command::new("cargo")
+        .args([&toolchain, "--version"])
+        .env("RUSTUP_AUTO_INSTALL", "0")
+        .current_dir(&context.root)
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if !installed {
+        return Ok(vec![finding(
+            context,
+            package,
+            Severity::Warning,
+            "sbp::unverified_rust_version",
+            format!(
+                "unable to verify rust-version {0}, as the toolchain is not installed (run `rustup toolchain install {0}`)",
+                rust_version
+            ),
+            line,
+        )]);
+    }
+    if text {
+        println!(
+            "  verifying rust-version via `cargo {} check`...",
+            toolchain
+        );
+    }
+    let mut command = Command::new("cargo");
+    command
+        .args([&toolchain, "check", "--manifest-path"])
+        .arg(&package.manifest_path)
+        .env("RUSTUP_AUTO_INSTALL", "0")
+        .current_dir(&context.root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let output = context
+        .output(&mut command, "manifests")
+        .map_err(|e| Error::command("cargo check", e))?;
+    if output.status.success() {
+        return Ok(Vec::new());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok(vec![finding(
+        context,
+        package,
+        Severity::Error,
+        "sbp::msrv_check_failed",
+        match stderr.lines().find(|l| l.starts_with("error")) {
+            Some(error) => format!(
+                "does not compile with rust-version {}: {}",
+                rust_version, error
+            ),
+            None => format!("does not compile with rust-version {}", rust_version),
+        },
+        line,
+    )])
+}
+
+/// Checks the README exists, where that of a pallet is expected to describe its interface and license.
+fn readme(context: &Context, package: &Package, manifest: &str) -> Vec<Finding> {
+    let Some(readme) = &package.readme else {
+        return Vec::new();
+    };
+    let directory = Path::new(&package.manifest_path)
+        .parent()
+        .unwrap_or(Path::new(""));
+    match fs::read_to_string(directory.join(readme)) {
+        Err(_) => vec![finding(
+            context,
+            package,
+            Severity::Error,
+            "sbp::missing_readme",
+            format!("readme '{}' does not exist", readme),
+            line_of(manifest, "readme"),
+        )],
+        Ok(content) if pallets::declares_pallet(&pallets::sources(directory, &context.root)) => {
+            let missing = missing_sections(&content);
+            if missing.is_empty() {
+                return Vec::new();
+            }
+            vec![finding(
+                context,
+                package,
+                Severity::Warning,
+                "sbp::incomplete_readme",
+                format!(
+                    "pallet readme '{}' has no {} section(s)",
+                    readme,
+                    missing.join(", ")
+                ),
+                line_of(manifest, "readme"),
+            )]
+        }
+        Ok(_) => Vec::new(),
+    }
+}
+
+/// Checks the releases of git dependencies on known ecosystems match, where the predominant release is expected.
+fn mixed_releases(context: &Context, git_dependencies: &[GitDependency]) -> Vec<Finding> {
+    let mut releases: Vec<(&str, usize)> = Vec::new();
+    for dependency in git_dependencies {
+        match releases.iter_mut().find(|(r, _)| *r == dependency.release) {
+            Some((_, count)) => *count += 1,
+            None => releases.push((&dependency.release, 1)),
+        }
+    }
+    let Some((predominant, _)) = releases
+        .iter()
+        .max_by_key(|(_, count)| *count)
+        .filter(|_| releases.len() > 1)
+    else {
+        return Vec::new();
+    };
+    let mut packages: Vec<&str> = git_dependencies
+        .iter()
+        .map(|d| d.package.as_str())
+        .collect();
+    packages.dedup();
+    let mut findings = Vec::new();
+    for package in packages {
+        let offending: Vec<_> = git_dependencies
+            .iter()
+            .filter(|d| d.package == package && d.release != *predominant)
+            .collect();
+        let Some(first) = offending.first() else {
+            continue;
+        };
+        let mut finding = Finding::new(
+            Category::Manifests,
+            Severity::Error,
+            format!(
+                "{} dependency(s) from mixed releases, using {} whereas most dependencies use {}",
+                offending.len(),
+                offending
+                    .iter()
+                    .map(|d| d.release.as_str())
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                predominant
+            ),
+        )
+        .with_code("sbp::mixed_releases")
+        .with_package(package);
+        finding.location = Some(location(context, &first.manifest_path, first.line));
+        finding.help = offending
+            .iter()
+            .map(|d| format!("'{}' uses {} {}", d.name, d.ecosystem, d.reference))
+            .collect();
+        findings.push(finding);
+    }
+    findings
+}
+
+/// Checks dependencies declared by multiple members are inherited from the workspace, avoiding version drift.
+fn uninherited_dependencies(context: &Context, declarations: &[Declaration]) -> Vec<Finding> {
+    let mut dependencies: BTreeMap<&str, Vec<&Declaration>> = BTreeMap::new();
+    for declaration in declarations {
+        dependencies
+            .entry(&declaration.name)
+            .or_default()
+            .push(declaration);
+    }
+    let mut findings = Vec::new();
+    for (name, declarations) in dependencies {
+        let packages: BTreeSet<_> = declarations.iter().map(|d| &d.package).collect();
+        if packages.len() < 2 {
+            continue;
+        }
+        let requirements: BTreeSet<_> = declarations.iter().map(|d| &d.requirement).collect();
+        let mut finding = Finding::new(
+            Category::Manifests,
+            Severity::Warning,
+            format!(
+                "'{}' is declared by {} members{}, rather than inherited from [workspace.dependencies]",
+                name,
+                packages.len(),
+                match requirements.len() {
+                    1 => String::new(),
+                    _ => format!(
+                        " with differing versions ({})",
+                        requirements
+                            .iter()
+                            .map(|r| r.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                }
+            ),
+        )
+        .with_code("sbp::uninherited_dependency");
+        let first = declarations[0];
+        finding.location = Some(location(context, &first.manifest_path, first.line));
+        finding.help = declarations
+            .iter()
+            .map(|d| format!("{} uses '{}'", d.package, d.requirement))
+            .collect();
+        finding
+            .help
+            .push(format!("use `{} = {{ workspace = true }}`", name));
+        findings.push(finding);
+    }
+    findings
+}
+
+/// Returns a finding of the package, located at the line of its manifest.
+fn finding(
+    context: &Context,
+    package: &Package,
+    severity: Severity,
+    code: &str,
+    message: String,
+    line: u16,
+) -> Finding {
+    let mut finding = Finding::new(Category::Manifests, severity, message)
+        .with_code(code)
+        .with_package(&package.name);
+    finding.location = Some(location(context, &package.manifest_path, line));
+    finding
+}
+
+/// Returns the location of the line of the manifest, relative to the workspace root.
+fn location(context: &Context, manifest_path: &str, line: u16) -> Location {
+    Location {
+        file: relative(&context.root, manifest_path),
+        line,
+        column: 1,
+        line_end: line,
+        column_end: 1,
+    }
+}
+
+/// Checks whether the root manifest of a workspace uses a resolver older than version 2, either explicitly or by
+/// default (for virtual workspaces and packages before the 2021 edition). Returns the line of the declaration, if so.
+fn outdated_resolver(manifest: &ImDocument<String>) -> Option<u16> {
+    let string = |table: &dyn TableLike, key| table.get(key)?.as_str().map(String::from);
+    let line =
+        |table: &dyn TableLike, key| line_at(manifest.raw(), table.key(key).and_then(Key::span));
+    let workspace = manifest.get("workspace").and_then(Item::as_table_like);
+    let package = manifest.get("package").and_then(Item::as_table_like);
+    if let Some((table, resolver)) = [workspace, package]
+        .into_iter()
+        .flatten()
+        .find_map(|t| Some((t, string(t, "resolver")?)))
+    {
+        return (resolver == "1").then(|| line(table, "resolver"));
+    }
+    match package {
+        // The default resolver of a package depends on its edition
+        Some(package) => match package.get("edition") {
+            // The edition may be inherited from the workspace
+            Some(edition) if edition.is_table_like() => workspace
+                .and_then(|w| w.get("package")?.as_table_like())
+                .and_then(|p| string(p, "edition")),
+            _ => string(package, "edition"),
+        }
+        .is_none_or(|e| e.parse::<u16>().is_ok_and(|e| e < 2021))
+        .then(|| line(manifest.as_table(), "package")),
+        None => workspace.map(|_| line(manifest.as_table(), "workspace")),
+    }
+}
+
+/// Checks whether a package forbids unsafe code, either via an inner attribute at the root of its library or via its
+/// `[lints]` table, which may be inherited from the workspace.
+fn forbids_unsafe(library: &str, manifest: &str, workspace: Option<&toml_edit::Table>) -> bool {
+    // The level of a lint is either a string or the `level` of a table
+    let forbidden = |lints: Option<&Item>| {
+        lints
+            .and_then(|l| l.get("rust")?.get("unsafe_code"))
+            .and_then(|lint| lint.as_str().or_else(|| lint.get("level")?.as_str()))
+            == Some("forbid")
+    };
+    let manifest = ImDocument::parse(manifest).ok();
+    let lints = manifest.as_ref().and_then(|m| m.get("lints"));
+    let inherited = lints.and_then(|l| l.get("workspace")?.as_bool()) == Some(true);
+    if forbidden(lints)
+        || (inherited
+            && forbidden(
+                workspace
+                    .and_then(|w| w.get("workspace"))
+                    .and_then(|w| w.get("lints")),
+            ))
+    {
+        return true;
+    }
+    fs::read_to_string(library)
+        .ok()
+        .and_then(|source| syn::parse_file(&source).ok())
+        .is_some_and(|file| {
+            file.attrs.iter().any(|attribute| {
+                attribute.path().is_ident("forbid")
+                    && attribute
+                        .parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+                        .is_ok_and(|lints| lints.iter().any(|l| l.is_ident("unsafe_code")))
+            })
+        })
+}
+
+/// Returns the expected sections missing from the README of a pallet, based on its (ATX or setext) headings.
+fn missing_sections(readme: &str) -> Vec<&'static str> {
+    let lines: Vec<_> = readme.lines().map(str::trim).collect();
+    let headings: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| match line.strip_prefix('#') {
+            Some(heading) => Some(heading.trim_start_matches('#')),
+            None => lines
+                .get(i + 1)
+                .filter(|next| {
+                    !line.is_empty()
+                        && !next.is_empty()
+                        && (next.chars().all(|c| c == '=') || next.chars().all(|c| c == '-'))
+                })
+                .map(|_| *line),
+        })
+        .map(|heading| heading.trim().to_lowercase())
+        .collect();
+    README_SECTIONS
+        .iter()
+        .filter(|(_, accepted)| {
+            !headings
+                .iter()
+                .any(|heading| accepted.iter().any(|a| heading.contains(a)))
+        })
+        .map(|(section, _)| *section)
+        .collect()
+}
+
+/// Requests the URL via HTTP HEAD (following any redirects), returning the HTTP status of the response or `None` when it
+/// could not be reached. Servers which do not support HEAD requests are requested via GET instead.
+fn status(url: &str) -> Option<u16> {
+    let request = |head: bool| {
+        let output = Command::new("curl")
+            .args(["--silent", "--location", "--max-time", "30"])
+            .args(["--output", "/dev/null", "--write-out", "%{http_code}"])
+            .args(head.then_some("--head"))
+            .arg(url)
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse::<u16>()
+            .ok()
+            .filter(|status| *status != 0)
+    };
+    match request(true) {
+        Some(status) if status < 400 => Some(status),
+        head => request(false).or(head),
+    }
+}
+
+/// The divergence of a fork from the repository from which it was forked.
+struct Divergence {
+    /// The upstream branch, e.g. `paritytech/polkadot-sdk:master`.
+    upstream: String,
+    /// The number of commits the fork is ahead of the upstream branch.
+    ahead: u64,
+    /// The number of commits the fork is behind the upstream branch.
+    behind: u64,
+}
+
+/// Determines the divergence of a fork hosted on GitHub (e.g. `owner/polkadot-sdk`) at the reference from the default
+/// branch of the repository from which it was forked, returning `None` when it could not be determined.
+fn divergence(fork: &str, reference: Option<&str>) -> Option<Divergence> {
+    let repository = github::json(
+        github::get(&format!("/repos/{}", fork)),
+        "GitHub repository",
+    )
+    .ok()?;
+    let parent = repository.get("parent")?;
+    let upstream = parent.get("full_name")?.as_str()?;
+    let base = parent.get("default_branch")?.as_str()?;
+    // Commits are shared across the network of forks, whereas branches and tags are qualified by the owner of the fork
+    let head = match reference {
+        Some(revision)
+            if revision.len() >= 7 && revision.chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            revision.to_string()
+        }
+        reference => format!(
+            "{}:{}",
+            fork.split('/').next()?,
+            reference.or(repository.get("default_branch")?.as_str())?
+        ),
+    };
+    let comparison = github::json(
+        github::get(&format!("/repos/{}/compare/{}...{}", upstream, base, head)),
+        "GitHub compare",
+    )
+    .ok()?;
+    Some(Divergence {
+        upstream: format!("{}:{}", upstream, base),
+        ahead: comparison.get("ahead_by")?.as_u64()?,
+        behind: comparison.get("behind_by")?.as_u64()?,
+    })
+}
+
+/// Whether the manifest is that of a crate copied from the Polkadot SDK, identified by its authors or repository.
+fn vendored(manifest: &Path) -> bool {
+    let Some(manifest) = fs::read_to_string(manifest)
+        .ok()
+        .and_then(|m| ImDocument::parse(m).ok())
+    else {
+        return false;
+    };
+    let Some(package) = manifest.get("package") else {
+        return false;
+    };
+    let authored = package
+        .get("authors")
+        .and_then(Item::as_array)
+        .is_some_and(|authors| {
+            authors.iter().any(|a| {
+                a.as_str()
+                    .is_some_and(|a| a.starts_with("Parity Technologies"))
+            })
+        });
+    let hosted = package
+        .get("repository")
+        .and_then(Item::as_str)
+        .is_some_and(|repository| {
+            let repository = normalize(repository);
+            SDK_REPOSITORIES
+                .iter()
+                .any(|name| repository == format!("github.com/paritytech/{}", name))
+        });
+    authored || hosted
+}
+
+/// Normalises a git or web URL of a repository for comparison, e.g. `github.com/owner/repo` for both
+/// `git@github.com:owner/repo.git` and `https://github.com/owner/repo/`.
+fn normalize(url: &str) -> String {
+    let url = url.trim().trim_start_matches("git+");
+    let url = match url.split_once("://") {
+        Some((_, rest)) => rest.to_string(),
+        // scp-like syntax, e.g. `git@github.com:owner/repo.git`
+        None => url.replacen(':', "/", 1),
+    };
+    let url = url.rsplit_once('@').map_or(url.as_str(), |(_, host)| host);
+    url.trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_lowercase()
+}
+
+/// Returns the license files within the directory, e.g. `LICENSE`, `LICENSE-APACHE` or `COPYING.md`.
+fn license_files(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            let name = p
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_uppercase();
+            ["LICENSE", "LICENCE", "COPYING"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Identifies the license within a license file from its text, returning its SPDX identifier without any `-only` or
+/// `-or-later` suffix.
+fn identify(text: &str) -> Option<&'static str> {
+    let text = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    LICENSE_TEXTS
+        .iter()
+        .find(|(_, phrases)| phrases.iter().all(|p| text.contains(p)))
+        .map(|(license, _)| *license)
+}
+
+/// Whether the license identified within a license file is one of the declared licenses, allowing only for the
+/// `-only` and `-or-later` (or `+`) variants of the license: a file containing `MIT` does not satisfy `MIT-0`.
+fn declares(declared: &[String], license: &str) -> bool {
+    declared.iter().any(|d| {
+        d.strip_prefix(license)
+            .is_some_and(|suffix| ["", "-only", "-or-later", "+"].contains(&suffix))
+    })
+}
+
+/// Returns the known ecosystem of the git source, if any.
+fn ecosystem(source: &str) -> Option<&'static str> {
+    ECOSYSTEMS
+        .iter()
+        .find(|(_, repository)| {
+            source
+                .strip_prefix(repository)
+                .is_some_and(|s| s.starts_with(['?', '#', '/']) || s.is_empty())
+        })
+        .map(|(name, _)| *name)
+}
+
+/// Returns the release of a branch or tag, ignoring any ecosystem specific prefixes: `polkadot-v1.0.0`,
+/// `release-v1.0.0` and `release-polkadot-v1.0.0` are all considered to be `1.0.0`.
+fn release(reference: &str) -> String {
+    let release = reference.strip_prefix("release-").unwrap_or(reference);
+    let release = release.strip_prefix("polkadot-").unwrap_or(release);
+    match release.strip_prefix('v') {
+        Some(version) if version.starts_with(|c: char| c.is_ascii_digit()) => version.into(),
+        _ => release.into(),
+    }
+}
+
+/// Determines how far the version is behind the latest stable version, returning the severity (a warning when the
+/// latest version is incompatible), the number of major or minor versions published since and which of the two.
+fn outdated<'a>(
+    version: &Version,
+    versions: &'a [Published],
+) -> Option<(Severity, usize, &'static str, &'a Version)> {
+    let stable: Vec<_> = versions
+        .iter()
+        .map(|p| &p.version)
+        .filter(|v| v.pre.is_none())
+        .collect();
+    let latest = *stable.last()?;
+    let severity = match version.compatible(latest) {
+        true => Severity::Info,
+        false => Severity::Warning,
+    };
+    let (mut behind, kind): (Vec<_>, _) = if latest.major > version.major {
+        (
+            stable
+                .iter()
+                .filter(|v| v.major > version.major)
+                .map(|v| (v.major, 0))
+                .collect(),
+            "major",
+        )
+    } else if latest.major == version.major && latest.minor > version.minor {
+        (
+            stable
+                .iter()
+                .filter(|v| v.major == version.major && v.minor > version.minor)
+                .map(|v| (v.major, v.minor))
+                .collect(),
+            "minor",
+        )
+    } else {
+        return None;
+    };
+    behind.dedup();
+    Some((severity, behind.len(), kind, latest))
+}
+
+/// Returns the dependency sections of a manifest (including target specific sections and any workspace
+/// dependencies), along with their names.
+fn sections(table: &toml_edit::Table) -> Vec<(String, &dyn TableLike)> {
+    const SECTIONS: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+    let mut sections: Vec<(String, &dyn TableLike)> = SECTIONS
+        .iter()
+        .filter_map(|s| Some((s.to_string(), table.get(s)?.as_table_like()?)))
+        .collect();
+    if let Some(targets) = table.get("target").and_then(Item::as_table_like) {
+        for (target, target_table) in targets.iter() {
+            sections.extend(SECTIONS.iter().filter_map(|s| {
+                Some((
+                    format!("target.'{}'.{}", target, s),
+                    target_table.get(s)?.as_table_like()?,
+                ))
+            }));
+        }
+    }
+    if let Some(dependencies) = table
+        .get("workspace")
+        .and_then(|w| w.get("dependencies")?.as_table_like())
+    {
+        sections.push(("workspace.dependencies".into(), dependencies));
+    }
+    sections
+}
+
+/// Returns the version requirement (or git repository) of a dependency declaration, unless inherited from the
+/// workspace or a local path dependency.
+fn declared(value: &Item) -> Option<String> {
+    if let Some(version) = value.as_str() {
+        return Some(version.into());
+    }
+    let table = value
+        .as_table_like()
+        .filter(|t| !t.contains_key("workspace"))?;
+    table
+        .get("version")
+        .and_then(Item::as_str)
+        .or_else(|| table.get("git")?.as_str())
+        .map(String::from)
+}
+
+/// Checks the style of the dependency declarations of a manifest: that dependencies are sorted, declared in a
+/// consistent style (inline or as tables), with consistent version requirements and without wildcards. Returns the
+/// code, message and line of each issue.
+fn style(manifest: &str, table: &toml_edit::Table) -> Vec<(&'static str, String, u16)> {
+    let sections = sections(table);
+    let mut issues = Vec::new();
+    let mut styles = Vec::new();
+    let mut requirements = Vec::new();
+    for (section, dependencies) in &sections {
+        let dependencies = entries(manifest, *dependencies);
+        // Sorted
+        if let Some(pair) = dependencies
+            .windows(2)
+            .find(|p| p[1].0.to_lowercase() < p[0].0.to_lowercase())
+        {
+            issues.push((
+                "sbp::unsorted_dependencies",
+                format!(
+                    "[{}] is not sorted: '{}' should precede '{}'",
+                    section, pair[1].0, pair[0].0
+                ),
+                pair[1].2,
+            ));
+        }
+
+        for (name, value, line) in dependencies {
+            // Declared as a table when defined by a `[dependencies.name]` header, rather than inline or via dotted keys
+            let table = value.as_table().is_some_and(|t| !t.is_dotted());
+            styles.push((table, name, line));
+
+            let Some(version) = value.as_str().or_else(|| value.get("version")?.as_str()) else {
+                continue;
+            };
+            // A requirement is classified by its first comparator, e.g. `>=` for `>=1.0, <2.0`
+            let mut comparators = version.split(',').map(str::trim);
+            if comparators.clone().any(|c| c == "*" || c.ends_with(".*")) {
+                issues.push((
+                    "sbp::wildcard_version",
+                    format!("wildcard version requirement '{}' for '{}'", version, name),
+                    line,
+                ));
+            } else if let Some(comparator) = comparators.next() {
+                let operator = comparator
+                    .find(|c: char| c.is_ascii_digit())
+                    .map_or(comparator, |i| comparator[..i].trim());
+                // A caret requirement is the default, so `^1.0` and `1.0` are of the same style
+                let operator = operator.trim_start_matches('^');
+                requirements.push((operator, version, name, line));
+            }
+        }
+    }
+
+    // Consistent styles, where the predominant style is expected
+    let tables = styles.iter().filter(|(table, ..)| *table).count();
+    if tables > 0 && tables < styles.len() {
+        let minority = tables <= styles.len() - tables;
+        for (_, name, line) in styles.iter().filter(|(table, ..)| *table == minority) {
+            issues.push((
+                "sbp::inconsistent_dependency_style",
+                format!(
+                    "'{}' is declared {}, unlike most other dependencies",
+                    name,
+                    if minority { "as a table" } else { "inline" }
+                ),
+                *line,
+            ));
+        }
+    }
+    let mut operators: Vec<&str> = requirements.iter().map(|(o, ..)| *o).collect();
+    operators.sort_unstable();
+    operators.dedup();
+    if let Some(predominant) = operators
+        .iter()
+        .max_by_key(|o| requirements.iter().filter(|r| r.0 == **o).count())
+    {
+        for (_, version, name, line) in requirements.iter().filter(|r| r.0 != *predominant) {
+            issues.push((
+                "sbp::inconsistent_version_requirement",
+                format!(
+                    "version requirement '{}' for '{}' is inconsistent with the predominant style{}",
+                    version,
+                    name,
+                    match *predominant {
+                        "" => String::new(),
+                        operator => format!(" ('{}')", operator),
+                    }
+                ),
+                *line,
+            ));
+        }
+    }
+    issues.sort_by_key(|(_, _, line)| *line);
+    issues
+}
+
+/// Returns the metadata of the workspace members, via `cargo metadata`.
+pub fn metadata(context: &Context) -> Result<Metadata, Error> {
+    cargo_metadata(context, &["--no-deps"])
+}
+
+/// Returns the metadata of the workspace members and all of their dependencies, including the resolved dependency
+/// graph (with all features enabled, so that optional dependencies are included), via `cargo metadata`.
+pub fn dependency_metadata(context: &Context) -> Result<Metadata, Error> {
+    cargo_metadata(context, &["--all-features"])
+}
+
+fn cargo_metadata(context: &Context, args: &[&str]) -> Result<Metadata, Error> {
+    let output = context
+        .cargo("metadata")
+        .args(args)
+        .arg("--format-version=1")
+        .output()
+        .map_err(|e| Error::command("cargo metadata", e))?;
+    if !output.status.success() {
+        return Err(Error::failed("cargo metadata", &output.stderr));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|source| Error::Parse {
+        command: "cargo metadata",
+        source,
+    })
+}
+
+/// Returns the path relative to the workspace root, where possible.
+pub fn relative(root: &Path, path: &str) -> String {
+    Path::new(path)
+        .strip_prefix(root)
+        .map_or_else(|_| path.into(), |p| p.display().to_string())
+}
+
+/// Returns the package name from a package id, in either the `name version (source)` or `source#name@version`
+/// formats, where the name is omitted from the latter when it matches the last path segment of the source (e.g.
+/// `path+file:///tmp/foo#0.1.0`).
+pub fn package_name(id: &str) -> Option<&str> {
+    match id.split_once(' ') {
+        Some((name, _)) => Some(name),
+        None => {
+            let (source, fragment) = id.rsplit_once('#')?;
+            match fragment.split_once('@') {
+                Some((name, _)) => Some(name),
+                None => source.rsplit('/').next(),
+            }
+        }
+    }
+}
+
+/// Returns the (one-based) number of the line of the document on which the span starts, e.g. that of a key parsed via
+/// `toml_edit`, defaulting to the first line.
+pub fn line_at(document: &str, span: Option<Range<usize>>) -> u16 {
+    let Some(preceding) = span.and_then(|s| document.get(..s.start)) else {
+        return 1;
+    };
+    u16::try_from(preceding.matches('\n').count() + 1).unwrap_or(u16::MAX)
+}
+
+/// Returns the entries of a table, along with the line of the document on which the key of each is declared.
+pub(crate) fn entries<'a>(
+    document: &str,
+    table: &'a dyn TableLike,
+) -> Vec<(&'a str, &'a Item, u16)> {
+    table
+        .iter()
+        .map(|(key, value)| {
+            let line = line_at(document, table.key(key).and_then(Key::span));
+            (key, value, line)
+        })
+        .collect()
+}
+
+/// Returns the (one-based) number of the first line of the manifest declaring the key (e.g. `license` or `serde`,
+/// including dotted keys such as `license.workspace` and headers such as `[dependencies.serde]`) or the table header
+/// (e.g. `[package]`), defaulting to the first line.
+pub fn line_of(manifest: &str, key: &str) -> u16 {
+    line(manifest, |line| {
+        let line = line.trim_start();
+        if key.starts_with('[') {
+            return line.starts_with(key);
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .trim_start_matches('[')
+                .split(']')
+                .next()
+                .unwrap_or_default();
+            return header
+                .rsplit('.')
+                .next()
+                .is_some_and(|k| k.trim().trim_matches(['"', '\'']) == key);
+        }
+        let rest = ["\"", "'", ""].iter().find_map(|quote| {
+            line.strip_prefix(quote)?
+                .strip_prefix(key)?
+                .strip_prefix(quote)
+        });
+        rest.is_some_and(|r| r.trim_start().starts_with(['=', '.']))
+    })
+}
+
+/// Returns the (one-based) number of the first line of the text containing the needle, defaulting to the first line.
+pub fn line_containing(text: &str, needle: &str) -> u16 {
+    line(text, |l| l.contains(needle))
+}
+
+fn line(text: &str, predicate: impl Fn(&str) -> bool) -> u16 {
+    text.lines()
+        .position(predicate)
+        .and_then(|i| u16::try_from(i + 1).ok())
+        .unwrap_or(1)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Metadata {
+    pub packages: Vec<Package>,
+    pub workspace_members: Vec<String>,
+    /// The resolved dependency graph, unless dependencies were excluded.
+    pub resolve: Option<Resolve>,
+    pub target_directory: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Resolve {
+    pub nodes: Vec<Node>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Node {
+    pub id: String,
+    pub deps: Vec<NodeDependency>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NodeDependency {
+    pub pkg: String,
+    pub dep_kinds: Vec<DependencyKind>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DependencyKind {
+    /// The kind of dependency: `dev`, `build` or `None` for normal dependencies.
+    pub kind: Option<String>,
+}
+
+impl Metadata {
+    /// Returns the package with the id.
+    pub fn package(&self, id: &str) -> Option<&Package> {
+        self.packages.iter().find(|p| p.id == id)
+    }
+
+    /// Returns the ids of the normal (non-dev, non-build) dependencies of the package, transitively, in breadth-first
+    /// order.
+    pub fn normal_dependencies(&self, id: &str) -> Vec<&str> {
+        let Some(resolve) = &self.resolve else {
+            return Vec::new();
+        };
+        let mut dependencies: Vec<&str> = Vec::new();
+        let mut queue = std::collections::VecDeque::from([id]);
+        while let Some(id) = queue.pop_front() {
+            let Some(node) = resolve.nodes.iter().find(|n| n.id == id) else {
+                continue;
+            };
+            for dependency in node
+                .deps
+                .iter()
+                .filter(|d| d.dep_kinds.iter().any(|k| k.kind.is_none()))
+            {
+                if dependency.pkg != id && !dependencies.contains(&dependency.pkg.as_str()) {
+                    dependencies.push(&dependency.pkg);
+                    queue.push_back(&dependency.pkg);
+                }
+            }
+        }
+        dependencies
+    }
+
+    /// Returns the package containing the file, i.e. the package with the closest containing directory.
+    pub fn package_of(&self, file: &Path) -> Option<&Package> {
+        self.packages
+            .iter()
+            .filter_map(|p| {
+                let directory = Path::new(&p.manifest_path).parent()?;
+                file.starts_with(directory)
+                    .then_some((directory.as_os_str().len(), p))
+            })
+            .max_by_key(|(length, _)| *length)
+            .map(|(_, package)| package)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Package {
+    pub id: String,
+    pub name: String,
+    pub manifest_path: String,
+    pub version: String,
+    /// The source of the package (e.g. `registry+https://github.com/rust-lang/crates.io-index`), or `None` for local
+    /// (path) packages. The source of a git package includes the resolved commit as its fragment.
+    pub source: Option<String>,
+    pub license: Option<String>,
+    pub license_file: Option<String>,
+    /// The registries the package may be published to, where an empty list prevents publishing and `None` allows any.
+    pub publish: Option<Vec<String>>,
+    pub description: Option<String>,
+    /// The URL of the documentation of the package.
+    pub documentation: Option<String>,
+    pub authors: Vec<String>,
+    pub repository: Option<String>,
+    /// The URL of the homepage of the package.
+    pub homepage: Option<String>,
+    /// The path of the README, relative to the manifest (including a colocated README inferred by cargo).
+    pub readme: Option<String>,
+    /// The minimum supported rust version.
+    pub rust_version: Option<String>,
+    pub categories: Vec<String>,
+    pub keywords: Vec<String>,
+    pub edition: String,
+    pub dependencies: Vec<Dependency>,
+    /// The features of the package, along with the features and dependencies each enables.
+    pub features: BTreeMap<String, Vec<String>>,
+    pub targets: Vec<Target>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Target {
+    pub name: String,
+    /// The kinds of the target, e.g. `lib`, `bin`, `test`, `example` or `bench`.
+    pub kind: Vec<String>,
+    /// The path of the root source file of the target.
+    pub src_path: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Dependency {
+    pub name: String,
+    pub source: Option<String>,
+    /// The version requirement, e.g. `^1.0`.
+    pub req: String,
+    /// The local path of a path dependency.
+    pub path: Option<String>,
+    /// The kind of dependency: `dev`, `build` or `None` for normal dependencies.
+    pub kind: Option<String>,
+    /// The name by which the dependency is referenced, when renamed.
+    pub rename: Option<String>,
+    pub optional: bool,
+    /// Whether the default features of the dependency are enabled.
+    pub uses_default_features: bool,
+    /// The features of the dependency enabled by the declaration.
+    pub features: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issues(manifest: &str) -> Vec<(&'static str, String, u16)> {
+        style(manifest, &ImDocument::parse(manifest).unwrap())
+    }
+
+    #[test]
+    fn parses_package_names_from_ids() {
+        assert_eq!(
+            package_name("serde 1.0.0 (registry+https://github.com/rust-lang/crates.io-index)"),
+            Some("serde")
+        );
+        assert_eq!(
+            package_name("registry+https://github.com/rust-lang/crates.io-index#serde@1.0.0"),
+            Some("serde")
+        );
+        assert_eq!(package_name("path+file:///tmp/foo#bar@0.1.0"), Some("bar"));
+        assert_eq!(package_name("path+file:///tmp/foo#0.1.0"), Some("foo"));
+        assert_eq!(
+            package_name("git+https://github.com/owner/repo?branch=main#pallet-foo@1.0.0"),
+            Some("pallet-foo")
+        );
+        assert_eq!(package_name("foo"), None);
+    }
+
+    #[test]
+    fn locates_keys() {
+        let manifest = "[package]\nlicense-file = \"LICENSE\"\nlicense.workspace = true\n\n[dependencies]\n\
+                        foo-bar = \"1\"\n\"foo\" = { version = \"1\" }\n\n[dev-dependencies.baz]\nversion = \"1\"\n";
+        assert_eq!(line_of(manifest, "[package]"), 1);
+        assert_eq!(line_of(manifest, "license-file"), 2);
+        assert_eq!(line_of(manifest, "license"), 3);
+        assert_eq!(line_of(manifest, "[dependencies]"), 5);
+        assert_eq!(line_of(manifest, "foo"), 7);
+        assert_eq!(line_of(manifest, "baz"), 9);
+        assert_eq!(line_of(manifest, "version"), 10);
+        assert_eq!(line_of(manifest, "missing"), 1);
+        assert_eq!(line_containing(manifest, "LICENSE"), 2);
+    }
+
+    #[test]
+    fn normalizes_repository_urls() {
+        for url in [
+            "https://github.com/Owner/Repo",
+            "https://github.com/owner/repo/",
+            "https://github.com/owner/repo.git",
+            "git+https://github.com/owner/repo",
+            "git@github.com:owner/repo.git",
+            "ssh://git@github.com/owner/repo",
+        ] {
+            assert_eq!(normalize(url), "github.com/owner/repo", "{}", url);
+        }
+    }
+
+    #[test]
+    fn reports_unsorted_dependencies() {
+        let issues = issues("[dependencies]\nb = \"1\"\na = \"1\"\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].0, "sbp::unsorted_dependencies");
+        assert_eq!(issues[0].2, 3);
+    }
+
+    #[test]
+    fn reports_inconsistent_dependency_styles() {
+        let issues = issues(
+            "[dependencies]\na = \"1\"\nb = { version = \"1\" }\n\n[dependencies.c]\nversion = \"1\"\n",
+        );
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].0, "sbp::inconsistent_dependency_style");
+        assert!(issues[0].1.starts_with("'c' is declared as a table"));
+    }
+
+    #[test]
+    fn treats_caret_requirements_as_default() {
+        let issues =
+            issues("[dependencies]\na = \"1.0\"\nb = \"^1.2\"\nc = { version = \"0.3\" }\n");
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn classifies_compound_requirements_once() {
+        let issues =
+            issues("[dependencies]\na = \"1.0\"\nb = \"^1.2\"\nc = \">=1, <2\"\nd = \"1.*, <2\"\n");
+        assert_eq!(
+            issues
+                .iter()
+                .map(|(code, _, line)| (*code, *line))
+                .collect::<Vec<_>>(),
+            [
+                ("sbp::inconsistent_version_requirement", 4),
+                ("sbp::wildcard_version", 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_predominant_operator() {
+        let issues = issues("[dependencies]\na = \"=1.0\"\nb = \"=1.2\"\nc = \"1.3\"\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].1,
+            "version requirement '1.3' for 'c' is inconsistent with the predominant style ('=')"
+        );
+    }
+
+    #[test]
+    fn matches_license_files_exactly() {
+        let declared = |expression: &str| spdx::licenses(&spdx::validate(expression).unwrap());
+        assert!(declares(&declared("MIT OR Apache-2.0"), "MIT"));
+        assert!(declares(&declared("GPL-3.0-only"), "GPL-3.0"));
+        assert!(declares(
+            &declared("GPL-3.0-or-later WITH Classpath-exception-2.0"),
+            "GPL-3.0"
+        ));
+        assert!(!declares(&declared("MIT-0"), "MIT"));
+        assert!(!declares(&declared("Apache-2.0"), "MIT"));
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    findings::{Finding, Severity},
+    score::Score,
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Renders the findings as a Markdown document, grouped by package and severity, preceded by the score of a review.
+pub fn render(findings: &[Finding], score: Option<&Score>) -> String {
+    let mut packages: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        packages
+            .entry(finding.package.as_deref().unwrap_or("workspace"))
+            .or_default()
+            .push(finding);
+    }
+
+    let mut markdown = String::from("# SBP Review\n");
+    if let Some(score) = score {
+        let _ = write!(
+            markdown,
+            "\n## Score: {}\n\n| Category | Score |\n| --- | --- |\n",
+            score
+        );
+        for (category, category_score) in &score.categories {
+            let _ = writeln!(markdown, "| {} | {} |", category, category_score);
+        }
+    }
+    if packages.is_empty() {
+        markdown.push_str("\nNo findings.\n");
+    }
+    for (package, findings) in packages {
+        let _ = write!(markdown, "\n## {}\n", package);
+        for severity in Severity::ALL {
+            let findings: Vec<_> = findings.iter().filter(|f| f.severity == severity).collect();
+            if findings.is_empty() {
+                continue;
+            }
+            let _ = write!(
+                markdown,
+                "\n### {} ({})\n\n",
+                severity.as_str(),
+                findings.len()
+            );
+            for finding in findings {
+                markdown.push_str("- ");
+                if let Some(code) = &finding.code {
+                    let _ = match finding.help_uri() {
+                        Some(url) => write!(markdown, "[`{}`]({}) ", code, url),
+                        None => write!(markdown, "`{}` ", code),
+                    };
+                }
+                markdown.push_str(&finding.message.replace('\n', " "));
+                for help in &finding.help {
+                    let _ = write!(markdown, " _help: {}_", help.replace('\n', " "));
+                }
+                if let Some(location) = &finding.location {
+                    let _ = write!(
+                        markdown,
+                        " at [{}:{}:{}]({}#L{})",
+                        location.file, location.line, location.column, location.file, location.line
+                    );
+                }
+                markdown.push('\n');
+            }
+        }
+    }
+    markdown
+}
@@ -0,0 +1,481 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    git, manifests, runtime, Analysis, Context, Error, Format,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Builds a runtime at a base git reference and at the current revision, and compares their metadata (via
+/// [`subwasm`](https://github.com/chevdor/subwasm)), reporting any pallets, calls, storage items or constants which
+/// were added, removed or changed, along with any `spec_version` or `transaction_version` which was not bumped
+/// accordingly.
+pub struct MetadataDiff {
+    /// The base git reference, e.g. `main`.
+    pub base: String,
+}
+
+impl Analysis for MetadataDiff {
+    fn category(&self) -> Category {
+        Category::Runtime
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        let version = Command::new("subwasm").arg("--version").output();
+        if !version.is_ok_and(|o| o.status.success()) {
+            return Err(Error::NotInstalled {
+                tool: "subwasm",
+                hint: "run `cargo install --git https://github.com/chevdor/subwasm --locked`",
+            });
+        }
+        let metadata = manifests::metadata(context)?;
+        let runtime = runtime::package(context, &metadata, None)?;
+        let target_directory = Path::new(&metadata.target_directory);
+        let output_directory = target_directory.join("sbp-review").join("metadata");
+        fs::create_dir_all(&output_directory).map_err(|source| Error::Io {
+            path: output_directory.clone(),
+            source,
+        })?;
+
+        let mut findings = Vec::new();
+        let mut report = |finding: Finding| {
+            if let Some(finding) = context.config.apply(finding.with_package(&runtime)) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        };
+
+        // The runtime is built at the base reference within a temporary worktree, sharing the target directory
+        if text {
+            println!("Building {} at {}...", runtime, self.base);
+        }
+        let worktree = output_directory.join("worktree");
+        let base = git::add_worktree(&context.root, &worktree, &self.base).and_then(|root| {
+            let built = build(
+                context,
+                Some(&root.join("Cargo.toml")),
+                &runtime,
+                target_directory,
+            )?;
+            built
+                .then(|| copy(target_directory, &runtime, &output_directory, "base"))
+                .transpose()
+        });
+        git::remove_worktree(&context.root, &worktree);
+        let Some(base) = base? else {
+            report(failed(&format!("runtime failed to build at {}", self.base)));
+            return Ok(findings);
+        };
+        if text {
+            println!("Building {}...", runtime);
+        }
+        if !build(context, None, &runtime, target_directory)? {
+            report(failed("runtime failed to build"));
+            return Ok(findings);
+        }
+        let head = copy(target_directory, &runtime, &output_directory, "head")?;
+
+        if text {
+            println!("Comparing runtime metadata with {}...", self.base);
+        }
+        let read = |path: &Path| {
+            fs::read(path).map_err(|source| Error::Io {
+                path: path.to_path_buf(),
+                source,
+            })
+        };
+        let blobs = (read(&base)?, read(&head)?);
+        let (base, head) = (items(&decode(&base)?), items(&decode(&head)?));
+        // Changes to the encoding of calls (and thereby transactions) require the transaction version to be bumped
+        let mut encoding = false;
+        for (key, signature) in &head {
+            let (kind, name) = key;
+            let previous = base.get(key);
+            encoding |=
+                matches!(*kind, "call" | "pallet") && previous.is_some_and(|p| p != signature);
+            match previous {
+                None => report(
+                    Finding::new(
+                        Category::Runtime,
+                        Severity::Info,
+                        format!("{} '{}' added", kind, name),
+                    )
+                    .with_code("sbp::metadata_added"),
+                ),
+                Some(previous) if previous != signature => report(
+                    Finding::new(
+                        Category::Runtime,
+                        Severity::Warning,
+                        format!(
+                            "{} '{}' changed from `{}` to `{}`",
+                            kind, name, previous, signature
+                        ),
+                    )
+                    .with_code("sbp::metadata_changed"),
+                ),
+                Some(_) => {}
+            }
+        }
+        for (kind, name) in base.keys().filter(|key| !head.contains_key(*key)) {
+            encoding |= matches!(*kind, "call" | "pallet");
+            report(
+                Finding::new(
+                    Category::Runtime,
+                    Severity::Warning,
+                    format!("{} '{}' removed", kind, name),
+                )
+                .with_code("sbp::metadata_removed"),
+            );
+        }
+
+        // Versions
+        let (Some(base_version), Some(head_version)) =
+            (runtime::version(&blobs.0), runtime::version(&blobs.1))
+        else {
+            return Ok(findings);
+        };
+        let version = |severity, code: &str, message: &str, (base, head): (u32, u32)| {
+            Finding::new(
+                Category::Runtime,
+                severity,
+                format!("{} ({} at {}, now {})", message, base, self.base, head),
+            )
+            .with_code(code)
+        };
+        let spec_versions = (base_version.spec_version, head_version.spec_version);
+        if spec_versions.1 < spec_versions.0 {
+            report(version(
+                Severity::Error,
+                "sbp::spec_version_not_bumped",
+                "'spec_version' was decreased",
+                spec_versions,
+            ));
+        } else if spec_versions.1 == spec_versions.0 && blobs.0 != blobs.1 {
+            report(version(
+                Severity::Warning,
+                "sbp::spec_version_not_bumped",
+                "runtime changed but 'spec_version' was not bumped",
+                spec_versions,
+            ));
+        }
+        let transaction_versions = (
+            base_version.transaction_version,
+            head_version.transaction_version,
+        );
+        if encoding && transaction_versions.1 <= transaction_versions.0 {
+            report(version(
+                Severity::Warning,
+                "sbp::transaction_version_not_bumped",
+                "calls were changed or removed but 'transaction_version' was not bumped",
+                transaction_versions,
+            ));
+        }
+        Ok(findings)
+    }
+}
+
+/// A finding for a runtime which could not be built.
+fn failed(message: &str) -> Finding {
+    Finding::new(Category::Runtime, Severity::Error, message.into())
+        .with_code("sbp::runtime_build_failed")
+}
+
+/// Builds the runtime package of the workspace (or of the manifest) within the target directory, returning whether it
+/// was built successfully.
+fn build(
+    context: &Context,
+    manifest_path: Option<&Path>,
+    runtime: &str,
+    target_directory: &Path,
+) -> Result<bool, Error> {
+    let mut command = match manifest_path {
+        Some(manifest_path) => {
+            let mut command = Command::new("cargo");
+            command
+                .arg("build")
+                .arg("--manifest-path")
+                .arg(manifest_path);
+            command
+        }
+        None => context.cargo("build"),
+    };
+    // The target directory of the workspace is shared, to avoid rebuilding dependencies
+    command
+        .args(["--release", "--package", runtime])
+        .env("CARGO_TARGET_DIR", target_directory)
+        .stdout(context.stdout());
+    context
+        .output(&mut command, "metadata-diff")
+        .map(|output| output.status.success())
+        .map_err(|e| Error::command("cargo build", e))
+}
+
+/// Copies the built (uncompressed) runtime Wasm to the output directory, as the built Wasm is overwritten by subsequent
+/// builds.
+fn copy(
+    target_directory: &Path,
+    runtime: &str,
+    output_directory: &Path,
+    name: &str,
+) -> Result<PathBuf, Error> {
+    let path = output_directory.join(format!("{}.wasm", name));
+    fs::copy(runtime::wasm(target_directory, runtime, false), &path).map_err(|source| {
+        Error::Io {
+            path: path.clone(),
+            source,
+        }
+    })?;
+    Ok(path)
+}
+
+/// Decodes the metadata of the runtime Wasm, via `subwasm metadata`.
+fn decode(wasm: &Path) -> Result<Metadata, Error> {
+    let output = Command::new("subwasm")
+        .args(["metadata", "--format", "json"])
+        .arg(wasm)
+        .output()
+        .map_err(|source| match source.kind() {
+            io::ErrorKind::NotFound => Error::NotInstalled {
+                tool: "subwasm",
+                hint: "run `cargo install --git https://github.com/chevdor/subwasm --locked`",
+            },
+            _ => Error::Command {
+                command: "subwasm metadata",
+                source,
+            },
+        })?;
+    if !output.status.success() {
+        return Err(Error::failed("subwasm metadata", &output.stderr));
+    }
+    let parse = |source| Error::Parse {
+        command: "subwasm metadata",
+        source,
+    };
+    // The metadata may be prefixed (by its magic number) and is wrapped by its version, e.g. `{"V14": {..}}`
+    let mut value: Value = serde_json::from_slice(&output.stdout).map_err(parse)?;
+    if let Value::Array(values) = value {
+        value = values.into_iter().last().unwrap_or_default();
+    }
+    if let Value::Object(map) = &value {
+        if let Some((_, metadata)) = map.iter().find(|(key, _)| key.starts_with('V')) {
+            value = metadata.clone();
+        }
+    }
+    serde_json::from_value(value).map_err(parse)
+}
+
+/// The runtime metadata (V14+), limited to that which is compared.
+#[derive(Deserialize)]
+struct Metadata {
+    types: Registry,
+    pallets: Vec<Pallet>,
+}
+
+#[derive(Deserialize)]
+struct Registry {
+    types: Vec<RegistryType>,
+}
+
+#[derive(Deserialize)]
+struct RegistryType {
+    id: u32,
+    #[serde(rename = "type")]
+    ty: Type,
+}
+
+#[derive(Deserialize)]
+struct Type {
+    #[serde(default)]
+    path: Vec<String>,
+    #[serde(default)]
+    params: Vec<TypeParameter>,
+    def: Value,
+}
+
+#[derive(Deserialize)]
+struct TypeParameter {
+    #[serde(rename = "type")]
+    ty: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct Pallet {
+    name: String,
+    index: u8,
+    storage: Option<Storage>,
+    calls: Option<Calls>,
+    #[serde(default)]
+    constants: Vec<Constant>,
+}
+
+#[derive(Deserialize)]
+struct Storage {
+    entries: Vec<StorageEntry>,
+}
+
+#[derive(Deserialize)]
+struct StorageEntry {
+    name: String,
+    modifier: String,
+    ty: Value,
+}
+
+#[derive(Deserialize)]
+struct Calls {
+    ty: u32,
+}
+
+#[derive(Deserialize)]
+struct Constant {
+    name: String,
+    ty: u32,
+    value: Value,
+}
+
+/// Returns the compared items of the metadata (pallets, calls, storage items and constants), keyed by their kind and
+/// name, along with their signature.
+fn items(metadata: &Metadata) -> BTreeMap<(&'static str, String), String> {
+    let types: BTreeMap<_, _> = metadata.types.types.iter().map(|t| (t.id, &t.ty)).collect();
+    let type_name = |id: &Value| {
+        id.as_u64()
+            .map_or("?".into(), |id| name(&types, id as u32, 0))
+    };
+
+    let mut items = BTreeMap::new();
+    for pallet in &metadata.pallets {
+        items.insert(
+            ("pallet", pallet.name.clone()),
+            format!("index {}", pallet.index),
+        );
+        let variants = pallet
+            .calls
+            .as_ref()
+            .and_then(|calls| types.get(&calls.ty))
+            .and_then(|ty| ty.def.pointer("/variant/variants"))
+            .and_then(Value::as_array);
+        for call in variants.into_iter().flatten() {
+            let fields: Vec<_> = fields(call)
+                .iter()
+                .map(|field| match field.get("name").and_then(Value::as_str) {
+                    Some(name) => format!("{}: {}", name, type_name(&field["type"])),
+                    None => type_name(&field["type"]),
+                })
+                .collect();
+            items.insert(
+                (
+                    "call",
+                    format!("{}::{}", pallet.name, call["name"].as_str().unwrap_or("?")),
+                ),
+                format!("#{}({})", call["index"], fields.join(", ")),
+            );
+        }
+        for entry in pallet.storage.iter().flat_map(|s| &s.entries) {
+            let signature = match (entry.ty.get("Plain"), entry.ty.get("Map")) {
+                (Some(value), _) => type_name(value),
+                (None, Some(map)) => format!(
+                    "{} => {} ({})",
+                    type_name(&map["key"]),
+                    type_name(&map["value"]),
+                    map["hashers"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                (None, None) => entry.ty.to_string(),
+            };
+            items.insert(
+                ("storage", format!("{}::{}", pallet.name, entry.name)),
+                format!("{} {}", entry.modifier, signature),
+            );
+        }
+        for constant in &pallet.constants {
+            let value = match &constant.value {
+                Value::Array(bytes) => format!(
+                    "0x{}",
+                    bytes
+                        .iter()
+                        .filter_map(Value::as_u64)
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<String>()
+                ),
+                value => value.as_str().map_or(value.to_string(), str::to_string),
+            };
+            items.insert(
+                ("constant", format!("{}::{}", pallet.name, constant.name)),
+                format!("{} = {}", type_name(&constant.ty.into()), value),
+            );
+        }
+    }
+    items
+}
+
+/// The fields of a composite type or variant.
+fn fields(value: &Value) -> &[Value] {
+    value
+        .get("fields")
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+/// Renders the name of a type within the registry, e.g. `BoundedVec<u8, MaxLength>` or `(u32, AccountId32)`. Type
+/// identifiers are not stable between builds, so types are compared by name.
+fn name(types: &BTreeMap<u32, &Type>, id: u32, depth: usize) -> String {
+    let Some(ty) = types.get(&id).filter(|_| depth < 8) else {
+        return "..".into();
+    };
+    let render = |value: &Value| {
+        value
+            .as_u64()
+            .map_or("?".into(), |id| name(types, id as u32, depth + 1))
+    };
+    if let Some(last) = ty.path.last() {
+        let params: Vec<_> = ty
+            .params
+            .iter()
+            .map(|p| p.ty.map_or("_".into(), |id| render(&id.into())))
+            .collect();
+        return match params.is_empty() {
+            true => last.clone(),
+            false => format!("{}<{}>", last, params.join(", ")),
+        };
+    }
+    let Some((kind, def)) = ty.def.as_object().and_then(|d| d.iter().next()) else {
+        return "?".into();
+    };
+    match kind.as_str() {
+        "primitive" => def.as_str().unwrap_or("?").into(),
+        "sequence" => format!("Vec<{}>", render(&def["type"])),
+        "array" => format!("[{}; {}]", render(&def["type"]), def["len"]),
+        "compact" => format!("Compact<{}>", render(&def["type"])),
+        "tuple" => format!(
+            "({})",
+            def.as_array()
+                .into_iter()
+                .flatten()
+                .map(render)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        "composite" => format!(
+            "{{ {} }}",
+            fields(def)
+                .iter()
+                .map(|f| render(&f["type"]))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        "bitSequence" => "BitVec".into(),
+        kind => kind.into(),
+    }
+}
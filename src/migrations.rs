@@ -0,0 +1,161 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    manifests, runtime, Analysis, Context, Error, Format,
+};
+use std::{
+    io,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Executes the migrations of a runtime against live (or snapshot) state, via the `on-runtime-upgrade` command of
+/// [`try-runtime`](https://github.com/paritytech/try-runtime-cli), summarising any failed pre/post upgrade or
+/// try-state checks.
+pub struct Migrations;
+
+impl Analysis for Migrations {
+    fn category(&self) -> Category {
+        Category::Migrations
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        let config = &context.config.migrations;
+        let version = Command::new("try-runtime").arg("--version").output();
+        if !version.is_ok_and(|o| o.status.success()) {
+            return Err(Error::NotInstalled {
+                tool: "try-runtime",
+                hint: "run `cargo install --git https://github.com/paritytech/try-runtime-cli --locked`",
+            });
+        }
+        let state = match (&config.uri, &config.snapshot) {
+            (Some(uri), _) => vec!["live".into(), "--uri".into(), uri.clone()],
+            (None, Some(snapshot)) => vec![
+                "snap".into(),
+                "--path".into(),
+                context.root.join(snapshot).display().to_string(),
+            ],
+            (None, None) => {
+                return Err(Error::Config(
+                    "no state configured to execute migrations against, use --uri or --snapshot or set 'uri' within [migrations]"
+                        .into(),
+                ))
+            }
+        };
+
+        let metadata = manifests::metadata(context)?;
+        let runtime = match &config.runtime {
+            Some(runtime) => runtime.clone(),
+            None => runtime::package(context, &metadata, Some("try-runtime"))?,
+        };
+
+        if text {
+            println!("Building {} with the 'try-runtime' feature...", runtime);
+        }
+        let mut findings = Vec::new();
+        let mut report = |finding: Finding| {
+            if let Some(finding) = context.config.apply(finding.with_package(&runtime)) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        };
+        let mut command = context.cargo("build");
+        command
+            .args(["--release", "--package", &runtime])
+            .args(["--features", "try-runtime"])
+            .stdout(context.stdout());
+        let status = context
+            .output(&mut command, "migrations")
+            .map_err(|e| Error::command("cargo build", e))?
+            .status;
+        if !status.success() {
+            report(
+                Finding::new(
+                    Category::Migrations,
+                    Severity::Error,
+                    format!("runtime failed to build with 'try-runtime' ({})", status),
+                )
+                .with_code("sbp::migrations_failed"),
+            );
+            return Ok(findings);
+        }
+
+        let wasm = runtime::wasm(Path::new(&metadata.target_directory), &runtime, true);
+        if text {
+            println!(
+                "Executing migrations of {} via try-runtime on-runtime-upgrade...",
+                runtime
+            );
+        }
+        let mut command = Command::new("try-runtime");
+        command
+            .arg("--runtime")
+            .arg(&wasm)
+            .arg("on-runtime-upgrade")
+            .arg(format!("--checks={}", config.checks))
+            .args(&state)
+            .current_dir(&context.root)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let output = context
+            .output(&mut command, "migrations")
+            .map_err(|source| match source.kind() {
+                io::ErrorKind::NotFound => Error::NotInstalled {
+                    tool: "try-runtime",
+                    hint: "run `cargo install --git https://github.com/paritytech/try-runtime-cli --locked`",
+                },
+                _ => Error::command("try-runtime on-runtime-upgrade", source),
+            })?;
+
+        // Failed checks are logged as errors, with the command then failing with the first error encountered
+        let log = String::from_utf8_lossy(&output.stderr);
+        let errors = errors(&log);
+        let failed = !errors.is_empty();
+        for error in errors {
+            report(
+                Finding::new(Category::Migrations, Severity::Error, error)
+                    .with_code("sbp::migration_check_failed"),
+            );
+        }
+        if !output.status.success() && !failed {
+            report(
+                Finding::new(
+                    Category::Migrations,
+                    Severity::Error,
+                    format!(
+                        "migrations failed ({}): {}",
+                        output.status,
+                        log.lines()
+                            .rev()
+                            .find(|l| !l.trim().is_empty())
+                            .unwrap_or_default()
+                            .trim()
+                    ),
+                )
+                .with_code("sbp::migrations_failed"),
+            );
+        }
+        Ok(findings)
+    }
+}
+
+/// Parses the messages of errors logged by `try-runtime`, whose log lines are of the form:
+///
+/// ```text
+/// 2024-01-01 00:00:00 ERROR runtime::template: [Template] post_upgrade failed: value not migrated
+/// ```
+fn errors(log: &str) -> Vec<String> {
+    log.lines()
+        .filter_map(|line| {
+            let (_, message) = line.split_once(" ERROR ")?;
+            // Messages are prefixed with the log target, e.g. `runtime::template: `
+            let message = message
+                .split_once(": ")
+                .filter(|(target, _)| !target.contains(' '))
+                .map_or(message, |(_, message)| message);
+            Some(message.trim().to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,361 @@
+use crate::{
+    findings::{Category, Finding, Location, Severity},
+    manifests,
+    pallets::{self, Source},
+    Analysis, Context, Error, Format,
+};
+use proc_macro2::Span;
+use quote::ToTokens;
+use std::path::Path;
+use syn::{
+    punctuated::Punctuated,
+    visit::{self, Visit},
+    Expr, ExprPath, ImplItem, ItemImpl, LitStr, Macro, Pat, Token,
+};
+
+/// Pruning modes which retain all state or blocks, along with what is retained.
+const ARCHIVE: [(&str, &str, &str); 4] = [
+    ("PruningMode", "ArchiveAll", "all state"),
+    ("PruningMode", "ArchiveCanonical", "all canonical state"),
+    ("BlocksPruning", "KeepAll", "all blocks"),
+    ("BlocksPruning", "KeepFinalized", "all finalized blocks"),
+];
+
+/// Command line arguments which expose unsafe RPC methods.
+const UNSAFE_RPC: [&str; 2] = ["--rpc-methods=unsafe", "--unsafe-rpc-external"];
+
+/// Inspects the node of a parachain (its CLI and service) for unhelpful or insecure defaults: a version without the
+/// commit hash, telemetry submitted unencrypted, pruning which retains all state or blocks and unsafe RPC methods.
+pub struct Node;
+
+impl Analysis for Node {
+    fn category(&self) -> Category {
+        Category::Node
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Analysing node defaults...");
+        }
+
+        let mut findings = Vec::new();
+        let metadata = manifests::metadata(context)?;
+        for package in metadata
+            .packages
+            .iter()
+            .filter(|p| context.selected(&p.name))
+        {
+            let Some(directory) = Path::new(&package.manifest_path).parent() else {
+                continue;
+            };
+            let mut report = |finding: Finding| {
+                if let Some(finding) = context.config.apply(finding.with_package(&package.name)) {
+                    if text {
+                        finding.print(context);
+                    }
+                    findings.push(finding)
+                }
+            };
+            // The version is checked across the package, as the cargo keys are generated by its build script
+            let sources = pallets::sources(directory, &context.root);
+            let mut defaults = Defaults::default();
+            for source in &sources {
+                defaults.source = Some(source);
+                defaults.visit_file(&source.file);
+            }
+            defaults.findings.into_iter().for_each(&mut report);
+            if let Some((location, commit)) = defaults.version {
+                if !commit {
+                    let mut finding = Finding::new(
+                        Category::Node,
+                        Severity::Warning,
+                        "`impl_version` does not include the commit hash, so the version output by `--version` (and \
+                         reported to telemetry) does not identify the build"
+                            .into(),
+                    )
+                    .with_code("sbp::node_version")
+                    .with_location(location);
+                    finding.help.push(
+                        "use `env!(\"SUBSTRATE_CLI_IMPL_VERSION\")`, generated by calling \
+                         `substrate_build_script_utils::generate_cargo_keys()` within the build script"
+                            .into(),
+                    );
+                    report(finding);
+                } else if let (Some(location), false) = (defaults.generated, defaults.reruns) {
+                    report(
+                        Finding::new(
+                            Category::Node,
+                            Severity::Info,
+                            "the build script does not call `rerun_if_git_head_changed()`, so the commit hash \
+                             within the version may be stale"
+                                .into(),
+                        )
+                        .with_code("sbp::node_version")
+                        .with_location(location),
+                    );
+                }
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// The defaults of a node, collected from each of the sources of its package.
+#[derive(Default)]
+struct Defaults<'a> {
+    /// The source currently being visited.
+    source: Option<&'a Source>,
+    /// The location of the `impl_version` function of the `SubstrateCli` implementation, along with whether it
+    /// includes the commit hash.
+    version: Option<(Location, bool)>,
+    /// The location at which the cargo keys of the version (including the commit hash) are generated, if at all.
+    generated: Option<Location>,
+    /// Whether the build script is re-run whenever the git HEAD changes, so that the commit hash remains current.
+    reruns: bool,
+    findings: Vec<Finding>,
+}
+
+impl Defaults<'_> {
+    fn location(&self, span: Span) -> Location {
+        self.source
+            .expect("defaults are only visited with a source")
+            .location(span)
+    }
+
+    fn report(&mut self, severity: Severity, code: &str, message: String, span: Span) {
+        let finding = Finding::new(Category::Node, severity, message)
+            .with_code(code)
+            .with_location(self.location(span));
+        self.findings.push(finding);
+    }
+}
+
+impl<'ast> Visit<'ast> for Defaults<'_> {
+    fn visit_item_impl(&mut self, item: &'ast ItemImpl) {
+        let substrate_cli = item
+            .trait_
+            .as_ref()
+            .and_then(|(_, path, _)| path.segments.last())
+            .is_some_and(|s| s.ident == "SubstrateCli");
+        if substrate_cli {
+            for item in &item.items {
+                if let ImplItem::Fn(function) = item {
+                    if function.sig.ident == "impl_version" {
+                        let commit = function
+                            .block
+                            .to_token_stream()
+                            .to_string()
+                            .contains("SUBSTRATE_CLI_IMPL_VERSION");
+                        self.version = Some((self.location(function.sig.ident.span()), commit));
+                    }
+                }
+            }
+        }
+        visit::visit_item_impl(self, item);
+    }
+
+    fn visit_expr_path(&mut self, expr: &'ast ExprPath) {
+        let segments: Vec<_> = expr
+            .path
+            .segments
+            .iter()
+            .map(|s| s.ident.to_string())
+            .collect();
+        let span = expr
+            .path
+            .segments
+            .last()
+            .map_or_else(Span::call_site, |s| s.ident.span());
+        match segments
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .as_slice()
+        {
+            [.., "generate_cargo_keys"] => self.generated = Some(self.location(span)),
+            [.., "rerun_if_git_head_changed"] => self.reruns = true,
+            [.., ty, variant] => {
+                if let Some((ty, variant, retained)) =
+                    ARCHIVE.iter().find(|(t, v, _)| ty == t && variant == v)
+                {
+                    self.report(
+                        Severity::Warning,
+                        "sbp::node_pruning",
+                        format!(
+                            "`{}::{}` retains {} by default, so storage grows without bound; leave archiving to node \
+                             operators via `--state-pruning` or `--blocks-pruning`",
+                            ty, variant, retained
+                        ),
+                        span,
+                    );
+                } else if (*ty, *variant) == ("RpcMethods", "Unsafe") {
+                    self.report(
+                        Severity::Error,
+                        "sbp::node_unsafe_rpc",
+                        "unsafe RPC methods are enabled by default via `RpcMethods::Unsafe`, exposing methods such as \
+                         `author_insertKey` to anyone able to reach the RPC server"
+                            .into(),
+                        span,
+                    );
+                }
+            }
+            _ => {}
+        }
+        visit::visit_expr_path(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &'ast Pat) {
+        // Paths within patterns, e.g. `RpcMethods::Unsafe => ..`, match rather than set a value
+        if !matches!(pat, Pat::Path(_)) {
+            visit::visit_pat(self, pat);
+        }
+    }
+
+    fn visit_lit_str(&mut self, literal: &'ast LitStr) {
+        let value = literal.value();
+        if UNSAFE_RPC.contains(&value.to_lowercase().as_str()) {
+            self.report(
+                Severity::Error,
+                "sbp::node_unsafe_rpc",
+                format!(
+                    "unsafe RPC methods are enabled by default via `{}`, exposing methods such as `author_insertKey` \
+                     to anyone able to reach the RPC server",
+                    value
+                ),
+                literal.span(),
+            );
+        }
+        if value.starts_with("ws://") && value.contains("telemetry") {
+            self.report(
+                Severity::Warning,
+                "sbp::node_telemetry",
+                format!(
+                    "telemetry is submitted unencrypted to `{}` by default, use a `wss://` endpoint",
+                    value
+                ),
+                literal.span(),
+            );
+        }
+        visit::visit_lit_str(self, literal);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        // Macros such as `vec!` are commonly used to declare defaults, such as telemetry endpoints
+        if let Ok(arguments) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+        {
+            for argument in &arguments {
+                self.visit_expr(argument);
+            }
+        }
+        visit::visit_macro(self, mac);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(path: &str, code: &str) -> Source {
+        Source {
+            path: path.into(),
+            file: syn::parse_file(code).unwrap(),
+        }
+    }
+
+    fn defaults(sources: &[Source]) -> Defaults<'_> {
+        let mut defaults = Defaults::default();
+        for source in sources {
+            defaults.source = Some(source);
+            defaults.visit_file(&source.file);
+        }
+        defaults
+    }
+
+    #[test]
+    fn reports_insecure_defaults() {
+        let sources = [
+            source(
+                "node/src/command.rs",
+                r#"
+                impl SubstrateCli for Cli {
+                    fn impl_version() -> String {
+                        env!("CARGO_PKG_VERSION").into()
+                    }
+                }
+                "#,
+            ),
+            source(
+                "node/src/service.rs",
+                r#"
+                const TELEMETRY_URL: &str = "ws://telemetry.example.com/submit/";
+                fn configuration() -> Configuration {
+                    Configuration {
+                        state_pruning: Some(PruningMode::ArchiveAll),
+                        blocks_pruning: BlocksPruning::KeepFinalized,
+                        rpc_methods: RpcMethods::Unsafe,
+                        args: vec!["--unsafe-rpc-external"],
+                    }
+                }
+                "#,
+            ),
+        ];
+        let defaults = defaults(&sources);
+        let codes: Vec<_> = defaults
+            .findings
+            .iter()
+            .filter_map(|f| f.code.as_deref())
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                "sbp::node_telemetry",
+                "sbp::node_pruning",
+                "sbp::node_pruning",
+                "sbp::node_unsafe_rpc",
+                "sbp::node_unsafe_rpc",
+            ]
+        );
+        let (location, commit) = defaults.version.unwrap();
+        assert_eq!(
+            (location.file.as_str(), location.line, commit),
+            ("node/src/command.rs", 3, false)
+        );
+    }
+
+    #[test]
+    fn secure_defaults_are_accepted() {
+        let sources = [
+            source(
+                "node/build.rs",
+                r#"
+                fn main() {
+                    generate_cargo_keys();
+                    rerun_if_git_head_changed();
+                }
+                "#,
+            ),
+            source(
+                "node/src/command.rs",
+                r#"
+                impl SubstrateCli for Cli {
+                    fn impl_version() -> String {
+                        env!("SUBSTRATE_CLI_IMPL_VERSION").into()
+                    }
+                }
+                const TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+                fn rpc_methods(methods: RpcMethods) -> bool {
+                    match methods {
+                        RpcMethods::Unsafe => false,
+                        _ => true,
+                    }
+                }
+                "#,
+            ),
+        ];
+        let defaults = defaults(&sources);
+        assert!(defaults.findings.is_empty());
+        assert!(defaults.version.is_some_and(|(_, commit)| commit));
+        assert!(defaults.generated.is_some() && defaults.reruns);
+    }
+}
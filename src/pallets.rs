@@ -0,0 +1,2138 @@
+use crate::{
+    deprecated,
+    findings::{Category, Finding, Location, Severity},
+    manifests, Analysis, Context, Error, Format,
+};
+use proc_macro2::{Delimiter, Ident, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+use syn::{
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    Attribute, BinOp, Block, Expr, ExprBinary, ExprCall, ExprLoop, ExprWhile, FnArg,
+    GenericArgument, ImplItem, ImplItemFn, Item, ItemEnum, ItemFn, ItemImpl, ItemMod, ItemStruct,
+    ItemTrait, ItemType, LitInt, Local, Macro, Meta, Pat, PathArguments, Signature, Token,
+    TraitItem, Type,
+};
+
+/// Analyses the source of FRAME pallets for known issues.
+pub struct Pallets;
+
+impl Analysis for Pallets {
+    fn category(&self) -> Category {
+        Category::Pallets
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Analysing pallet source...");
+        }
+
+        let mut findings = Vec::new();
+        let metadata = manifests::metadata(context)?;
+        let packages: Vec<_> = metadata
+            .packages
+            .iter()
+            .filter_map(|p| {
+                let directory = Path::new(&p.manifest_path).parent()?;
+                Some((p, sources(directory, &context.root)))
+            })
+            .collect();
+        // Migrations are included within the Executive of a runtime, which is typically declared in another package
+        let included = migrations(packages.iter().flat_map(|(_, sources)| sources));
+        for (package, sources) in packages.iter().filter(|(p, _)| context.selected(&p.name)) {
+            let mut report = |finding: Finding| {
+                if let Some(finding) = context.config.apply(finding.with_package(&package.name)) {
+                    if text {
+                        finding.print(context);
+                    }
+                    findings.push(finding)
+                }
+            };
+
+            // Deprecated APIs and crates, within any package
+            for finding in deprecated::dependencies(package, &context.root) {
+                report(finding);
+            }
+            for source in sources {
+                for finding in deprecated::apis(source) {
+                    report(finding);
+                }
+            }
+            for finding in analyse(sources, included.as_ref(), &context.config.runtime.allow) {
+                report(finding);
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// Analyses the sources of a package, given the migrations included within the `Executive` of the runtime (if any) and
+/// the development pallets permitted within the runtime.
+fn analyse(
+    sources: &[Source],
+    included: Option<&HashSet<String>>,
+    allowed: &[String],
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if let Some(included) = included {
+        for (source, ident) in upgrades(sources) {
+            if !included.contains(&ident.to_string()) {
+                findings.push(
+                    Finding::new(
+                        Category::Pallets,
+                        Severity::Warning,
+                        format!(
+                            "migration '{}' is not included within the migrations of the runtime's `Executive`",
+                            ident
+                        ),
+                    )
+                    .with_code("sbp::unused_migration")
+                    .with_location(source.location(ident.span())),
+                );
+            }
+        }
+    }
+
+    // Development pallets are not expected within production runtimes, unless explicitly allowed
+    for source in sources {
+        for (name, krate) in runtime_pallets(&source.file) {
+            if allowed
+                .iter()
+                .any(|a| name == a || a.replace('-', "_") == krate)
+            {
+                continue;
+            }
+            let Some((_, reason)) = DEV_PALLETS
+                .iter()
+                .find(|(prefix, _)| krate.starts_with(prefix))
+            else {
+                continue;
+            };
+            findings.push(
+                Finding::new(
+                    Category::Pallets,
+                    Severity::Warning,
+                    format!(
+                        "runtime includes development pallet '{}' ({}), which {}",
+                        name, krate, reason
+                    ),
+                )
+                .with_code("sbp::dev_pallet")
+                .with_location(source.location(name.span())),
+            );
+        }
+    }
+
+    let pallets: Vec<_> = sources.iter().flat_map(Pallet::find).collect();
+    if pallets.is_empty() {
+        return findings;
+    }
+    for source in sources {
+        for (operator, span) in arithmetic(source) {
+            findings.push(
+                Finding::new(
+                    Category::Pallets,
+                    Severity::Warning,
+                    format!(
+                        "unchecked arithmetic (`{}`) on a balance or block number, use `checked_{}`, `saturating_{}` or `ensure_{}` instead",
+                        operator.0, operator.1, operator.1, operator.1
+                    ),
+                )
+                .with_code("sbp::unchecked_arithmetic")
+                .with_location(source.location(span)),
+            );
+        }
+    }
+
+    // Calls are considered tested when referenced by name within any test of the package
+    let tested = tested(sources);
+    // Calls are considered benchmarked when a benchmark of the package has the same name, per convention
+    let benchmarked = benchmarked(sources);
+    let max_encoded_len = max_encoded_len(sources);
+    let functions = Functions::find(sources);
+    let depositing = functions.referencing(&["deposit_event"]);
+    let authorizing = functions.referencing(&ORIGIN_CHECKS);
+    for pallet in &pallets {
+        for call in &pallet.calls {
+            let name = call.sig.ident.to_string();
+            if !tested.contains(&name) {
+                findings.push(
+                    Finding::new(
+                        Category::Pallets,
+                        Severity::Warning,
+                        format!("call '{}' has no direct tests", name),
+                    )
+                    .with_code("sbp::untested_call")
+                    .with_location(pallet.source.location(call.sig.ident.span())),
+                );
+            }
+            if !benchmarked.contains(&name) {
+                findings.push(
+                    Finding::new(
+                        Category::Pallets,
+                        Severity::Warning,
+                        format!("call '{}' has no corresponding benchmark", name),
+                    )
+                    .with_code("sbp::unbenchmarked_call")
+                    .with_location(pallet.source.location(call.sig.ident.span())),
+                );
+            }
+            // Events may be deposited directly or via a helper function of the package
+            let mut referenced = HashSet::new();
+            identifiers(call.block.to_token_stream(), &mut referenced);
+            if mutates(call)
+                && !referenced.contains("deposit_event")
+                && referenced.is_disjoint(&depositing)
+            {
+                findings.push(
+                    Finding::new(
+                        Category::Pallets,
+                        Severity::Warning,
+                        format!("call '{}' mutates storage but deposits no event", name),
+                    )
+                    .with_code("sbp::missing_event")
+                    .with_location(pallet.source.location(call.sig.ident.span())),
+                );
+            }
+            // Origins may be checked directly or via a helper function of the package
+            if ORIGIN_CHECKS.iter().all(|c| !referenced.contains(*c))
+                && referenced.is_disjoint(&authorizing)
+            {
+                findings.push(
+                    Finding::new(
+                        Category::Pallets,
+                        Severity::Warning,
+                        format!(
+                            "call '{}' does not check its origin (e.g. via `ensure_signed`), so may be missing authorization",
+                            name
+                        ),
+                    )
+                    .with_code("sbp::missing_origin_check")
+                    .with_location(pallet.source.location(call.sig.ident.span())),
+                );
+            }
+            // Implicit call indices follow declaration order, so reordering or inserting calls is breaking
+            if !call
+                .attrs
+                .iter()
+                .any(|a| is_pallet_attribute(a, Some("call_index")))
+            {
+                findings.push(
+                    Finding::new(
+                        Category::Pallets,
+                        Severity::Warning,
+                        format!("call '{}' has no explicit call index", name),
+                    )
+                    .with_code("sbp::missing_call_index")
+                    .with_location(pallet.source.location(call.sig.ident.span())),
+                );
+            }
+            if let Some((severity, code, message)) = pallet.weight(call) {
+                findings.push(
+                    Finding::new(Category::Pallets, severity, message)
+                        .with_code(code)
+                        .with_location(pallet.source.location(match weight(call) {
+                            Some(attribute) => attribute.span(),
+                            None => call.sig.ident.span(),
+                        })),
+                );
+            }
+        }
+        // A storage version allows migrations to determine whether they have already been applied, and so is
+        // required for the layout of storage to change safely between runtime upgrades
+        if let Some(declaration) = pallet
+            .declaration
+            .filter(|_| !pallet.storage.is_empty() && !pallet.storage_version)
+        {
+            findings.push(
+                Finding::new(
+                    Category::Pallets,
+                    Severity::Warning,
+                    "pallet declares storage but no storage version, via `#[pallet::storage_version(..)]`"
+                        .into(),
+                )
+                .with_code("sbp::missing_storage_version")
+                .with_location(pallet.source.location(declaration.ident.span())),
+            );
+        }
+        for variant in pallet.event.iter().flat_map(|e| &e.variants) {
+            if !functions.referenced.contains(&variant.ident.to_string()) {
+                findings.push(
+                    Finding::new(
+                        Category::Pallets,
+                        Severity::Warning,
+                        format!("event '{}' is never deposited", variant.ident),
+                    )
+                    .with_code("sbp::unused_event")
+                    .with_location(pallet.source.location(variant.ident.span())),
+                );
+            }
+        }
+        // Pallet items are documented within the metadata of the runtime, and so to users of the chain
+        let config_types =
+            pallet
+                .config
+                .iter()
+                .flat_map(|c| &c.items)
+                .filter_map(|item| match item {
+                    TraitItem::Type(ty) => Some(("config type", &ty.ident, &ty.attrs)),
+                    _ => None,
+                });
+        let calls = pallet
+            .calls
+            .iter()
+            .map(|c| ("call", &c.sig.ident, &c.attrs));
+        let storage = pallet
+            .storage
+            .iter()
+            .map(|s| ("storage", &s.ident, &s.attrs));
+        let variants = [("event", pallet.event), ("error", pallet.error)]
+            .into_iter()
+            .flat_map(|(kind, item)| {
+                item.into_iter()
+                    .flat_map(move |e| &e.variants)
+                    .map(move |v| (kind, &v.ident, &v.attrs))
+            });
+        for (kind, ident, _) in config_types
+            .chain(calls)
+            .chain(storage)
+            .chain(variants)
+            .filter(|(_, _, attributes)| !attributes.iter().any(|a| a.path().is_ident("doc")))
+        {
+            findings.push(
+                Finding::new(
+                    Category::Docs,
+                    Severity::Warning,
+                    format!("{} '{}' has no doc comment", kind, ident),
+                )
+                .with_code("sbp::undocumented_pallet_item")
+                .with_location(pallet.source.location(ident.span())),
+            );
+        }
+        // Hooks executed within every block must perform bounded work, accounted for within the block weight
+        let hook = |name: &str| pallet.hooks.iter().find(|h| h.sig.ident == name);
+        for function in ["on_initialize", "on_finalize"]
+            .into_iter()
+            .filter_map(hook)
+        {
+            for (work, span) in unbounded_work(function) {
+                findings.push(
+                    Finding::new(
+                        Category::Pallets,
+                        Severity::Warning,
+                        format!(
+                            "{} {}, which is potentially unbounded",
+                            function.sig.ident, work
+                        ),
+                    )
+                    .with_code("sbp::unbounded_hook")
+                    .with_location(pallet.source.location(span)),
+                );
+            }
+        }
+        let mut weights = HashSet::new();
+        if let Some(function) = hook("on_initialize") {
+            identifiers(function.block.to_token_stream(), &mut weights);
+        }
+        // The weight of `on_finalize` is expected to be accounted for by `on_initialize`
+        let unweighted = [("on_initialize", 1), ("on_finalize", 0)]
+            .into_iter()
+            .filter_map(|(name, statements)| {
+                hook(name).filter(|f| f.block.stmts.len() > statements)
+            })
+            .find(|_| !weights.contains("WeightInfo") && !weights.contains("DbWeight"));
+        if let Some(function) = unweighted {
+            findings.push(
+                Finding::new(
+                    Category::Pallets,
+                    Severity::Warning,
+                    format!(
+                        "{} performs work but its weight is not returned from on_initialize via `WeightInfo` or `DbWeight`",
+                        function.sig.ident
+                    ),
+                )
+                .with_code("sbp::missing_hook_weight")
+                .with_location(pallet.source.location(function.sig.ident.span())),
+            );
+        }
+        // Storage info is required to account for the proof size (PoV) of storage accesses on parachains
+        if let Some(attribute) = pallet.attribute("without_storage_info") {
+            findings.push(
+                Finding::new(
+                    Category::Pallets,
+                    Severity::Warning,
+                    "pallet is declared without storage info, so the proof size of its storage is unbounded"
+                        .into(),
+                )
+                .with_code("sbp::without_storage_info")
+                .with_location(pallet.source.location(attribute.span())),
+            );
+        }
+        for storage in &pallet.storage {
+            if let Some(attribute) = storage
+                .attrs
+                .iter()
+                .find(|a| is_pallet_attribute(a, Some("unbounded")))
+            {
+                findings.push(
+                    Finding::new(
+                        Category::Pallets,
+                        Severity::Warning,
+                        format!(
+                            "storage '{}' is declared as unbounded, so its proof size is not accounted for",
+                            storage.ident
+                        ),
+                    )
+                    .with_code("sbp::without_storage_info")
+                    .with_location(pallet.source.location(attribute.span())),
+                );
+            }
+            let Some((keys, value)) = storage_types(&storage.ty) else {
+                continue;
+            };
+            for (kind, ty) in keys
+                .into_iter()
+                .map(|key| ("key", key))
+                .chain(value.map(|value| ("value", value)))
+            {
+                let mut identifiers = HashSet::new();
+                self::identifiers(ty.to_token_stream(), &mut identifiers);
+                let mut missing: Vec<_> = identifiers
+                    .iter()
+                    .filter(|i| max_encoded_len.get(*i) == Some(&false))
+                    .collect();
+                missing.sort();
+                for name in missing {
+                    findings.push(
+                        Finding::new(
+                            Category::Pallets,
+                            Severity::Warning,
+                            format!(
+                                "type '{}' of storage '{}' does not implement `MaxEncodedLen`",
+                                name, storage.ident
+                            ),
+                        )
+                        .with_code("sbp::missing_max_encoded_len")
+                        .with_location(pallet.source.location(storage.ident.span())),
+                    );
+                }
+                if let Some(unbounded) = unbounded(ty) {
+                    findings.push(
+                        Finding::new(
+                            Category::Pallets,
+                            Severity::Warning,
+                            format!(
+                                "storage '{}' has an unbounded {} type ('{}'), use a bounded alternative such as `BoundedVec` or `BoundedBTreeMap`",
+                                storage.ident, kind, unbounded
+                            ),
+                        )
+                        .with_code("sbp::unbounded_storage")
+                        .with_location(pallet.source.location(storage.ident.span())),
+                    );
+                }
+            }
+        }
+    }
+    findings
+}
+
+/// A parsed source file.
+pub(crate) struct Source {
+    /// The path of the file, relative to the workspace root.
+    pub(crate) path: String,
+    pub(crate) file: syn::File,
+}
+
+impl Source {
+    /// Parses the Rust source file, returning `None` if it cannot be read or parsed.
+    pub(crate) fn parse(path: &Path, root: &Path) -> Option<Source> {
+        let file = syn::parse_file(&fs::read_to_string(path).ok()?).ok()?;
+        Some(Source {
+            path: manifests::relative(root, &path.to_string_lossy()),
+            file,
+        })
+    }
+
+    /// The location of the span within the source file.
+    pub(crate) fn location(&self, span: Span) -> Location {
+        let (start, end) = (span.start(), span.end());
+        let number = |n: usize| u16::try_from(n).unwrap_or(u16::MAX);
+        Location {
+            file: self.path.clone(),
+            line: number(start.line),
+            column: number(start.column + 1),
+            line_end: number(end.line),
+            column_end: number(end.column + 1),
+        }
+    }
+}
+
+/// A FRAME pallet, declared via `#[frame_support::pallet]`.
+struct Pallet<'a> {
+    source: &'a Source,
+    /// Whether the pallet is declared in `dev_mode`, where calls without a weight have zero weight.
+    dev_mode: bool,
+    /// Whether a default weight for calls is declared via `#[pallet::call(weight(..))]`.
+    default_weight: bool,
+    /// The dispatchable functions declared within `#[pallet::call]` blocks.
+    calls: Vec<&'a ImplItemFn>,
+    /// The hooks (e.g. `on_initialize`) declared within `#[pallet::hooks]` blocks.
+    hooks: Vec<&'a ImplItemFn>,
+    /// The storage items, declared as `#[pallet::storage]` type aliases.
+    storage: Vec<&'a ItemType>,
+    /// The configuration trait of the pallet, declared via `#[pallet::config]`.
+    config: Option<&'a ItemTrait>,
+    /// The events of the pallet, declared via `#[pallet::event]`.
+    event: Option<&'a ItemEnum>,
+    /// The errors of the pallet, declared via `#[pallet::error]`.
+    error: Option<&'a ItemEnum>,
+    /// The pallet struct, declared via `#[pallet::pallet]`.
+    declaration: Option<&'a ItemStruct>,
+    /// Whether a storage version is declared, via `#[pallet::storage_version(..)]` or a `STORAGE_VERSION` constant.
+    storage_version: bool,
+}
+
+impl<'a> Pallet<'a> {
+    /// Finds the pallets declared within the source file.
+    fn find(source: &'a Source) -> Vec<Pallet<'a>> {
+        #[derive(Default)]
+        struct Modules<'a>(Vec<&'a ItemMod>);
+        impl<'a> Visit<'a> for Modules<'a> {
+            fn visit_item_mod(&mut self, module: &'a ItemMod) {
+                match module.attrs.iter().any(|a| is_pallet_attribute(a, None)) {
+                    true => self.0.push(module),
+                    false => visit::visit_item_mod(self, module),
+                }
+            }
+        }
+
+        let mut modules = Modules::default();
+        modules.visit_file(&source.file);
+        modules
+            .0
+            .into_iter()
+            .map(|module| Pallet {
+                source,
+                dev_mode: module
+                    .attrs
+                    .iter()
+                    .filter(|a| is_pallet_attribute(a, None))
+                    .any(|a| arguments(a).contains("dev_mode")),
+                default_weight: items(module).any(|item| {
+                    matches!(item, Item::Impl(block) if block.attrs.iter().any(|a| {
+                        is_pallet_attribute(a, Some("call")) && arguments(a).contains("weight")
+                    }))
+                }),
+                calls: functions(module, "call"),
+                hooks: functions(module, "hooks"),
+                storage: items(module)
+                    .filter_map(|item| match item {
+                        Item::Type(item)
+                            if item
+                                .attrs
+                                .iter()
+                                .any(|a| is_pallet_attribute(a, Some("storage"))) =>
+                        {
+                            Some(item)
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+                config: items(module).find_map(|item| match item {
+                    Item::Trait(item)
+                        if item
+                            .attrs
+                            .iter()
+                            .any(|a| is_pallet_attribute(a, Some("config"))) =>
+                    {
+                        Some(item)
+                    }
+                    _ => None,
+                }),
+                event: items(module).find_map(|item| match item {
+                    Item::Enum(item)
+                        if item
+                            .attrs
+                            .iter()
+                            .any(|a| is_pallet_attribute(a, Some("event"))) =>
+                    {
+                        Some(item)
+                    }
+                    _ => None,
+                }),
+                error: items(module).find_map(|item| match item {
+                    Item::Enum(item)
+                        if item
+                            .attrs
+                            .iter()
+                            .any(|a| is_pallet_attribute(a, Some("error"))) =>
+                    {
+                        Some(item)
+                    }
+                    _ => None,
+                }),
+                declaration: items(module).find_map(|item| match item {
+                    Item::Struct(item)
+                        if item
+                            .attrs
+                            .iter()
+                            .any(|a| is_pallet_attribute(a, Some("pallet"))) =>
+                    {
+                        Some(item)
+                    }
+                    _ => None,
+                }),
+                storage_version: items(module).any(|item| match item {
+                    Item::Struct(item) => item
+                        .attrs
+                        .iter()
+                        .any(|a| is_pallet_attribute(a, Some("storage_version"))),
+                    Item::Const(item) => item.ident == "STORAGE_VERSION",
+                    _ => false,
+                }),
+            })
+            .collect()
+    }
+}
+
+impl Pallet<'_> {
+    /// The `#[pallet::<name>]` attribute of the pallet struct, if any.
+    fn attribute(&self, name: &str) -> Option<&Attribute> {
+        self.declaration?
+            .attrs
+            .iter()
+            .find(|a| is_pallet_attribute(a, Some(name)))
+    }
+
+    /// Checks the weight of the call, returning the severity, code and message of any issue. Weights are expected to be
+    /// derived from benchmarks, via `WeightInfo`.
+    fn weight(&self, call: &ImplItemFn) -> Option<(Severity, &'static str, String)> {
+        let name = &call.sig.ident;
+        let Some(attribute) = weight(call) else {
+            return (self.dev_mode && !self.default_weight).then(|| {
+                (
+                    Severity::Error,
+                    "sbp::zero_weight",
+                    format!(
+                        "call '{}' has no weight and so zero weight (dev_mode)",
+                        name
+                    ),
+                )
+            });
+        };
+        let mut identifiers = HashSet::new();
+        self::identifiers(attribute.meta.to_token_stream(), &mut identifiers);
+        identifiers.remove("pallet");
+        identifiers.remove("weight");
+        if identifiers.contains("WeightInfo") {
+            return None;
+        }
+        // Weights constructed solely from literals are constant, regardless of the work performed by the call
+        const CONSTRUCTORS: [&str; 5] =
+            ["Weight", "from_parts", "from_ref_time", "from_all", "zero"];
+        if identifiers
+            .iter()
+            .all(|i| CONSTRUCTORS.contains(&i.as_str()))
+        {
+            let mut literals = Vec::new();
+            integers(attribute.meta.to_token_stream(), &mut literals);
+            return Some(match literals.iter().all(|l| *l == 0) {
+                true => (
+                    Severity::Error,
+                    "sbp::zero_weight",
+                    format!("call '{}' has zero weight", name),
+                ),
+                false => (
+                    Severity::Warning,
+                    "sbp::constant_weight",
+                    format!(
+                        "call '{}' has a constant weight, rather than one derived from benchmarks",
+                        name
+                    ),
+                ),
+            });
+        }
+        Some((
+            Severity::Warning,
+            "sbp::missing_weight_info",
+            format!(
+                "weight of call '{}' is not derived from benchmarks via `WeightInfo`",
+                name
+            ),
+        ))
+    }
+}
+
+/// Returns the key and value types of the storage type, e.g. `StorageMap<_, Blake2_128Concat, K, V>`, supporting both
+/// positional and named (`Key = K, Value = V`) generic arguments. Returns `None` for unknown storage types.
+fn storage_types(ty: &Type) -> Option<(Vec<&Type>, Option<&Type>)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    // The positions of the key(s) and value within the positional generic arguments
+    let (keys, value): (&[usize], usize) = match segment.ident.to_string().as_str() {
+        "StorageValue" => (&[], 1),
+        "StorageMap" | "CountedStorageMap" => (&[2], 3),
+        "StorageDoubleMap" => (&[2, 4], 5),
+        "StorageNMap" | "CountedStorageNMap" => (&[1], 2),
+        _ => return None,
+    };
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    let mut types = (Vec::new(), None);
+    for (index, argument) in arguments.args.iter().enumerate() {
+        match argument {
+            GenericArgument::Type(ty) if keys.contains(&index) => types.0.push(ty),
+            GenericArgument::Type(ty) if index == value => types.1 = Some(ty),
+            GenericArgument::AssocType(assoc) if assoc.ident == "Value" => {
+                types.1 = Some(&assoc.ty)
+            }
+            GenericArgument::AssocType(assoc) if assoc.ident.to_string().starts_with("Key") => {
+                types.0.push(&assoc.ty)
+            }
+            _ => {}
+        }
+    }
+    Some(types)
+}
+
+/// Returns the first unbounded collection type within the type, if any.
+fn unbounded(ty: &Type) -> Option<String> {
+    const UNBOUNDED: [&str; 9] = [
+        "Vec",
+        "VecDeque",
+        "BTreeMap",
+        "BTreeSet",
+        "HashMap",
+        "HashSet",
+        "BinaryHeap",
+        "LinkedList",
+        "String",
+    ];
+    fn find(tokens: TokenStream) -> Option<String> {
+        tokens.into_iter().find_map(|token| match token {
+            TokenTree::Ident(ident) if UNBOUNDED.iter().any(|u| ident == u) => {
+                Some(ident.to_string())
+            }
+            TokenTree::Group(group) => find(group.stream()),
+            _ => None,
+        })
+    }
+    find(ty.to_token_stream())
+}
+
+/// The `#[pallet::weight(..)]` attribute of the call, if any.
+fn weight(call: &ImplItemFn) -> Option<&Attribute> {
+    call.attrs
+        .iter()
+        .find(|a| is_pallet_attribute(a, Some("weight")))
+}
+
+/// The identifiers within the arguments of the attribute, if any.
+fn arguments(attribute: &Attribute) -> HashSet<String> {
+    let mut arguments = HashSet::new();
+    if let Meta::List(list) = &attribute.meta {
+        identifiers(list.tokens.clone(), &mut arguments);
+    }
+    arguments
+}
+
+/// The functions declared within the `#[pallet::<name>]` impl blocks of the module.
+fn functions<'a>(module: &'a ItemMod, name: &str) -> Vec<&'a ImplItemFn> {
+    items(module)
+        .filter_map(|item| match item {
+            Item::Impl(block)
+                if block
+                    .attrs
+                    .iter()
+                    .any(|a| is_pallet_attribute(a, Some(name))) =>
+            {
+                Some(block.items.iter().filter_map(|item| match item {
+                    ImplItem::Fn(function) => Some(function),
+                    _ => None,
+                }))
+            }
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// Whether the function mutates storage, e.g. via `Something::<T>::put(..)`.
+fn mutates(function: &ImplItemFn) -> bool {
+    /// Storage functions which mutate storage.
+    const MUTATORS: [&str; 16] = [
+        "put",
+        "set",
+        "insert",
+        "remove",
+        "remove_all",
+        "mutate",
+        "mutate_exists",
+        "try_mutate",
+        "try_mutate_exists",
+        "take",
+        "kill",
+        "append",
+        "swap",
+        "clear",
+        "clear_prefix",
+        "translate",
+    ];
+    #[derive(Default)]
+    struct Mutates(bool);
+    impl<'a> Visit<'a> for Mutates {
+        fn visit_expr_call(&mut self, call: &'a ExprCall) {
+            if let Expr::Path(path) = &*call.func {
+                self.0 |= (path.qself.is_some() || path.path.segments.len() > 1)
+                    && path
+                        .path
+                        .segments
+                        .last()
+                        .is_some_and(|s| MUTATORS.iter().any(|m| s.ident == m));
+            }
+            visit::visit_expr_call(self, call);
+        }
+    }
+
+    let mut mutates = Mutates::default();
+    mutates.visit_block(&function.block);
+    mutates.0
+}
+
+/// Functions which check the origin of a call, including those of `EnsureOrigin` implementations.
+const ORIGIN_CHECKS: [&str; 6] = [
+    "ensure_signed",
+    "ensure_root",
+    "ensure_none",
+    "ensure_signed_or_root",
+    "ensure_origin",
+    "try_origin",
+];
+
+/// The identifiers referenced within the (non-test) functions of the sources.
+#[derive(Default)]
+struct Functions {
+    /// The identifiers referenced by any function, including any event variants deposited.
+    referenced: HashSet<String>,
+    /// The identifiers referenced by each function, keyed by function name.
+    functions: HashMap<String, HashSet<String>>,
+}
+
+impl Functions {
+    /// Finds the functions declared within the sources.
+    fn find(sources: &[Source]) -> Functions {
+        let mut functions = Functions::default();
+        for source in sources {
+            functions.visit_file(&source.file);
+        }
+        functions
+    }
+
+    /// The names of the functions referencing any of the identifiers.
+    fn referencing(&self, identifiers: &[&str]) -> HashSet<String> {
+        self.functions
+            .iter()
+            .filter(|(_, referenced)| identifiers.iter().any(|i| referenced.contains(*i)))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    fn function(&mut self, ident: &Ident, attributes: &[Attribute], block: &Block) {
+        // Tests and benchmarks reference events and origins without depositing or checking them
+        if attributes.iter().any(|a| {
+            a.path()
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "test" || s.ident == "benchmark")
+        }) {
+            return;
+        }
+        let mut referenced = HashSet::new();
+        identifiers(block.to_token_stream(), &mut referenced);
+        self.referenced.extend(referenced.iter().cloned());
+        self.functions
+            .entry(ident.to_string())
+            .or_default()
+            .extend(referenced);
+    }
+}
+
+impl<'a> Visit<'a> for Functions {
+    fn visit_item_mod(&mut self, module: &'a ItemMod) {
+        let test = module
+            .attrs
+            .iter()
+            .any(|a| a.path().is_ident("cfg") && arguments(a).contains("test"));
+        if !test {
+            visit::visit_item_mod(self, module);
+        }
+    }
+
+    fn visit_item_fn(&mut self, function: &'a ItemFn) {
+        self.function(&function.sig.ident, &function.attrs, &function.block);
+    }
+
+    fn visit_impl_item_fn(&mut self, function: &'a ImplItemFn) {
+        self.function(&function.sig.ident, &function.attrs, &function.block);
+    }
+}
+
+/// Returns the potentially unbounded work performed by the function: iteration over storage (e.g. `Foo::<T>::iter()`)
+/// or `while`/`loop` expressions, along with its span.
+fn unbounded_work(function: &ImplItemFn) -> Vec<(String, Span)> {
+    /// Storage functions which iterate over (potentially) all keys of a storage map.
+    const ITERATORS: [&str; 11] = [
+        "iter",
+        "iter_keys",
+        "iter_values",
+        "iter_prefix",
+        "iter_prefix_values",
+        "iter_key_prefix",
+        "drain",
+        "drain_prefix",
+        "translate",
+        "translate_values",
+        "clear_prefix",
+    ];
+    #[derive(Default)]
+    struct Work(Vec<(String, Span)>);
+    impl<'a> Visit<'a> for Work {
+        fn visit_expr_call(&mut self, call: &'a ExprCall) {
+            if let Expr::Path(path) = &*call.func {
+                if let Some(segment) = path.path.segments.last().filter(|s| {
+                    path.path.segments.len() > 1 && ITERATORS.iter().any(|i| s.ident == i)
+                }) {
+                    self.0.push((
+                        format!("iterates over storage (`{}`)", segment.ident),
+                        call.span(),
+                    ));
+                }
+            }
+            visit::visit_expr_call(self, call);
+        }
+
+        fn visit_expr_while(&mut self, expr: &'a ExprWhile) {
+            self.0
+                .push(("contains a `while` loop".into(), expr.while_token.span));
+            visit::visit_expr_while(self, expr);
+        }
+
+        fn visit_expr_loop(&mut self, expr: &'a ExprLoop) {
+            self.0
+                .push(("contains a `loop`".into(), expr.loop_token.span));
+            visit::visit_expr_loop(self, expr);
+        }
+    }
+
+    let mut work = Work::default();
+    work.visit_block(&function.block);
+    work.0
+}
+
+/// Returns the unchecked arithmetic operations (`+`, `-` and `*`, including compound assignment) on balances or block
+/// numbers within the (non-test) functions of the source, along with the name of the corresponding checked operation.
+///
+/// Values are considered balances or block numbers when declared with a type whose name contains `Balance` or
+/// `BlockNumber`, or when initialised via a function such as `block_number()` or `free_balance(..)`. As the source is
+/// analysed prior to macro expansion, code generated by FRAME macros is not analysed, although the arguments of
+/// `ensure!` are.
+fn arithmetic(source: &Source) -> Vec<((&'static str, &'static str), Span)> {
+    /// Functions which return a balance or block number.
+    const VALUES: [&str; 6] = [
+        "block_number",
+        "balance",
+        "free_balance",
+        "reserved_balance",
+        "total_balance",
+        "reducible_balance",
+    ];
+    #[derive(Default)]
+    struct Arithmetic {
+        /// The variables of the current function which hold balances or block numbers.
+        variables: HashSet<String>,
+        operations: Vec<((&'static str, &'static str), Span)>,
+    }
+    impl Arithmetic {
+        fn function(&mut self, attributes: &[Attribute], signature: &Signature, block: &Block) {
+            if attributes.iter().any(|a| {
+                a.path()
+                    .segments
+                    .last()
+                    .is_some_and(|s| s.ident == "test" || s.ident == "benchmark")
+            }) {
+                return;
+            }
+            let variables = std::mem::take(&mut self.variables);
+            for input in &signature.inputs {
+                if let FnArg::Typed(input) = input {
+                    self.declare(&input.pat, Some(&input.ty), None);
+                }
+            }
+            self.visit_block(block);
+            self.variables = variables;
+        }
+
+        /// Declares the variable when its type or initialiser indicate a balance or block number.
+        fn declare(&mut self, pattern: &Pat, ty: Option<&Type>, init: Option<&Expr>) {
+            let typed = ty.is_some_and(|ty| {
+                let mut identifiers = HashSet::new();
+                self::identifiers(ty.to_token_stream(), &mut identifiers);
+                identifiers
+                    .iter()
+                    .any(|i| i.contains("Balance") || i.contains("BlockNumber"))
+            });
+            let initialised = init.is_some_and(|init| {
+                let mut identifiers = HashSet::new();
+                self::identifiers(init.to_token_stream(), &mut identifiers);
+                VALUES.iter().any(|v| identifiers.contains(*v))
+            });
+            if let (true, Pat::Ident(pattern)) = (typed || initialised, pattern) {
+                self.variables.insert(pattern.ident.to_string());
+            }
+        }
+
+        /// Whether the expression is (or directly references) a balance or block number.
+        fn value(&self, expr: &Expr) -> bool {
+            match expr {
+                Expr::Path(path) => path
+                    .path
+                    .get_ident()
+                    .is_some_and(|i| self.variables.contains(&i.to_string())),
+                Expr::Call(ExprCall { func, .. }) => matches!(&**func, Expr::Path(path)
+                    if path.path.segments.last().is_some_and(|s| VALUES.iter().any(|v| s.ident == v))),
+                Expr::MethodCall(call) => VALUES.iter().any(|v| call.method == v),
+                Expr::Paren(expr) => self.value(&expr.expr),
+                Expr::Reference(expr) => self.value(&expr.expr),
+                Expr::Unary(expr) => self.value(&expr.expr),
+                _ => false,
+            }
+        }
+    }
+    impl<'a> Visit<'a> for Arithmetic {
+        fn visit_item_mod(&mut self, module: &'a ItemMod) {
+            let test = module
+                .attrs
+                .iter()
+                .any(|a| a.path().is_ident("cfg") && arguments(a).contains("test"));
+            if !test {
+                visit::visit_item_mod(self, module);
+            }
+        }
+
+        fn visit_item_fn(&mut self, function: &'a ItemFn) {
+            self.function(&function.attrs, &function.sig, &function.block);
+        }
+
+        fn visit_impl_item_fn(&mut self, function: &'a ImplItemFn) {
+            self.function(&function.attrs, &function.sig, &function.block);
+        }
+
+        fn visit_local(&mut self, local: &'a Local) {
+            visit::visit_local(self, local);
+            let init = local.init.as_ref().map(|i| &*i.expr);
+            match &local.pat {
+                Pat::Type(pattern) => self.declare(&pattern.pat, Some(&pattern.ty), init),
+                pattern => self.declare(pattern, None, init),
+            }
+        }
+
+        fn visit_expr_binary(&mut self, expr: &'a ExprBinary) {
+            let operator = match expr.op {
+                BinOp::Add(_) | BinOp::AddAssign(_) => Some(("+", "add")),
+                BinOp::Sub(_) | BinOp::SubAssign(_) => Some(("-", "sub")),
+                BinOp::Mul(_) | BinOp::MulAssign(_) => Some(("*", "mul")),
+                _ => None,
+            };
+            if let Some(operator) = operator {
+                if self.value(&expr.left) || self.value(&expr.right) {
+                    self.operations.push((operator, expr.op.span()));
+                }
+            }
+            visit::visit_expr_binary(self, expr);
+        }
+
+        fn visit_macro(&mut self, mac: &'a Macro) {
+            if mac.path.is_ident("ensure") {
+                if let Ok(arguments) =
+                    mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                {
+                    for argument in &arguments {
+                        self.visit_expr(argument);
+                    }
+                }
+            }
+            visit::visit_macro(self, mac);
+        }
+    }
+
+    let mut arithmetic = Arithmetic::default();
+    arithmetic.visit_file(&source.file);
+    arithmetic.operations
+}
+
+/// Pallets intended for development or testing only, keyed by crate name (prefix), along with the reason.
+const DEV_PALLETS: [(&str, &str); 7] = [
+    ("pallet_sudo", "grants a single account root access"),
+    (
+        "pallet_insecure_randomness_collective_flip",
+        "provides insecure randomness",
+    ),
+    ("pallet_template", "is a template"),
+    ("pallet_example", "is an example"),
+    ("pallet_dev_mode", "is an example"),
+    ("pallet_root_testing", "is intended for testing only"),
+    ("pallet_faucet", "freely issues funds"),
+];
+
+/// Returns the pallets of any runtime declared within the source, via `construct_runtime!` or a
+/// `#[frame_support::runtime]` module, along with the (crate) name of each pallet.
+pub(crate) fn runtime_pallets(file: &syn::File) -> Vec<(Ident, String)> {
+    #[derive(Default)]
+    struct Runtime(Vec<(Ident, String)>);
+    impl<'a> Visit<'a> for Runtime {
+        fn visit_macro(&mut self, mac: &'a Macro) {
+            if mac.path.is_ident("construct_runtime") {
+                // Pallets are declared within the braces as `Name: path::to::pallet {..} = index,`
+                let body = mac.tokens.clone().into_iter().find_map(|t| match t {
+                    TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                        Some(group.stream())
+                    }
+                    _ => None,
+                });
+                let mut tokens = body.into_iter().flatten().peekable();
+                while let Some(token) = tokens.next() {
+                    let TokenTree::Ident(name) = token else {
+                        continue;
+                    };
+                    if !matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ':') {
+                        continue;
+                    }
+                    tokens.next();
+                    if let Some(TokenTree::Ident(krate)) = tokens.next() {
+                        self.0.push((name, krate.to_string()));
+                    }
+                    // Skip the remainder of the declaration
+                    for token in tokens.by_ref() {
+                        if matches!(token, TokenTree::Punct(p) if p.as_char() == ',') {
+                            break;
+                        }
+                    }
+                }
+            }
+            visit::visit_macro(self, mac);
+        }
+
+        fn visit_item_mod(&mut self, module: &'a ItemMod) {
+            let runtime = module.attrs.iter().any(|a| {
+                a.path().is_ident("runtime")
+                    || a.path()
+                        .segments
+                        .iter()
+                        .map(|s| s.ident.to_string())
+                        .eq(["frame_support", "runtime"])
+            });
+            if runtime {
+                for item in items(module) {
+                    if let Item::Type(item) = item {
+                        if let Type::Path(path) = &*item.ty {
+                            if let Some(krate) = path.path.segments.first() {
+                                self.0.push((item.ident.clone(), krate.ident.to_string()));
+                            }
+                        }
+                    }
+                }
+            }
+            visit::visit_item_mod(self, module);
+        }
+    }
+
+    let mut runtime = Runtime::default();
+    runtime.visit_file(file);
+    runtime.0
+}
+
+/// Whether any of the sources declare a FRAME pallet.
+pub(crate) fn declares_pallet(sources: &[Source]) -> bool {
+    sources
+        .iter()
+        .any(|source| !Pallet::find(source).is_empty())
+}
+
+/// The items declared inline within the module.
+fn items(module: &ItemMod) -> impl Iterator<Item = &Item> {
+    module.content.iter().flat_map(|(_, items)| items)
+}
+
+/// Whether the attribute is a pallet attribute: `#[frame_support::pallet]`/`#[pallet]` when no name is provided,
+/// otherwise `#[pallet::<name>]`.
+fn is_pallet_attribute(attribute: &Attribute, name: Option<&str>) -> bool {
+    let segments: Vec<_> = attribute
+        .path()
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect();
+    match name {
+        None => matches!(
+            segments.iter().map(String::as_str).collect::<Vec<_>>()[..],
+            ["pallet"] | ["frame_support", "pallet"] | ["frame", "pallet"]
+        ),
+        Some(name) => segments.len() == 2 && segments[0] == "pallet" && segments[1] == name,
+    }
+}
+
+/// Returns the identifiers referenced within the tests (functions annotated with `#[test]`) of the sources, including
+/// those within macro invocations.
+fn tested(sources: &[Source]) -> HashSet<String> {
+    #[derive(Default)]
+    struct Tests(HashSet<String>);
+    impl<'a> Visit<'a> for Tests {
+        fn visit_item_fn(&mut self, function: &'a ItemFn) {
+            if function.attrs.iter().any(|a| a.path().is_ident("test")) {
+                identifiers(function.block.to_token_stream(), &mut self.0);
+            }
+            visit::visit_item_fn(self, function);
+        }
+    }
+
+    let mut tests = Tests::default();
+    for source in sources {
+        tests.visit_file(&source.file);
+    }
+    tests.0
+}
+
+/// Returns the names of the benchmarks declared within the sources, either via the `benchmarks!` macro (v1) or as
+/// `#[benchmark]` functions within a `#[benchmarks]` module (v2).
+fn benchmarked(sources: &[Source]) -> HashSet<String> {
+    #[derive(Default)]
+    struct Benchmarks(HashSet<String>);
+    impl<'a> Visit<'a> for Benchmarks {
+        fn visit_item_fn(&mut self, function: &'a ItemFn) {
+            if function.attrs.iter().any(|a| {
+                a.path()
+                    .segments
+                    .last()
+                    .is_some_and(|s| s.ident == "benchmark")
+            }) {
+                self.0.insert(function.sig.ident.to_string());
+            }
+            visit::visit_item_fn(self, function);
+        }
+
+        fn visit_macro(&mut self, mac: &'a Macro) {
+            if mac.path.segments.last().is_some_and(|s| {
+                matches!(
+                    s.ident.to_string().as_str(),
+                    "benchmarks" | "benchmarks_instance" | "benchmarks_instance_pallet"
+                )
+            }) {
+                // Benchmarks are declared as `name { .. }: call(..) verify { .. }`, so any top-level identifier
+                // followed by a block (other than keywords of the macro) names a benchmark
+                let mut tokens = mac.tokens.clone().into_iter().peekable();
+                while let Some(token) = tokens.next() {
+                    if let (TokenTree::Ident(ident), Some(TokenTree::Group(group))) =
+                        (&token, tokens.peek())
+                    {
+                        if group.delimiter() == Delimiter::Brace
+                            && ident != "verify"
+                            && ident != "where_clause"
+                        {
+                            self.0.insert(ident.to_string());
+                        }
+                    }
+                }
+            }
+            visit::visit_macro(self, mac);
+        }
+    }
+
+    let mut benchmarks = Benchmarks::default();
+    for source in sources {
+        benchmarks.visit_file(&source.file);
+    }
+    benchmarks.0
+}
+
+/// Returns the identifiers of the migrations included within the `Executive` of a runtime declared within the sources,
+/// resolving any type aliases, or `None` if no `Executive` is declared.
+fn migrations<'a>(sources: impl Iterator<Item = &'a Source>) -> Option<HashSet<String>> {
+    #[derive(Default)]
+    struct Aliases<'a>(HashMap<String, &'a Type>);
+    impl<'a> Visit<'a> for Aliases<'a> {
+        fn visit_item_type(&mut self, item: &'a ItemType) {
+            self.0.insert(item.ident.to_string(), &item.ty);
+            visit::visit_item_type(self, item);
+        }
+    }
+
+    let mut aliases = Aliases::default();
+    for source in sources {
+        aliases.visit_file(&source.file);
+    }
+    let aliases = aliases.0;
+    // The migrations are the sixth (optional) generic argument of `frame_executive::Executive`
+    let Type::Path(executive) = aliases.get("Executive")? else {
+        return None;
+    };
+    let segment = executive.path.segments.last()?;
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    let mut included = HashSet::new();
+    let Some(migrations) = arguments.args.iter().nth(5) else {
+        return Some(included);
+    };
+    let mut pending = HashSet::new();
+    identifiers(migrations.to_token_stream(), &mut pending);
+    while let Some(identifier) = pending.iter().next().cloned() {
+        pending.remove(&identifier);
+        if let Some(alias) = aliases
+            .get(&identifier)
+            .filter(|_| !included.contains(&identifier))
+        {
+            identifiers(alias.to_token_stream(), &mut pending);
+        }
+        included.insert(identifier);
+    }
+    Some(included)
+}
+
+/// Returns the types implementing `OnRuntimeUpgrade` (or `UncheckedOnRuntimeUpgrade`) within the sources, along with
+/// their source.
+fn upgrades(sources: &[Source]) -> Vec<(&Source, &Ident)> {
+    #[derive(Default)]
+    struct Upgrades<'a>(Vec<&'a Ident>);
+    impl<'a> Visit<'a> for Upgrades<'a> {
+        fn visit_item_impl(&mut self, item: &'a ItemImpl) {
+            let upgrade = item.trait_.as_ref().is_some_and(|(_, path, _)| {
+                path.segments.last().is_some_and(|s| {
+                    s.ident == "OnRuntimeUpgrade" || s.ident == "UncheckedOnRuntimeUpgrade"
+                })
+            });
+            if let (true, Type::Path(ty)) = (upgrade, &*item.self_ty) {
+                if let Some(segment) = ty.path.segments.last() {
+                    self.0.push(&segment.ident);
+                }
+            }
+            visit::visit_item_impl(self, item);
+        }
+    }
+
+    sources
+        .iter()
+        .flat_map(|source| {
+            let mut upgrades = Upgrades::default();
+            upgrades.visit_file(&source.file);
+            upgrades.0.into_iter().map(move |ident| (source, ident))
+        })
+        .collect()
+}
+
+/// Returns the structs and enums declared within the sources, along with whether each implements `MaxEncodedLen`,
+/// either via derive or manually.
+fn max_encoded_len(sources: &[Source]) -> HashMap<String, bool> {
+    #[derive(Default)]
+    struct Types(HashMap<String, bool>);
+    impl Types {
+        fn declare(&mut self, ident: &Ident, attributes: &[Attribute]) {
+            let derived = attributes
+                .iter()
+                .filter(|a| a.path().is_ident("derive"))
+                .any(|a| arguments(a).contains("MaxEncodedLen"));
+            *self.0.entry(ident.to_string()).or_default() |= derived;
+        }
+    }
+    impl<'a> Visit<'a> for Types {
+        fn visit_item_struct(&mut self, item: &'a ItemStruct) {
+            self.declare(&item.ident, &item.attrs);
+            visit::visit_item_struct(self, item);
+        }
+
+        fn visit_item_enum(&mut self, item: &'a ItemEnum) {
+            self.declare(&item.ident, &item.attrs);
+            visit::visit_item_enum(self, item);
+        }
+
+        fn visit_item_impl(&mut self, item: &'a ItemImpl) {
+            let implemented = item.trait_.as_ref().is_some_and(|(_, path, _)| {
+                path.segments
+                    .last()
+                    .is_some_and(|s| s.ident == "MaxEncodedLen")
+            });
+            if let (true, Type::Path(ty)) = (implemented, &*item.self_ty) {
+                if let Some(segment) = ty.path.segments.last() {
+                    self.0.insert(segment.ident.to_string(), true);
+                }
+            }
+            visit::visit_item_impl(self, item);
+        }
+    }
+
+    let mut types = Types::default();
+    for source in sources {
+        types.visit_file(&source.file);
+    }
+    types.0
+}
+
+/// Collects the values of the integer literals within the tokens, e.g. `10_000` or `0u64`.
+fn integers(tokens: TokenStream, integers: &mut Vec<u128>) {
+    for token in tokens {
+        match token {
+            TokenTree::Literal(literal) => {
+                if let Ok(value) =
+                    syn::parse_str::<LitInt>(&literal.to_string()).and_then(|l| l.base10_parse())
+                {
+                    integers.push(value);
+                }
+            }
+            TokenTree::Group(group) => self::integers(group.stream(), integers),
+            _ => {}
+        }
+    }
+}
+
+/// Collects the identifiers within the tokens, including those within any groups.
+pub(crate) fn identifiers(tokens: TokenStream, identifiers: &mut HashSet<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                identifiers.insert(ident.to_string());
+            }
+            TokenTree::Group(group) => self::identifiers(group.stream(), identifiers),
+            _ => {}
+        }
+    }
+}
+
+/// Parses the Rust source files within the package directory, excluding hidden and `target` directories. Files which
+/// cannot be parsed are skipped.
+pub(crate) fn sources(directory: &Path, root: &Path) -> Vec<Source> {
+    fn files(directory: &Path, paths: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(directory) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        entries.sort();
+        for path in entries {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if name.starts_with('.') || name == "target" {
+                continue;
+            }
+            if path.is_dir() {
+                // Nested packages are analysed separately
+                if !path.join("Cargo.toml").exists() {
+                    files(&path, paths);
+                }
+            } else if path.extension().is_some_and(|e| e == "rs") {
+                paths.push(path);
+            }
+        }
+    }
+
+    let mut paths = Vec::new();
+    files(directory, &mut paths);
+    paths
+        .into_iter()
+        .filter_map(|path| Source::parse(&path, root))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(code: &str) -> Source {
+        Source {
+            path: "src/lib.rs".into(),
+            file: syn::parse_file(code).unwrap(),
+        }
+    }
+
+    /// The messages of the findings of the analysis with the code.
+    fn findings(sources: &[Source], code: &str) -> Vec<String> {
+        analyse(sources, None, &[])
+            .into_iter()
+            .filter(|f| f.code.as_deref() == Some(code))
+            .map(|f| f.message)
+            .collect()
+    }
+
+    const PALLET: &str = r#"
+        #[frame_support::pallet]
+        pub mod pallet {
+            #[pallet::call]
+            impl<T: Config> Pallet<T> {
+                #[pallet::call_index(0)]
+                #[pallet::weight(T::WeightInfo::store())]
+                pub fn store(origin: OriginFor<T>, value: u32) -> DispatchResult {
+                    ensure_signed(origin)?;
+                    Something::<T>::put(value);
+                    Self::deposit_event(Event::Stored { value });
+                    Ok(())
+                }
+            }
+        }
+    "#;
+
+    #[test]
+    fn reports_untested_calls() {
+        let sources = [source(PALLET)];
+        assert!(!tested(&sources).contains("store"));
+        assert_eq!(
+            findings(&sources, "sbp::untested_call"),
+            ["call 'store' has no direct tests"]
+        );
+    }
+
+    #[test]
+    fn calls_referenced_within_tests_are_tested() {
+        let sources = [
+            source(PALLET),
+            source(
+                r#"
+                #[test]
+                fn stores_value() {
+                    new_test_ext().execute_with(|| {
+                        assert_ok!(Template::store(RuntimeOrigin::signed(1), 42));
+                    });
+                }
+                "#,
+            ),
+        ];
+        assert!(tested(&sources).contains("store"));
+        assert!(findings(&sources, "sbp::untested_call").is_empty());
+    }
+
+    /// A pallet with a single call, `store`, with the attributes.
+    fn call(attributes: &str) -> Source {
+        source(&PALLET.replace(
+            "#[pallet::call_index(0)]\n                #[pallet::weight(T::WeightInfo::store())]",
+            attributes,
+        ))
+    }
+
+    #[test]
+    fn reports_zero_and_constant_weights() {
+        for (attributes, code, message) in [
+            (
+                "#[pallet::weight(Weight::zero())]",
+                "sbp::zero_weight",
+                "call 'store' has zero weight",
+            ),
+            (
+                "#[pallet::weight(Weight::from_parts(0, 0))]",
+                "sbp::zero_weight",
+                "call 'store' has zero weight",
+            ),
+            (
+                "#[pallet::weight(Weight::from_parts(10_000, 0))]",
+                "sbp::constant_weight",
+                "call 'store' has a constant weight, rather than one derived from benchmarks",
+            ),
+            (
+                "#[pallet::weight(T::DbWeight::get().writes(1))]",
+                "sbp::missing_weight_info",
+                "weight of call 'store' is not derived from benchmarks via `WeightInfo`",
+            ),
+        ] {
+            assert_eq!(
+                findings(&[call(attributes)], code),
+                [message],
+                "{attributes}"
+            );
+        }
+        let dev_mode = source(
+            &PALLET
+                .replace(
+                    "#[frame_support::pallet]",
+                    "#[frame_support::pallet(dev_mode)]",
+                )
+                .replace("#[pallet::weight(T::WeightInfo::store())]", ""),
+        );
+        assert_eq!(
+            findings(&[dev_mode], "sbp::zero_weight"),
+            ["call 'store' has no weight and so zero weight (dev_mode)"]
+        );
+    }
+
+    #[test]
+    fn benchmarked_weights_are_accepted() {
+        let weights = |sources: &[Source]| {
+            [
+                "sbp::zero_weight",
+                "sbp::constant_weight",
+                "sbp::missing_weight_info",
+            ]
+            .iter()
+            .flat_map(|code| findings(sources, code))
+            .collect::<Vec<_>>()
+        };
+        assert!(weights(&[source(PALLET)]).is_empty());
+        let default = source(
+            &PALLET
+                .replace(
+                    "#[frame_support::pallet]",
+                    "#[frame_support::pallet(dev_mode)]",
+                )
+                .replace(
+                    "#[pallet::call]",
+                    "#[pallet::call(weight(<T as Config>::WeightInfo))]",
+                )
+                .replace("#[pallet::weight(T::WeightInfo::store())]", ""),
+        );
+        assert!(weights(&[default]).is_empty());
+    }
+
+    #[test]
+    fn reports_unbenchmarked_calls() {
+        let sources = [source(PALLET)];
+        assert!(benchmarked(&sources).is_empty());
+        assert_eq!(
+            findings(&sources, "sbp::unbenchmarked_call"),
+            ["call 'store' has no corresponding benchmark"]
+        );
+    }
+
+    #[test]
+    fn calls_with_benchmarks_of_the_same_name_are_benchmarked() {
+        let v1 = source(
+            r#"
+            benchmarks! {
+                store {
+                    let caller: T::AccountId = whitelisted_caller();
+                }: _(RawOrigin::Signed(caller), 42)
+                verify {
+                    assert_eq!(Something::<T>::get(), Some(42));
+                }
+            }
+            "#,
+        );
+        let v2 = source(
+            r#"
+            #[benchmarks]
+            mod benchmarks {
+                #[benchmark]
+                fn store() {
+                    #[extrinsic_call]
+                    _(RawOrigin::Signed(whitelisted_caller()), 42);
+                }
+            }
+            "#,
+        );
+        for benchmarks in [v1, v2] {
+            assert_eq!(
+                benchmarked(std::slice::from_ref(&benchmarks)),
+                HashSet::from(["store".into()])
+            );
+            assert!(findings(&[source(PALLET), benchmarks], "sbp::unbenchmarked_call").is_empty());
+        }
+    }
+
+    #[test]
+    fn reports_calls_without_call_index() {
+        assert_eq!(
+            findings(
+                &[call("#[pallet::weight(T::WeightInfo::store())]")],
+                "sbp::missing_call_index"
+            ),
+            ["call 'store' has no explicit call index"]
+        );
+        assert!(findings(&[source(PALLET)], "sbp::missing_call_index").is_empty());
+    }
+
+    /// A pallet with the storage items (and any supporting items).
+    fn storage(items: &str) -> Source {
+        source(&format!(
+            r#"
+            #[frame_support::pallet]
+            pub mod pallet {{
+                #[pallet::pallet]
+                #[pallet::storage_version(STORAGE_VERSION)]
+                pub struct Pallet<T>(_);
+
+                {items}
+            }}
+            "#
+        ))
+    }
+
+    #[test]
+    fn reports_unbounded_storage() {
+        let sources = [storage(
+            r#"
+            #[pallet::storage]
+            pub type Names<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, Vec<u8>>;
+            #[pallet::storage]
+            pub type Keys<T: Config> = StorageMap<Hasher = Twox64Concat, Key = String, Value = u32>;
+            "#,
+        )];
+        assert_eq!(
+            findings(&sources, "sbp::unbounded_storage"),
+            [
+                "storage 'Names' has an unbounded value type ('Vec'), use a bounded alternative such as `BoundedVec` or `BoundedBTreeMap`",
+                "storage 'Keys' has an unbounded key type ('String'), use a bounded alternative such as `BoundedVec` or `BoundedBTreeMap`",
+            ]
+        );
+    }
+
+    #[test]
+    fn bounded_storage_is_accepted() {
+        let sources = [storage(
+            r#"
+            #[pallet::storage]
+            pub type Names<T: Config> =
+                StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<u8, T::MaxLength>>;
+            #[pallet::storage]
+            pub type Value<T> = StorageValue<_, u32, ValueQuery>;
+            "#,
+        )];
+        assert!(findings(&sources, "sbp::unbounded_storage").is_empty());
+    }
+
+    #[test]
+    fn reports_storage_without_storage_info() {
+        let sources = [source(
+            r#"
+            #[frame_support::pallet]
+            pub mod pallet {
+                #[pallet::pallet]
+                #[pallet::without_storage_info]
+                pub struct Pallet<T>(_);
+
+                #[derive(Encode, Decode, TypeInfo)]
+                pub struct Details {
+                    owner: u64,
+                }
+
+                #[pallet::storage]
+                #[pallet::unbounded]
+                pub type Owners<T> = StorageMap<_, Twox64Concat, u32, Details>;
+            }
+            "#,
+        )];
+        assert_eq!(
+            findings(&sources, "sbp::without_storage_info"),
+            [
+                "pallet is declared without storage info, so the proof size of its storage is unbounded",
+                "storage 'Owners' is declared as unbounded, so its proof size is not accounted for",
+            ]
+        );
+        assert_eq!(
+            findings(&sources, "sbp::missing_max_encoded_len"),
+            ["type 'Details' of storage 'Owners' does not implement `MaxEncodedLen`"]
+        );
+    }
+
+    #[test]
+    fn storage_with_max_encoded_len_is_accepted() {
+        let sources = [
+            storage(
+                r#"
+                #[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+                pub struct Details {
+                    owner: u64,
+                }
+                pub enum Status {
+                    Active,
+                }
+
+                #[pallet::storage]
+                pub type Owners<T> = StorageDoubleMap<_, Twox64Concat, Status, Twox64Concat, u32, Details>;
+                "#,
+            ),
+            source("impl MaxEncodedLen for Status { fn max_encoded_len() -> usize { 1 } }"),
+        ];
+        assert!(findings(&sources, "sbp::without_storage_info").is_empty());
+        assert!(findings(&sources, "sbp::missing_max_encoded_len").is_empty());
+    }
+
+    #[test]
+    fn reports_storage_without_storage_version() {
+        let sources = [source(
+            r#"
+            #[frame_support::pallet]
+            pub mod pallet {
+                #[pallet::pallet]
+                pub struct Pallet<T>(_);
+
+                #[pallet::storage]
+                pub type Value<T> = StorageValue<_, u32>;
+            }
+            "#,
+        )];
+        assert_eq!(
+            findings(&sources, "sbp::missing_storage_version"),
+            ["pallet declares storage but no storage version, via `#[pallet::storage_version(..)]`"]
+        );
+        let versioned = [storage(
+            "#[pallet::storage] pub type Value<T> = StorageValue<_, u32>;",
+        )];
+        assert!(findings(&versioned, "sbp::missing_storage_version").is_empty());
+    }
+
+    #[test]
+    fn reports_migrations_not_included_within_executive() {
+        let runtime = source(
+            r#"
+            type Migrations = (pallet_template::migrations::v1::MigrateToV1<Runtime>,);
+            pub type Executive = frame_executive::Executive<
+                Runtime,
+                Block,
+                frame_system::ChainContext<Runtime>,
+                Runtime,
+                AllPalletsWithSystem,
+                Migrations,
+            >;
+            "#,
+        );
+        let included = migrations([&runtime].into_iter()).unwrap();
+        assert!(included.contains("Migrations") && included.contains("MigrateToV1"));
+        let pallet = [source(
+            r#"
+            pub struct MigrateToV1<T>(PhantomData<T>);
+            impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {}
+            pub struct MigrateToV2<T>(PhantomData<T>);
+            impl<T: Config> UncheckedOnRuntimeUpgrade for MigrateToV2<T> {}
+            "#,
+        )];
+        let unused: Vec<_> = analyse(&pallet, Some(&included), &[])
+            .into_iter()
+            .filter(|f| f.code.as_deref() == Some("sbp::unused_migration"))
+            .map(|f| f.message)
+            .collect();
+        assert_eq!(
+            unused,
+            ["migration 'MigrateToV2' is not included within the migrations of the runtime's `Executive`"]
+        );
+        // Without an `Executive`, inclusion cannot be determined
+        assert!(migrations(pallet.iter()).is_none());
+    }
+
+    /// A pallet with the hooks.
+    fn hooks(functions: &str) -> Source {
+        source(&format!(
+            r#"
+            #[frame_support::pallet]
+            pub mod pallet {{
+                #[pallet::hooks]
+                impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {{
+                    {functions}
+                }}
+            }}
+            "#
+        ))
+    }
+
+    #[test]
+    fn reports_unbounded_and_unweighted_hooks() {
+        let sources = [hooks(
+            r#"
+            fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+                for (key, _) in Pending::<T>::iter() {
+                    Pending::<T>::remove(key);
+                }
+                Weight::zero()
+            }
+            fn on_finalize(n: BlockNumberFor<T>) {
+                loop {
+                    break;
+                }
+            }
+            "#,
+        )];
+        assert_eq!(
+            findings(&sources, "sbp::unbounded_hook"),
+            [
+                "on_initialize iterates over storage (`iter`), which is potentially unbounded",
+                "on_finalize contains a `loop`, which is potentially unbounded",
+            ]
+        );
+        assert_eq!(
+            findings(&sources, "sbp::missing_hook_weight"),
+            ["on_initialize performs work but its weight is not returned from on_initialize via `WeightInfo` or `DbWeight`"]
+        );
+    }
+
+    #[test]
+    fn bounded_and_weighted_hooks_are_accepted() {
+        let sources = [hooks(
+            r#"
+            fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+                Pending::<T>::kill();
+                T::DbWeight::get().writes(1)
+            }
+            fn on_finalize(n: BlockNumberFor<T>) {
+                Pending::<T>::kill();
+            }
+            "#,
+        )];
+        assert!(findings(&sources, "sbp::unbounded_hook").is_empty());
+        assert!(findings(&sources, "sbp::missing_hook_weight").is_empty());
+    }
+
+    #[test]
+    fn reports_calls_without_events_and_unused_events() {
+        let sources = [source(
+            r#"
+            #[frame_support::pallet]
+            pub mod pallet {
+                #[pallet::event]
+                pub enum Event<T: Config> {
+                    Stored { value: u32 },
+                }
+
+                #[pallet::call]
+                impl<T: Config> Pallet<T> {
+                    pub fn store(origin: OriginFor<T>, value: u32) -> DispatchResult {
+                        ensure_signed(origin)?;
+                        Something::<T>::put(value);
+                        Ok(())
+                    }
+                }
+            }
+            "#,
+        )];
+        assert_eq!(
+            findings(&sources, "sbp::missing_event"),
+            ["call 'store' mutates storage but deposits no event"]
+        );
+        assert_eq!(
+            findings(&sources, "sbp::unused_event"),
+            ["event 'Stored' is never deposited"]
+        );
+    }
+
+    #[test]
+    fn events_deposited_directly_or_via_helpers_are_accepted() {
+        let helper = source(&PALLET.replace(
+            "Self::deposit_event(Event::Stored { value });",
+            "Self::stored(value);",
+        ));
+        let helpers = source(
+            r#"
+            impl<T: Config> Pallet<T> {
+                fn stored(value: u32) {
+                    Self::deposit_event(Event::Stored { value });
+                }
+            }
+            "#,
+        );
+        assert!(findings(&[source(PALLET)], "sbp::missing_event").is_empty());
+        assert!(findings(&[helper, helpers], "sbp::missing_event").is_empty());
+    }
+
+    #[test]
+    fn reports_calls_without_origin_checks() {
+        let sources = [source(&PALLET.replace("ensure_signed(origin)?;", ""))];
+        assert_eq!(
+            findings(&sources, "sbp::missing_origin_check"),
+            ["call 'store' does not check its origin (e.g. via `ensure_signed`), so may be missing authorization"]
+        );
+    }
+
+    #[test]
+    fn origins_checked_directly_or_via_helpers_are_accepted() {
+        let helper =
+            source(&PALLET.replace("ensure_signed(origin)?;", "Self::authorize(origin)?;"));
+        let helpers = source(
+            r#"
+            impl<T: Config> Pallet<T> {
+                fn authorize(origin: OriginFor<T>) -> DispatchResult {
+                    T::AdminOrigin::ensure_origin(origin)?;
+                    Ok(())
+                }
+            }
+            "#,
+        );
+        assert!(findings(&[source(PALLET)], "sbp::missing_origin_check").is_empty());
+        assert!(findings(&[helper, helpers], "sbp::missing_origin_check").is_empty());
+    }
+
+    /// The unchecked operators within the source.
+    fn operators(code: &str) -> Vec<&'static str> {
+        arithmetic(&source(code))
+            .into_iter()
+            .map(|((operator, _), _)| operator)
+            .collect()
+    }
+
+    #[test]
+    fn reports_unchecked_arithmetic_on_balances_and_block_numbers() {
+        assert_eq!(
+            operators(
+                r#"
+                fn expiry<T: Config>(amount: BalanceOf<T>, fee: BalanceOf<T>) -> BlockNumberFor<T> {
+                    let now = frame_system::Pallet::<T>::block_number();
+                    let mut total = amount * 2u32.into();
+                    total -= fee;
+                    ensure!(T::Currency::free_balance(&who) + fee > total, Error::<T>::Low);
+                    now + T::Period::get()
+                }
+                "#
+            ),
+            ["*", "-", "+", "+"]
+        );
+        let sources = [source(&PALLET.replace(
+            "Something::<T>::put(value);",
+            "Something::<T>::put(frame_system::Pallet::<T>::block_number() + 1);",
+        ))];
+        assert_eq!(
+            findings(&sources, "sbp::unchecked_arithmetic"),
+            ["unchecked arithmetic (`+`) on a balance or block number, use `checked_add`, `saturating_add` or `ensure_add` instead"]
+        );
+    }
+
+    #[test]
+    fn checked_arithmetic_and_other_values_are_accepted() {
+        assert!(operators(
+            r#"
+            fn expiry<T: Config>(amount: BalanceOf<T>, count: u32) -> u32 {
+                let now = frame_system::Pallet::<T>::block_number();
+                let total = amount.saturating_mul(2u32.into());
+                let _ = now.checked_add(&T::Period::get());
+                count + 1
+            }
+
+            #[cfg(test)]
+            mod tests {
+                #[test]
+                fn expires() {
+                    let now: BlockNumber = 1;
+                    assert_eq!(now + 1, 2);
+                }
+            }
+            "#
+        )
+        .is_empty());
+        assert!(findings(&[source(PALLET)], "sbp::unchecked_arithmetic").is_empty());
+    }
+
+    #[test]
+    fn reports_development_pallets_within_runtimes() {
+        let sources = [
+            source(
+                r#"
+                construct_runtime!(
+                    pub enum Runtime {
+                        System: frame_system = 0,
+                        Sudo: pallet_sudo::{Pallet, Call, Storage, Event<T>} = 1,
+                    }
+                );
+                "#,
+            ),
+            source(
+                r#"
+                #[frame_support::runtime]
+                mod runtime {
+                    #[runtime::pallet_index(2)]
+                    pub type TemplatePallet = pallet_template::Pallet<Runtime>;
+                }
+                "#,
+            ),
+        ];
+        assert_eq!(
+            findings(&sources, "sbp::dev_pallet"),
+            [
+                "runtime includes development pallet 'Sudo' (pallet_sudo), which grants a single account root access",
+                "runtime includes development pallet 'TemplatePallet' (pallet_template), which is a template",
+            ]
+        );
+    }
+
+    #[test]
+    fn allowed_and_production_pallets_are_accepted() {
+        let sources = [source(
+            r#"
+            construct_runtime!(
+                pub enum Runtime {
+                    System: frame_system = 0,
+                    Balances: pallet_balances = 1,
+                    Sudo: pallet_sudo = 2,
+                    Faucet: pallet_faucet = 3,
+                }
+            );
+            "#,
+        )];
+        let allowed = ["Sudo".into(), "pallet-faucet".into()];
+        assert!(analyse(&sources, None, &allowed)
+            .iter()
+            .all(|f| f.code.as_deref() != Some("sbp::dev_pallet")));
+    }
+
+    #[test]
+    fn reports_undocumented_pallet_items() {
+        let sources = [source(
+            r#"
+            #[frame_support::pallet]
+            pub mod pallet {
+                #[pallet::config]
+                pub trait Config: frame_system::Config {
+                    type Currency: Currency<Self::AccountId>;
+                }
+                #[pallet::storage]
+                pub type Value<T> = StorageValue<_, u32>;
+                #[pallet::error]
+                pub enum Error<T> {
+                    Overflow,
+                }
+            }
+            "#,
+        )];
+        assert_eq!(
+            findings(&sources, "sbp::undocumented_pallet_item"),
+            [
+                "config type 'Currency' has no doc comment",
+                "storage 'Value' has no doc comment",
+                "error 'Overflow' has no doc comment",
+            ]
+        );
+        assert_eq!(
+            findings(&[source(PALLET)], "sbp::undocumented_pallet_item"),
+            ["call 'store' has no doc comment"]
+        );
+    }
+
+    #[test]
+    fn documented_pallet_items_are_accepted() {
+        let sources = [source(
+            r#"
+            #[frame_support::pallet]
+            pub mod pallet {
+                #[pallet::config]
+                pub trait Config: frame_system::Config {
+                    /// The currency mechanism.
+                    type Currency: Currency<Self::AccountId>;
+                }
+                /// A value.
+                #[pallet::storage]
+                pub type Value<T> = StorageValue<_, u32>;
+                #[pallet::event]
+                pub enum Event<T: Config> {
+                    /// A value was stored.
+                    Stored { value: u32 },
+                }
+                #[pallet::call]
+                impl<T: Config> Pallet<T> {
+                    /// Stores a value.
+                    pub fn store(origin: OriginFor<T>) -> DispatchResult {
+                        Ok(())
+                    }
+                }
+            }
+            "#,
+        )];
+        assert!(findings(&sources, "sbp::undocumented_pallet_item").is_empty());
+    }
+}
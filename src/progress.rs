@@ -0,0 +1,72 @@
+//! Progress indication for long-running phases, such as linting and testing.
+
+use crate::{Context, Format};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::{
+    io::{stderr, IsTerminal},
+    time::Duration,
+};
+
+/// A spinner (or progress bar, when the number of packages is known) shown on stderr during a phase, along with the
+/// elapsed time and the package currently being processed. Nothing is shown when emitting structured output or when
+/// stderr is not a terminal, so that output remains machine-friendly.
+pub(crate) struct Progress(Option<ProgressBar>);
+
+impl Progress {
+    /// Starts indicating the progress of the phase, e.g. `clippy`, across the number of packages if known.
+    pub(crate) fn new(context: &Context, phase: &str, packages: Option<usize>) -> Self {
+        if context.format != Format::Text || !stderr().is_terminal() {
+            return Progress(None);
+        }
+        let (bar, template) = match packages {
+            Some(packages) => (
+                ProgressBar::new(packages as u64),
+                "{spinner:.cyan} {prefix:.bold} [{elapsed}] {bar:20.cyan/blue} {pos}/{len} {wide_msg}",
+            ),
+            None => (
+                ProgressBar::new_spinner(),
+                "{spinner:.cyan} {prefix:.bold} [{elapsed}] {wide_msg}",
+            ),
+        };
+        bar.set_style(ProgressStyle::with_template(template).expect("template is valid"));
+        bar.set_prefix(phase.to_string());
+        bar.enable_steady_tick(Duration::from_millis(100));
+        Progress(Some(bar))
+    }
+
+    /// Indicates that the package has been processed (e.g. checked), advancing any progress bar.
+    pub(crate) fn package(&self, package: &str) {
+        if let Some(bar) = &self.0 {
+            bar.set_message(package.to_string());
+            bar.inc(1);
+        }
+    }
+
+    /// Describes the current step of the phase, e.g. the test suite being executed.
+    pub(crate) fn message(&self, message: String) {
+        if let Some(bar) = &self.0 {
+            bar.set_message(message);
+        }
+    }
+
+    /// Hides the progress indication whilst executing the function, so that any output is not interleaved with it.
+    pub(crate) fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        match &self.0 {
+            Some(bar) => bar.suspend(f),
+            None => f(),
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if let Some(bar) = &self.0 {
+            bar.finish_and_clear();
+            eprintln!(
+                "{} finished after {:.1}s",
+                bar.prefix(),
+                bar.elapsed().as_secs_f64()
+            );
+        }
+    }
+}
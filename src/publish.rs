@@ -0,0 +1,360 @@
+//! Publishing of findings to GitHub, as inline review comments on a pull request or as a check run.
+
+use crate::{git, github, Context, Error, Finding, Severity};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A marker included within the body of each comment, used to identify comments previously published.
+const MARKER: &str = "<!-- sbp-review -->";
+/// The maximum number of comments published per review, and of annotations published per check run request.
+const BATCH: usize = 50;
+/// The name of the check run.
+const CHECK: &str = "sbp-review";
+
+/// The outcome of publishing findings to a pull request.
+#[derive(Default, Serialize)]
+pub struct Published {
+    /// The number of comments published.
+    pub comments: usize,
+    /// The number of reviews the comments were published within.
+    pub reviews: usize,
+    /// The number of findings already published by a previous run.
+    pub duplicates: usize,
+    /// The number of findings which are not located on a line of the diff of the pull request, and therefore cannot be
+    /// commented upon.
+    pub outside_diff: usize,
+    /// The number of findings without a location.
+    pub unlocated: usize,
+}
+
+/// A check run published for a commit.
+#[derive(Serialize)]
+pub struct Check {
+    /// The identifier of the check run.
+    pub id: u64,
+    /// The URL of the check run.
+    pub url: String,
+    /// The number of annotations published.
+    pub annotations: usize,
+    /// The number of requests the annotations were published within.
+    pub requests: usize,
+}
+
+/// An inline review comment.
+#[derive(Serialize)]
+struct Comment {
+    path: String,
+    line: u16,
+    side: &'static str,
+    body: String,
+}
+
+/// Publishes the findings as inline comments on the pull request of the repository (e.g. `owner/repo`), batched into
+/// reviews. Findings already published by a previous run are skipped.
+pub fn publish(
+    context: &Context,
+    repository: &str,
+    pull_request: u64,
+    findings: &[Finding],
+) -> Result<Published, Error> {
+    token()?;
+    let pull = format!("/repos/{}/pulls/{}", repository, pull_request);
+    let head = github::json(github::get(&pull), "GitHub pull request")?;
+    let commit = head["head"]["sha"]
+        .as_str()
+        .ok_or_else(|| Error::Config(format!("pull request {} has no head commit", pull)))?
+        .to_string();
+    let lines = lines(&github::get_all(
+        &format!("{}/files", pull),
+        "GitHub pull request files",
+    )?);
+    let mut existing: BTreeSet<(String, u64, String)> =
+        github::get_all(&format!("{}/comments", pull), "GitHub review comments")?
+            .iter()
+            .filter_map(|c| {
+                let body = c["body"].as_str().filter(|b| b.contains(MARKER))?;
+                Some((
+                    c["path"].as_str()?.to_string(),
+                    c["line"].as_u64().or(c["original_line"].as_u64())?,
+                    body.to_string(),
+                ))
+            })
+            .collect();
+
+    let prefix = prefix(context);
+    let mut published = Published::default();
+    let mut comments = Vec::new();
+    for finding in findings {
+        let Some(location) = &finding.location else {
+            published.unlocated += 1;
+            continue;
+        };
+        let path = format!("{}{}", prefix, location.file.trim_start_matches("./"));
+        if !lines
+            .get(&path)
+            .is_some_and(|l| l.contains(&u64::from(location.line)))
+        {
+            published.outside_diff += 1;
+            continue;
+        }
+        let body = body(finding);
+        if !existing.insert((path.clone(), location.line.into(), body.clone())) {
+            published.duplicates += 1;
+            continue;
+        }
+        comments.push(Comment {
+            path,
+            line: location.line,
+            side: "RIGHT",
+            body,
+        });
+    }
+
+    let batches = comments.len().div_ceil(BATCH);
+    for (index, batch) in comments.chunks(BATCH).enumerate() {
+        let review = json!({
+            "commit_id": commit,
+            "event": "COMMENT",
+            "body": format!(
+                "sbp-review: {} finding(s){}",
+                batch.len(),
+                match batches {
+                    1 => String::new(),
+                    batches => format!(" ({} of {})", index + 1, batches),
+                }
+            ),
+            "comments": batch,
+        });
+        github::json(
+            github::post(&format!("{}/reviews", pull), &review.to_string()),
+            "GitHub review",
+        )?;
+        published.comments += batch.len();
+        published.reviews += 1;
+    }
+    Ok(published)
+}
+
+/// Publishes the findings as a completed check run of the commit (defaulting to `HEAD`) of the repository (e.g.
+/// `owner/repo`), summarising the findings of each category and annotating those with a location. Annotations are
+/// published in batches, as each request is limited to 50 annotations. Check runs which have not failed but include
+/// errors or warnings conclude as neutral.
+pub fn check(
+    context: &Context,
+    repository: &str,
+    commit: Option<&str>,
+    findings: &[Finding],
+    failed: bool,
+) -> Result<Check, Error> {
+    token()?;
+    let commit = match commit {
+        Some(commit) => commit.to_string(),
+        None => git::git(&context.root, &["rev-parse", "HEAD"])
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())?,
+    };
+    let prefix = prefix(context);
+    let annotations: Vec<Value> = findings
+        .iter()
+        .filter_map(|finding| {
+            let location = finding.location.as_ref()?;
+            let mut annotation = json!({
+                "path": format!("{}{}", prefix, location.file.trim_start_matches("./")),
+                "start_line": location.line,
+                "end_line": location.line_end.max(location.line),
+                "annotation_level": match finding.severity {
+                    Severity::Error => "failure",
+                    Severity::Warning => "warning",
+                    Severity::Info => "notice",
+                },
+                "message": finding.message,
+            });
+            // Columns may only be specified for annotations of a single line
+            if location.line_end == location.line {
+                annotation["start_column"] = location.column.into();
+                annotation["end_column"] = location.column_end.max(location.column).into();
+            }
+            if let Some(code) = &finding.code {
+                annotation["title"] = code.as_str().into();
+            }
+            if !finding.help.is_empty() {
+                annotation["raw_details"] = finding.help.join("\n").into();
+            }
+            Some(annotation)
+        })
+        .collect();
+
+    let output = |annotations: &[Value]| {
+        json!({
+            "title": title(findings),
+            "summary": summary(findings),
+            "annotations": annotations,
+        })
+    };
+    let mut batches = annotations.chunks(BATCH);
+    let run = json!({
+        "name": CHECK,
+        "head_sha": commit,
+        "status": "completed",
+        "conclusion": match failed {
+            true => "failure",
+            false if findings.iter().any(|f| f.severity != Severity::Info) => "neutral",
+            false => "success",
+        },
+        "output": output(batches.next().unwrap_or_default()),
+    });
+    let run = github::json(
+        github::post(
+            &format!("/repos/{}/check-runs", repository),
+            &run.to_string(),
+        ),
+        "GitHub check run",
+    )?;
+    let id = run["id"]
+        .as_u64()
+        .ok_or_else(|| Error::Config("GitHub check run has no identifier".into()))?;
+    let mut check = Check {
+        id,
+        url: run["html_url"].as_str().unwrap_or_default().into(),
+        annotations: annotations.len().min(BATCH),
+        requests: 1,
+    };
+    // Annotations of subsequent requests are appended to those of the check run
+    for batch in batches {
+        github::json(
+            github::patch(
+                &format!("/repos/{}/check-runs/{}", repository, id),
+                &json!({ "output": output(batch) }).to_string(),
+            ),
+            "GitHub check run",
+        )?;
+        check.annotations += batch.len();
+        check.requests += 1;
+    }
+    Ok(check)
+}
+
+/// Ensures a token is available with which to authenticate requests.
+fn token() -> Result<(), Error> {
+    match std::env::var("GITHUB_TOKEN") {
+        Ok(_) => Ok(()),
+        Err(_) => Err(Error::Config(
+            "GITHUB_TOKEN must be set to publish to GitHub".into(),
+        )),
+    }
+}
+
+/// The path of the workspace root relative to the repository root, e.g. `node/`. Findings are located relative to the
+/// workspace root, whereas GitHub locates files relative to the repository root.
+fn prefix(context: &Context) -> String {
+    git::git(&context.root, &["rev-parse", "--show-prefix"])
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+/// The title of a check run, e.g. `2 error(s), 5 warning(s)`.
+fn title(findings: &[Finding]) -> String {
+    let count = |severity| findings.iter().filter(|f| f.severity == severity).count();
+    format!(
+        "{} error(s), {} warning(s), {} info",
+        count(Severity::Error),
+        count(Severity::Warning),
+        count(Severity::Info)
+    )
+}
+
+/// The (Markdown) summary of a check run, tabulating the number of findings of each severity by category.
+fn summary(findings: &[Finding]) -> String {
+    let mut counts: BTreeMap<String, [usize; 3]> = BTreeMap::new();
+    for finding in findings {
+        let index = match finding.severity {
+            Severity::Error => 0,
+            Severity::Warning => 1,
+            Severity::Info => 2,
+        };
+        counts.entry(finding.category.to_string()).or_default()[index] += 1;
+    }
+    let mut summary =
+        String::from("| Category | Errors | Warnings | Info |\n| --- | --: | --: | --: |\n");
+    for (category, [errors, warnings, info]) in &counts {
+        summary.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            category, errors, warnings, info
+        ));
+    }
+    let unlocated = findings.iter().filter(|f| f.location.is_none()).count();
+    summary.push_str(&format!(
+        "\n{} finding(s), of which {} have no location and are therefore not annotated.",
+        findings.len(),
+        unlocated
+    ));
+    summary
+}
+
+/// The body of the comment for a finding.
+fn body(finding: &Finding) -> String {
+    let mut body = format!("**{}**", finding.severity.as_str());
+    if let Some(code) = &finding.code {
+        body.push_str(&format!(" `{}`", code));
+    }
+    body.push_str(&format!(": {}", finding.message));
+    for help in &finding.help {
+        body.push_str(&format!("\n\n{}", help));
+    }
+    body.push_str(&format!("\n\n{}", MARKER));
+    body
+}
+
+/// The lines of each file which may be commented upon, being the added and context lines of its patch.
+fn lines(files: &[Value]) -> BTreeMap<String, BTreeSet<u64>> {
+    let mut lines: BTreeMap<String, BTreeSet<u64>> = BTreeMap::new();
+    for file in files {
+        let (Some(path), Some(patch)) = (file["filename"].as_str(), file["patch"].as_str()) else {
+            continue;
+        };
+        let lines = lines.entry(path.to_string()).or_default();
+        let mut line = 0;
+        for text in patch.lines() {
+            // Hunks are headed by the ranges of lines changed, e.g. `@@ -1,2 +1,3 @@`
+            if let Some(header) = text.strip_prefix("@@ ") {
+                line = header
+                    .split_whitespace()
+                    .find_map(|r| r.strip_prefix('+'))
+                    .and_then(|r| r.split(',').next())
+                    .and_then(|l| l.parse().ok())
+                    .unwrap_or(0);
+                continue;
+            }
+            match text.chars().next() {
+                Some('-') | Some('\\') => {}
+                _ => {
+                    lines.insert(line);
+                    line += 1;
+                }
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_commentable_lines() {
+        let files = [
+            json!({
+                "filename": "src/lib.rs",
+                "patch": "@@ -1,3 +1,4 @@\n a\n-b\n+c\n+d\n e\n\\ No newline at end of file\n@@ -10,2 +11,2 @@\n x\n+y",
+            }),
+            json!({ "filename": "image.png" }),
+        ];
+        let lines = lines(&files);
+        assert_eq!(
+            lines.get("src/lib.rs"),
+            Some(&BTreeSet::from([1, 2, 3, 4, 11, 12]))
+        );
+        assert!(!lines.contains_key("image.png"));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    findings::{Category, Finding, Location, Severity},
+    link,
+    manifests::{self, line_containing, line_of, relative},
+    Analysis, Context, Error, Format,
+};
+use colored::Colorize;
+use std::{fs, process::Stdio};
+
+/// The maximum number of keywords and categories accepted by crates.io.
+const MAX_KEYWORDS: usize = 5;
+/// The maximum length of a keyword accepted by crates.io.
+const MAX_KEYWORD_LENGTH: usize = 20;
+/// The length beyond which a description is no longer considered a brief summary of the crate.
+const MAX_DESCRIPTION_LENGTH: usize = 200;
+
+/// Checks packages intended for release (those without `publish = false`) are ready to be published to crates.io,
+/// optionally verifying each via `cargo publish --dry-run`.
+pub struct PublishCheck {
+    /// Packages and verifies each package via `cargo publish --dry-run`.
+    pub dry_run: bool,
+}
+
+impl Analysis for PublishCheck {
+    fn category(&self) -> Category {
+        Category::Manifests
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Checking packages are ready to publish...");
+        }
+
+        // The dry run resolves dependencies against the crates.io index, so requires network access
+        let dry_run = self.dry_run && !context.offline;
+        if self.dry_run && context.offline {
+            eprintln!(
+                "{} `cargo publish --dry-run` requires network access, skipping as --offline was used",
+                Severity::Warning
+            );
+        }
+        let mut findings = Vec::new();
+        let metadata = manifests::metadata(context)?;
+        for package in metadata.packages.iter().filter(|p| {
+            context.selected(&p.name) && p.publish.as_ref().is_none_or(|r| !r.is_empty())
+        }) {
+            if text {
+                println!(
+                    "{}",
+                    link(&package.name, &format!("file:///{}", package.manifest_path)).cyan()
+                );
+            }
+            let manifest = fs::read_to_string(&package.manifest_path).unwrap_or_default();
+            let mut report = |severity, code: &str, message: String, line: u16| {
+                let mut finding = Finding::new(Category::Manifests, severity, message)
+                    .with_code(code)
+                    .with_package(&package.name);
+                finding.location = Some(Location {
+                    file: relative(&context.root, &package.manifest_path),
+                    line,
+                    column: 1,
+                    line_end: line,
+                    column_end: 1,
+                });
+                if let Some(finding) = context.config.apply(finding) {
+                    if text {
+                        println!("  {} {}", finding.severity, finding.message);
+                    }
+                    findings.push(finding)
+                }
+            };
+
+            // Keywords and categories determine whether the crate is discoverable on crates.io
+            for (field, values) in [
+                ("keywords", &package.keywords),
+                ("categories", &package.categories),
+            ] {
+                let line = match manifest.contains(field) {
+                    true => line_of(&manifest, field),
+                    false => line_of(&manifest, "[package]"),
+                };
+                if values.is_empty() {
+                    report(
+                        Severity::Warning,
+                        &format!("sbp::missing_{}", field),
+                        format!("no '{}' found, so the crate is harder to discover", field),
+                        line,
+                    );
+                } else if values.len() > MAX_KEYWORDS {
+                    report(
+                        Severity::Error,
+                        &format!("sbp::too_many_{}", field),
+                        format!(
+                            "{} '{}' declared, whereas crates.io accepts at most {}",
+                            values.len(),
+                            field,
+                            MAX_KEYWORDS
+                        ),
+                        line,
+                    );
+                }
+            }
+            for keyword in package.keywords.iter().filter(|k| !valid_keyword(k)) {
+                report(
+                    Severity::Error,
+                    "sbp::invalid_keyword",
+                    format!(
+                        "keyword '{}' is invalid, as crates.io requires keywords of at most {} ASCII alphanumeric \
+                         characters, '_', '-' or '+', starting with a letter",
+                        keyword, MAX_KEYWORD_LENGTH
+                    ),
+                    line_containing(&manifest, &format!("\"{}\"", keyword)),
+                );
+            }
+
+            // A description is required by crates.io
+            match package.description.as_deref().map(str::trim) {
+                None | Some("") => report(
+                    Severity::Error,
+                    "sbp::missing_description",
+                    "no 'description' found, which is required by crates.io".into(),
+                    line_of(&manifest, "[package]"),
+                ),
+                Some(description) if description.chars().count() > MAX_DESCRIPTION_LENGTH => {
+                    report(
+                        Severity::Warning,
+                        "sbp::long_description",
+                        format!(
+                            "'description' is {} characters, whereas a brief summary of at most {} is expected \
+                             (use the README for details)",
+                            description.chars().count(),
+                            MAX_DESCRIPTION_LENGTH
+                        ),
+                        line_of(&manifest, "description"),
+                    )
+                }
+                Some(_) => {}
+            }
+
+            if package.documentation.is_none() {
+                report(
+                    Severity::Info,
+                    "sbp::missing_documentation",
+                    "no 'documentation' found, so crates.io links to docs.rs, which only builds the default features \
+                     for the default target"
+                        .into(),
+                    line_of(&manifest, "[package]"),
+                );
+            }
+
+            // Dependencies must specify a version to be published, other than development dependencies which are
+            // removed when publishing
+            for dependency in package
+                .dependencies
+                .iter()
+                .filter(|d| d.kind.as_deref() != Some("dev") && d.req == "*")
+            {
+                let source = match (&dependency.path, &dependency.source) {
+                    (Some(_), _) => "path",
+                    (None, Some(source)) if source.starts_with("git+") => "git",
+                    _ => continue,
+                };
+                report(
+                    Severity::Error,
+                    "sbp::unversioned_dependency",
+                    format!(
+                        "{} dependency '{}' has no version, so the package cannot be published",
+                        source, dependency.name
+                    ),
+                    line_of(&manifest, &dependency.name),
+                );
+            }
+
+            if dry_run {
+                if text {
+                    println!("  verifying via `cargo publish --dry-run`...");
+                }
+                let mut command = context.cargo("publish");
+                command
+                    .args(["--dry-run", "--allow-dirty", "--package", &package.name])
+                    .current_dir(&context.root)
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped());
+                let output = context
+                    .output(&mut command, "publish-check")
+                    .map_err(|e| Error::command("cargo publish", e))?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    report(
+                        Severity::Error,
+                        "sbp::publish_dry_run_failed",
+                        match stderr.lines().find(|l| l.starts_with("error")) {
+                            Some(error) => format!("`cargo publish --dry-run` failed: {}", error),
+                            None => "`cargo publish --dry-run` failed".into(),
+                        },
+                        line_of(&manifest, "[package]"),
+                    );
+                }
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// Whether the keyword is accepted by crates.io.
+fn valid_keyword(keyword: &str) -> bool {
+    keyword.len() <= MAX_KEYWORD_LENGTH
+        && keyword.starts_with(|c: char| c.is_ascii_alphabetic())
+        && keyword
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+}
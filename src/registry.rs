@@ -0,0 +1,160 @@
+use crate::Error;
+use serde::Deserialize;
+use std::{cmp::Ordering, collections::HashMap, process::Command};
+
+/// The sparse index of crates.io.
+const INDEX: &str = "https://index.crates.io";
+
+/// The published versions of crates, queried from the crates.io index via `curl` and cached per crate.
+#[derive(Default)]
+pub(crate) struct Registry {
+    crates: HashMap<String, Vec<Published>>,
+}
+
+/// A published version of a crate.
+pub(crate) struct Published {
+    pub version: Version,
+    /// The dependencies of the version, as (package name, version requirement) pairs.
+    pub dependencies: Vec<(String, String)>,
+}
+
+impl Registry {
+    /// Returns the published (non-yanked) versions of the crate, in ascending order.
+    pub(crate) fn versions(&mut self, name: &str) -> Result<&[Published], Error> {
+        if !self.crates.contains_key(name) {
+            let output = Command::new("curl")
+                .args(["--silent", "--show-error", "--fail", "--max-time", "10"])
+                .arg(format!("{}/{}", INDEX, path(name)))
+                .output()
+                .map_err(|source| Error::Command {
+                    command: "curl",
+                    source,
+                })?;
+            if !output.status.success() {
+                return Err(Error::failed("curl", &output.stderr));
+            }
+            // Each line of an index file describes a single published version
+            let mut versions = Vec::new();
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                let entry: Entry = serde_json::from_str(line).map_err(|source| Error::Parse {
+                    command: "curl",
+                    source,
+                })?;
+                if let Some(version) = Version::parse(&entry.vers).filter(|_| !entry.yanked) {
+                    versions.push(Published {
+                        version,
+                        dependencies: entry
+                            .deps
+                            .into_iter()
+                            .map(|d| (d.package.unwrap_or(d.name), d.req))
+                            .collect(),
+                    });
+                }
+            }
+            versions.sort_by(|a, b| a.version.cmp(&b.version));
+            self.crates.insert(name.into(), versions);
+        }
+        Ok(&self.crates[name])
+    }
+}
+
+/// The path of the crate within the index: https://doc.rust-lang.org/cargo/reference/registry-index.html#index-files
+fn path(name: &str) -> String {
+    let name = name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+/// An entry of an index file.
+#[derive(Deserialize)]
+struct Entry {
+    vers: String,
+    yanked: bool,
+    #[serde(default)]
+    deps: Vec<EntryDependency>,
+}
+
+#[derive(Deserialize)]
+struct EntryDependency {
+    name: String,
+    req: String,
+    /// The name of the package, when renamed.
+    package: Option<String>,
+}
+
+/// A semantic version, ignoring any build metadata.
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Option<String>,
+}
+
+impl Version {
+    /// Parses a version, where any missing minor or patch components default to zero.
+    pub(crate) fn parse(version: &str) -> Option<Version> {
+        let version = version.split('+').next()?.trim();
+        let (version, pre) = match version.split_once('-') {
+            Some((version, pre)) => (version, Some(pre.to_string())),
+            None => (version, None),
+        };
+        let mut components = version.split('.');
+        let mut component = || components.next().map_or(Some(0), |c| c.parse().ok());
+        Some(Version {
+            major: component()?,
+            minor: component()?,
+            patch: component()?,
+            pre,
+        })
+    }
+
+    /// Parses the minimum version allowed by the first comparator of a version requirement (e.g. `^1.2`, `>=0.3, <0.5`),
+    /// unless it is a wildcard.
+    pub(crate) fn minimum(requirement: &str) -> Option<Version> {
+        let comparator = requirement.split(',').next()?.trim();
+        Version::parse(comparator.trim_start_matches(['^', '~', '=', '>', '<', ' ']))
+    }
+
+    /// Whether the version is semver compatible with the other version.
+    pub(crate) fn compatible(&self, other: &Version) -> bool {
+        match self.major {
+            0 => other.major == 0 && self.minor == other.minor,
+            major => major == other.major,
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            // Pre-release versions precede the corresponding release
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
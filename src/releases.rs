@@ -0,0 +1,193 @@
+//! Discovery of the current releases of the Polkadot SDK: their release branches, via the GitHub releases API, and
+//! the versions of the crates published to crates.io for each, via the `polkadot-sdk` umbrella crate.
+
+use crate::{
+    github,
+    registry::{Registry, Version},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The releases of the Polkadot SDK, most recent first.
+const RELEASES: &str = "/repos/paritytech/polkadot-sdk/releases?per_page=100";
+/// The duration for which discovered branches are cached, in seconds.
+const EXPIRY: u64 = 24 * 60 * 60;
+
+#[derive(Serialize, Deserialize)]
+struct Cached {
+    /// When the branches were discovered, in seconds since the Unix epoch.
+    timestamp: u64,
+    branches: Vec<String>,
+}
+
+/// A release, as returned by the GitHub releases API.
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    draft: bool,
+    prerelease: bool,
+}
+
+/// Returns the release branches of the latest `count` Polkadot SDK releases, cached within the target directory. When
+/// offline or the releases cannot be queried, any previously cached branches are used regardless of their age.
+pub(crate) fn branches(target_directory: &Path, count: usize, offline: bool) -> Vec<String> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let path = target_directory.join("sbp-review").join("releases.json");
+    let cached: Option<Cached> = fs::read(&path)
+        .ok()
+        .and_then(|c| serde_json::from_slice(&c).ok());
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    if let Some(cached) = cached
+        .as_ref()
+        .filter(|c| offline || now.saturating_sub(c.timestamp) < EXPIRY)
+    {
+        return cached.branches.iter().take(count).cloned().collect();
+    }
+    if offline {
+        return Vec::new();
+    }
+
+    match fetch() {
+        Some(branches) => {
+            let cached = Cached {
+                timestamp: now,
+                branches,
+            };
+            // Failures are ignored, as the cache is only an optimisation
+            if let Ok(contents) = serde_json::to_vec(&cached) {
+                let _ = fs::create_dir_all(path.parent().unwrap_or(target_directory))
+                    .and_then(|_| fs::write(&path, contents));
+            }
+            cached.branches.into_iter().take(count).collect()
+        }
+        None => cached
+            .map(|c| c.branches.into_iter().take(count).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Queries the releases of the Polkadot SDK, returning the corresponding release branches, most recent first.
+fn fetch() -> Option<Vec<String>> {
+    let (status, body) = github::get(RELEASES)?;
+    if status != 200 {
+        return None;
+    }
+    let releases: Vec<GithubRelease> = serde_json::from_slice(&body).ok()?;
+    let mut branches: Vec<String> = Vec::new();
+    for branch in releases
+        .iter()
+        .filter(|r| !r.draft && !r.prerelease)
+        .filter_map(|r| branch(&r.tag_name))
+    {
+        if !branches.contains(&branch) {
+            branches.push(branch);
+        }
+    }
+    Some(branches)
+}
+
+/// Maps a release tag to its release branch: `polkadot-v1.5.0` to `release-polkadot-v1.5.0` and `polkadot-stable2409-2`
+/// to `stable2409`.
+fn branch(tag: &str) -> Option<String> {
+    let release = tag.strip_prefix("polkadot-")?;
+    if release.starts_with('v') {
+        return Some(format!("release-{}", tag));
+    }
+    let stable = release.strip_prefix("stable")?;
+    let version: String = stable.chars().take_while(char::is_ascii_digit).collect();
+    (!version.is_empty()).then(|| format!("stable{}", version))
+}
+
+/// The Polkadot SDK releases published to crates.io, most recent first.
+pub(crate) struct Published(Vec<Release>);
+
+/// A Polkadot SDK release published to crates.io.
+pub(crate) struct Release {
+    /// The name of the release, e.g. `stable2409`.
+    pub(crate) name: String,
+    /// The minimum versions of each crate required by the (patch) versions of the release.
+    crates: HashMap<String, Vec<Version>>,
+}
+
+impl Published {
+    /// Queries the releases from the versions of the `polkadot-sdk` umbrella crate, which depends on all crates of the
+    /// release at their published versions.
+    pub(crate) fn query(registry: &mut Registry) -> Result<Published, Error> {
+        let mut releases: Vec<(Version, Release)> = Vec::new();
+        for published in registry.versions("polkadot-sdk")?.iter().rev() {
+            let version = &published.version;
+            if version.pre.is_some() {
+                continue;
+            }
+            // Patch versions of a release are compatible with its first version
+            let index = match releases.iter().position(|(v, _)| v.compatible(version)) {
+                Some(index) => index,
+                None => {
+                    releases.push((
+                        version.clone(),
+                        Release {
+                            name: match version.major {
+                                // Versions follow the stable release from `stable2503`
+                                major if major >= 2000 => format!("stable{}", major),
+                                _ => format!("polkadot-sdk {}", version),
+                            },
+                            crates: HashMap::new(),
+                        },
+                    ));
+                    releases.len() - 1
+                }
+            };
+            for (name, requirement) in &published.dependencies {
+                if let Some(minimum) = Version::minimum(requirement) {
+                    releases[index]
+                        .1
+                        .crates
+                        .entry(name.clone())
+                        .or_default()
+                        .push(minimum);
+                }
+            }
+        }
+        releases.sort_by(|a, b| b.0.cmp(&a.0));
+        Ok(Published(releases.into_iter().map(|(_, r)| r).collect()))
+    }
+
+    /// Whether the crate is published as part of the Polkadot SDK.
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|r| r.crates.contains_key(name))
+    }
+
+    /// Returns the indices of the releases (most recent first) with which the version of the crate is compatible.
+    pub(crate) fn compatible(&self, name: &str, version: &Version) -> Vec<usize> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| {
+                r.crates
+                    .get(name)
+                    .is_some_and(|v| v.iter().any(|v| v.compatible(version)))
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the release at the index.
+    pub(crate) fn release(&self, index: usize) -> Option<&Release> {
+        self.0.get(index)
+    }
+
+    /// Returns the minimum version of the crate required by the release.
+    pub(crate) fn version(&self, index: usize, name: &str) -> Option<&Version> {
+        self.0.get(index)?.crates.get(name)?.iter().max()
+    }
+}
@@ -0,0 +1,314 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    manifests::{self, Metadata},
+    pallets, Analysis, Context, Error, Format,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Builds a runtime and reports the size of its Wasm blob, its embedded runtime version and its pallets, flagging any
+/// blob exceeding the maximum code size accepted by the relay chain.
+pub struct Runtime;
+
+impl Analysis for Runtime {
+    fn category(&self) -> Category {
+        Category::Runtime
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        let metadata = manifests::metadata(context)?;
+        let runtime = package(context, &metadata, None)?;
+
+        if text {
+            println!("Building {}...", runtime);
+        }
+        let mut findings = Vec::new();
+        let mut report = |finding: Finding| {
+            if let Some(finding) = context.config.apply(finding.with_package(&runtime)) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        };
+        let mut command = context.cargo("build");
+        command
+            .args(["--release", "--package", &runtime])
+            .stdout(context.stdout());
+        let status = context
+            .output(&mut command, "runtime")
+            .map_err(|e| Error::command("cargo build", e))?
+            .status;
+        if !status.success() {
+            report(
+                Finding::new(
+                    Category::Runtime,
+                    Severity::Error,
+                    format!("runtime failed to build ({})", status),
+                )
+                .with_code("sbp::runtime_build_failed"),
+            );
+            return Ok(findings);
+        }
+
+        let target_directory = Path::new(&metadata.target_directory);
+        let read = |compressed| {
+            let path = wasm(target_directory, &runtime, compressed);
+            fs::read(&path).map_err(|source| Error::Io { path, source })
+        };
+        let (blob, compressed) = (read(false)?, read(true)?);
+
+        // Size
+        let max_code_size = context.config.runtime.max_code_size;
+        report(
+            Finding::new(
+                Category::Runtime,
+                Severity::Info,
+                format!(
+                    "runtime Wasm is {} compressed ({}% of the {} limit), {} uncompressed",
+                    size(compressed.len() as u64),
+                    compressed.len() as u64 * 100 / max_code_size.max(1),
+                    size(max_code_size),
+                    size(blob.len() as u64)
+                ),
+            )
+            .with_code("sbp::runtime_size"),
+        );
+        if compressed.len() as u64 > max_code_size {
+            report(
+                Finding::new(
+                    Category::Runtime,
+                    Severity::Error,
+                    format!(
+                        "compressed runtime Wasm of {} bytes exceeds the maximum code size of the relay chain ({} bytes)",
+                        compressed.len(),
+                        max_code_size
+                    ),
+                )
+                .with_code("sbp::runtime_too_large"),
+            );
+        }
+
+        // Version
+        match version(&blob) {
+            Some(version) => report(
+                Finding::new(
+                    Category::Runtime,
+                    Severity::Info,
+                    format!(
+                        "runtime version: spec {} v{}, impl {} v{}, authoring v{}, transaction v{}, {} runtime API(s)",
+                        version.spec_name,
+                        version.spec_version,
+                        version.impl_name,
+                        version.impl_version,
+                        version.authoring_version,
+                        version.transaction_version,
+                        version.apis
+                    ),
+                )
+                .with_code("sbp::runtime_version"),
+            ),
+            None => report(
+                Finding::new(
+                    Category::Runtime,
+                    Severity::Warning,
+                    "runtime Wasm has no embedded runtime version ('runtime_version' custom section)".into(),
+                )
+                .with_code("sbp::runtime_version"),
+            ),
+        }
+
+        // Pallets, as declared within the runtime source
+        let directory = metadata
+            .packages
+            .iter()
+            .find(|p| p.name == runtime)
+            .and_then(|p| Path::new(&p.manifest_path).parent());
+        let names: Vec<_> = directory
+            .into_iter()
+            .flat_map(|directory| pallets::sources(directory, &context.root))
+            .flat_map(|source| pallets::runtime_pallets(&source.file))
+            .map(|(name, krate)| format!("{} ({})", name, krate))
+            .collect();
+        if !names.is_empty() {
+            report(
+                Finding::new(
+                    Category::Runtime,
+                    Severity::Info,
+                    format!(
+                        "runtime has {} pallet(s): {}",
+                        names.len(),
+                        names.join(", ")
+                    ),
+                )
+                .with_code("sbp::runtime_pallets"),
+            );
+        }
+        Ok(findings)
+    }
+}
+
+/// Determines the runtime package: either that configured or the single selected package named `*runtime*`, optionally
+/// with the feature.
+pub(crate) fn package(
+    context: &Context,
+    metadata: &Metadata,
+    feature: Option<&str>,
+) -> Result<String, Error> {
+    if let Some(package) = &context.config.runtime.package {
+        return Ok(package.clone());
+    }
+    let runtimes: Vec<_> = metadata
+        .packages
+        .iter()
+        .filter(|p| {
+            context.selected(&p.name)
+                && p.name.contains("runtime")
+                && feature.is_none_or(|f| p.features.contains_key(f))
+        })
+        .collect();
+    match runtimes[..] {
+        [runtime] => Ok(runtime.name.clone()),
+        _ => Err(Error::Config(
+            "could not determine the runtime package, use --runtime or set 'package' within [runtime]"
+                .into(),
+        )),
+    }
+}
+
+/// The path of the (compressed) runtime Wasm built by `substrate-wasm-builder` for the runtime package.
+pub(crate) fn wasm(target_directory: &Path, runtime: &str, compressed: bool) -> PathBuf {
+    target_directory
+        .join("release")
+        .join("wbuild")
+        .join(runtime)
+        .join(format!(
+            "{}.compact{}.wasm",
+            runtime.replace('-', "_"),
+            match compressed {
+                true => ".compressed",
+                false => "",
+            }
+        ))
+}
+
+/// The version of a runtime, as embedded within its Wasm blob.
+pub(crate) struct Version {
+    pub(crate) spec_name: String,
+    pub(crate) impl_name: String,
+    pub(crate) authoring_version: u32,
+    pub(crate) spec_version: u32,
+    pub(crate) impl_version: u32,
+    pub(crate) transaction_version: u32,
+    /// The number of runtime APIs.
+    pub(crate) apis: usize,
+}
+
+/// Decodes the runtime version from the `runtime_version` custom section of the (uncompressed) Wasm blob, with the
+/// runtime APIs from the `runtime_apis` custom section.
+pub(crate) fn version(blob: &[u8]) -> Option<Version> {
+    let sections = custom_sections(blob)?;
+    let section = |name| sections.iter().find(|(n, _)| *n == name).map(|(_, c)| *c);
+    let mut input = section("runtime_version")?;
+    let spec_name = string(&mut input)?;
+    let impl_name = string(&mut input)?;
+    let authoring_version = u32(&mut input)?;
+    let spec_version = u32(&mut input)?;
+    let impl_version = u32(&mut input)?;
+    // Each API is an 8 byte identifier along with its version
+    let apis = compact(&mut input)? as usize;
+    bytes(&mut input, apis * 12)?;
+    let transaction_version = u32(&mut input)?;
+    Some(Version {
+        spec_name,
+        impl_name,
+        authoring_version,
+        spec_version,
+        impl_version,
+        transaction_version,
+        apis: match section("runtime_apis") {
+            Some(runtime_apis) if apis == 0 => runtime_apis.len() / 12,
+            _ => apis,
+        },
+    })
+}
+
+/// Returns the name and contents of each custom section of the Wasm blob.
+fn custom_sections(blob: &[u8]) -> Option<Vec<(&str, &[u8])>> {
+    let mut input = blob.strip_prefix(b"\0asm")?;
+    bytes(&mut input, 4)?;
+    let mut sections = Vec::new();
+    while !input.is_empty() {
+        let id = bytes(&mut input, 1)?[0];
+        let length = leb128(&mut input)? as usize;
+        let mut contents = bytes(&mut input, length)?;
+        if id == 0 {
+            let length = leb128(&mut contents)? as usize;
+            let name = std::str::from_utf8(bytes(&mut contents, length)?).ok()?;
+            sections.push((name, contents));
+        }
+    }
+    Some(sections)
+}
+
+/// Takes the next `length` bytes from the input.
+fn bytes<'a>(input: &mut &'a [u8], length: usize) -> Option<&'a [u8]> {
+    if input.len() < length {
+        return None;
+    }
+    let (bytes, remainder) = input.split_at(length);
+    *input = remainder;
+    Some(bytes)
+}
+
+/// Decodes an unsigned LEB128 integer, as used by the Wasm binary format.
+fn leb128(input: &mut &[u8]) -> Option<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = bytes(input, 1)?[0];
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Decodes a SCALE compact integer.
+fn compact(input: &mut &[u8]) -> Option<u64> {
+    let first = bytes(input, 1)?[0];
+    let mut value = |length| {
+        let bytes = bytes(input, length)?;
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0u64, |value, byte| value << 8 | u64::from(*byte)),
+        )
+    };
+    match first & 0b11 {
+        0b00 => Some(u64::from(first >> 2)),
+        0b01 => Some((value(1)? << 8 | u64::from(first)) >> 2),
+        0b10 => Some((value(3)? << 8 | u64::from(first)) >> 2),
+        _ => value(usize::from(first >> 2) + 4).filter(|_| first >> 2 <= 4),
+    }
+}
+
+/// Decodes a SCALE little-endian `u32`.
+fn u32(input: &mut &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes(input, 4)?.try_into().ok()?))
+}
+
+/// Decodes a SCALE string.
+fn string(input: &mut &[u8]) -> Option<String> {
+    let length = compact(input)? as usize;
+    Some(String::from_utf8_lossy(bytes(input, length)?).into_owned())
+}
+
+/// Formats a size in bytes, e.g. `1.25 MiB`.
+fn size(bytes: u64) -> String {
+    format!("{:.2} MiB", bytes as f64 / 1024.0 / 1024.0)
+}
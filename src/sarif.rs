@@ -0,0 +1,173 @@
+use crate::{
+    findings::{Finding, Severity},
+    score::Score,
+};
+use serde::Serialize;
+
+// Source: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+#[derive(Serialize)]
+pub struct Log {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+pub struct Run {
+    pub tool: Tool,
+    pub results: Vec<Result>,
+    /// The property bag of the run, holding the score of a review.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<Properties>,
+}
+
+#[derive(Serialize)]
+pub struct Properties {
+    pub score: serde_json::Value,
+}
+
+impl Log {
+    /// Adds the score of a review to the properties of the run.
+    pub fn with_score(mut self, score: &Score) -> Self {
+        for run in &mut self.runs {
+            run.properties = Some(Properties {
+                score: serde_json::to_value(score).unwrap_or_default(),
+            });
+        }
+        self
+    }
+}
+
+#[derive(Serialize)]
+pub struct Tool {
+    pub driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Driver {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub information_uri: &'static str,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Result {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: &'static str,
+    pub message: Message,
+    pub locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+    pub region: Region,
+}
+
+#[derive(Serialize)]
+pub struct ArtifactLocation {
+    pub uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub start_line: u16,
+    pub start_column: u16,
+    pub end_line: u16,
+    pub end_column: u16,
+}
+
+impl From<&[Finding]> for Log {
+    fn from(findings: &[Finding]) -> Self {
+        let mut rules: Vec<Rule> = Vec::new();
+        // Code scanning requires a location for each result
+        let results = findings
+            .iter()
+            .filter_map(|f| f.location.as_ref().map(|l| (f, l)))
+            .map(|(finding, location)| {
+                let rule_id = finding
+                    .code
+                    .clone()
+                    .unwrap_or_else(|| "sbp::unknown".into());
+                let rule_index = match rules.iter().position(|r| r.id == rule_id) {
+                    Some(index) => index,
+                    None => {
+                        rules.push(Rule {
+                            id: rule_id.clone(),
+                            help_uri: finding.help_uri(),
+                        });
+                        rules.len() - 1
+                    }
+                };
+                Result {
+                    rule_id,
+                    rule_index,
+                    level: match finding.severity {
+                        Severity::Error => "error",
+                        Severity::Warning => "warning",
+                        Severity::Info => "note",
+                    },
+                    message: Message {
+                        text: finding.message.clone(),
+                    },
+                    locations: vec![Location {
+                        physical_location: PhysicalLocation {
+                            artifact_location: ArtifactLocation {
+                                uri: location.file.clone(),
+                            },
+                            region: Region {
+                                start_line: location.line,
+                                start_column: location.column,
+                                end_line: location.line_end,
+                                end_column: location.column_end,
+                            },
+                        },
+                    }],
+                }
+            })
+            .collect();
+
+        Log {
+            schema: "https://json.schemastore.org/sarif-2.1.0.json",
+            version: "2.1.0",
+            runs: vec![Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: env!("CARGO_PKG_NAME"),
+                        version: env!("CARGO_PKG_VERSION"),
+                        information_uri: "https://github.com/evilrobot-01/sbp-review",
+                        rules,
+                    },
+                },
+                results,
+                properties: None,
+            }],
+        }
+    }
+}
@@ -0,0 +1,446 @@
+//! Generation of a software bill of materials (SBOM) of the workspace, in the CycloneDX or SPDX formats, from its
+//! resolved dependency graph.
+
+use crate::{
+    history,
+    lockfile::Lockfile,
+    manifests::{self, Metadata, Package},
+    spdx, Context, Error,
+};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The source of packages published to crates.io.
+const CRATES_IO: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+/// A package of the bill of materials.
+struct Component<'a> {
+    package: &'a Package,
+    /// Whether the package is a member of the workspace.
+    member: bool,
+    /// The repository and resolved commit of a git package.
+    git: Option<(String, String)>,
+    /// The SHA-256 checksum of a registry package, from the lock file.
+    checksum: Option<String>,
+    /// The ids of the dependencies of the package included within the bill of materials.
+    dependencies: Vec<&'a str>,
+}
+
+impl Component<'_> {
+    /// The package URL (purl) of the package, e.g. `pkg:cargo/serde@1.0.0`, qualified by the repository and commit of
+    /// a git package.
+    fn purl(&self) -> String {
+        let purl = format!("pkg:cargo/{}@{}", self.package.name, self.package.version);
+        match &self.git {
+            Some((repository, commit)) => format!(
+                "{}?vcs_url={}",
+                purl,
+                url::form_urlencoded::byte_serialize(
+                    format!("git+{}@{}", repository, commit).as_bytes()
+                )
+                .collect::<String>()
+            ),
+            None => purl,
+        }
+    }
+
+    /// The location from which the package can be downloaded, if any.
+    fn download(&self) -> Option<String> {
+        match (&self.git, self.package.source.as_deref()) {
+            (Some((repository, commit)), _) => Some(format!("git+{}@{}", repository, commit)),
+            (None, Some(CRATES_IO)) => Some(format!(
+                "https://crates.io/api/v1/crates/{}/{}/download",
+                self.package.name, self.package.version
+            )),
+            _ => None,
+        }
+    }
+
+    /// The declared license of the package, when a valid SPDX expression.
+    fn license(&self) -> Option<&str> {
+        self.package
+            .license
+            .as_deref()
+            .filter(|l| spdx::validate(l).is_ok())
+    }
+}
+
+/// Generates a CycloneDX (1.5) bill of materials of the selected workspace members and their dependencies.
+pub fn cyclonedx(context: &Context) -> Result<Value, Error> {
+    let metadata = manifests::dependency_metadata(context)?;
+    let components = components(context, &metadata);
+    let (timestamp, serial) = identity(context);
+    let bom_components: Vec<_> = components
+        .iter()
+        .map(|c| {
+            let mut component = json!({
+                "type": if c.member { "application" } else { "library" },
+                "bom-ref": c.package.id,
+                "name": c.package.name,
+                "version": c.package.version,
+                "purl": c.purl(),
+            });
+            if let Some(description) = &c.package.description {
+                component["description"] = description.as_str().into();
+            }
+            match (c.license(), &c.package.license) {
+                (Some(expression), _) => {
+                    component["licenses"] = json!([{ "expression": expression }])
+                }
+                (None, Some(license)) => {
+                    component["licenses"] = json!([{ "license": { "name": license } }])
+                }
+                (None, None) => {}
+            }
+            if let Some(checksum) = &c.checksum {
+                component["hashes"] = json!([{ "alg": "SHA-256", "content": checksum }]);
+            }
+            let mut references = Vec::new();
+            match &c.git {
+                Some((repository, commit)) => references.push(json!({
+                    "type": "vcs",
+                    "url": repository,
+                    "comment": format!("commit {}", commit),
+                })),
+                None => {
+                    if let Some(repository) = &c.package.repository {
+                        references.push(json!({ "type": "vcs", "url": repository }));
+                    }
+                }
+            }
+            if let Some(download) = c.download().filter(|_| c.git.is_none()) {
+                references.push(json!({ "type": "distribution", "url": download }));
+            }
+            if !references.is_empty() {
+                component["externalReferences"] = references.into();
+            }
+            component
+        })
+        .collect();
+    // The workspace depends upon its members, which in turn depend upon their dependencies
+    let members: Vec<_> = components
+        .iter()
+        .filter(|c| c.member)
+        .map(|c| &c.package.id)
+        .collect();
+    let dependencies: Vec<_> = [json!({ "ref": "workspace", "dependsOn": members })]
+        .into_iter()
+        .chain(
+            components
+                .iter()
+                .map(|c| json!({ "ref": c.package.id, "dependsOn": c.dependencies })),
+        )
+        .collect();
+    Ok(json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", serial),
+        "version": 1,
+        "metadata": {
+            "timestamp": timestamp,
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "component": {
+                "type": "application",
+                "bom-ref": "workspace",
+                "name": workspace(context),
+            },
+        },
+        "components": bom_components,
+        "dependencies": dependencies,
+    }))
+}
+
+/// Generates an SPDX (2.3) document of the selected workspace members and their dependencies.
+pub fn spdx(context: &Context) -> Result<Value, Error> {
+    let metadata = manifests::dependency_metadata(context)?;
+    let components = components(context, &metadata);
+    let (timestamp, serial) = identity(context);
+    let mut identifiers = HashMap::new();
+    for (index, component) in components.iter().enumerate() {
+        identifiers.insert(
+            component.package.id.as_str(),
+            identifier(component.package, index),
+        );
+    }
+    let packages: Vec<_> = components
+        .iter()
+        .map(|c| {
+            let noassertion = || "NOASSERTION".to_string();
+            let mut package = json!({
+                "SPDXID": identifiers[c.package.id.as_str()],
+                "name": c.package.name,
+                "versionInfo": c.package.version,
+                "downloadLocation": c.download().unwrap_or_else(noassertion),
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": c.license().map_or_else(noassertion, str::to_string),
+                "copyrightText": "NOASSERTION",
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": c.purl(),
+                }],
+            });
+            if let Some(description) = &c.package.description {
+                package["summary"] = description.as_str().into();
+            }
+            if let Some(repository) = &c.package.repository {
+                package["homepage"] = repository.as_str().into();
+            }
+            if let Some(checksum) = &c.checksum {
+                package["checksums"] =
+                    json!([{ "algorithm": "SHA256", "checksumValue": checksum }]);
+            }
+            if let Some((_, commit)) = &c.git {
+                package["sourceInfo"] = format!("built from git commit {}", commit).into();
+            }
+            package
+        })
+        .collect();
+    let mut relationships: Vec<_> = components
+        .iter()
+        .filter(|c| c.member)
+        .map(|c| {
+            json!({
+                "spdxElementId": "SPDXRef-DOCUMENT",
+                "relationshipType": "DESCRIBES",
+                "relatedSpdxElement": identifiers[c.package.id.as_str()],
+            })
+        })
+        .collect();
+    for component in &components {
+        for dependency in &component.dependencies {
+            relationships.push(json!({
+                "spdxElementId": identifiers[component.package.id.as_str()],
+                "relationshipType": "DEPENDS_ON",
+                "relatedSpdxElement": identifiers[dependency],
+            }));
+        }
+    }
+    let name = workspace(context);
+    Ok(json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": name,
+        "documentNamespace": format!("https://spdx.org/spdxdocs/{}-{}", name, serial),
+        "creationInfo": {
+            "created": timestamp,
+            "creators": [format!("Tool: {}-{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    }))
+}
+
+/// Returns the selected workspace members and all of the packages on which they depend (including development and
+/// build dependencies), in breadth-first order.
+fn components<'a>(context: &Context, metadata: &'a Metadata) -> Vec<Component<'a>> {
+    let checksums: HashMap<(String, String), String> =
+        Lockfile::read(&context.root.join("Cargo.lock"))
+            .map(|lockfile| {
+                lockfile
+                    .packages
+                    .into_iter()
+                    .filter_map(|p| Some(((p.name, p.version), p.checksum?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+    let nodes: HashMap<_, _> = metadata
+        .resolve
+        .iter()
+        .flat_map(|r| &r.nodes)
+        .map(|n| (n.id.as_str(), n))
+        .collect();
+
+    let mut queue: VecDeque<&str> = metadata
+        .packages
+        .iter()
+        .filter(|p| metadata.workspace_members.contains(&p.id) && context.selected(&p.name))
+        .map(|p| p.id.as_str())
+        .collect();
+    let mut visited: BTreeSet<&str> = queue.iter().copied().collect();
+    let mut components = Vec::new();
+    while let Some(id) = queue.pop_front() {
+        let Some(package) = metadata.package(id) else {
+            continue;
+        };
+        let dependencies: Vec<&str> = nodes
+            .get(id)
+            .map(|n| n.deps.iter().map(|d| d.pkg.as_str()).collect())
+            .unwrap_or_default();
+        for dependency in &dependencies {
+            if visited.insert(dependency) {
+                queue.push_back(dependency);
+            }
+        }
+        components.push(Component {
+            package,
+            member: metadata.workspace_members.contains(&package.id),
+            git: package.source.as_deref().and_then(git),
+            checksum: checksums
+                .get(&(package.name.clone(), package.version.clone()))
+                .cloned(),
+            dependencies,
+        });
+    }
+    components
+}
+
+/// Parses the repository and resolved commit from the source of a git package, which is qualified by the reference and
+/// resolved commit, e.g. `git+https://github.com/paritytech/polkadot-sdk?branch=stable2407#abc123`.
+fn git(source: &str) -> Option<(String, String)> {
+    let (repository, commit) = source.strip_prefix("git+")?.split_once('#')?;
+    let repository = repository.split('?').next().unwrap_or(repository);
+    Some((repository.to_string(), commit.to_string()))
+}
+
+/// The SPDX identifier of the package, unique by its index. Identifiers may only contain letters, numbers, `.` and `-`.
+fn identifier(package: &Package, index: usize) -> String {
+    format!(
+        "SPDXRef-Package-{}-{}-{}",
+        package.name, package.version, index
+    )
+    .replace(
+        |c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-',
+        "-",
+    )
+}
+
+/// Returns the creation time of the bill of materials (RFC 3339) along with a unique serial number (UUID), derived
+/// from the workspace and the time.
+fn identity(context: &Context) -> (String, String) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = now.as_secs();
+    let (year, month, day) = history::civil(seconds);
+    let timestamp = format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds % 86_400 / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    );
+    let mut hash: [u8; 32] = Sha256::new()
+        .chain_update(context.root.to_string_lossy().as_bytes())
+        .chain_update(now.as_nanos().to_le_bytes())
+        .finalize()
+        .into();
+    // A version 4 (random) UUID, of the RFC 4122 variant
+    hash[6] = (hash[6] & 0x0f) | 0x40;
+    hash[8] = (hash[8] & 0x3f) | 0x80;
+    let hex: String = hash[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    let serial = format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    );
+    (timestamp, serial)
+}
+
+/// The name of the workspace, being that of its root directory.
+fn workspace(context: &Context) -> String {
+    context
+        .root
+        .file_name()
+        .map_or_else(|| "workspace".into(), |n| n.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, source: Option<&str>, license: Option<&str>) -> Package {
+        serde_json::from_value(json!({
+            "id": format!("{} 1.0.0", name),
+            "name": name,
+            "manifest_path": format!("/{}/Cargo.toml", name),
+            "version": "1.0.0",
+            "source": source,
+            "license": license,
+            "authors": [],
+            "categories": [],
+            "keywords": [],
+            "edition": "2021",
+            "dependencies": [],
+            "features": {},
+            "targets": [],
+        }))
+        .unwrap()
+    }
+
+    fn component(package: &Package) -> Component<'_> {
+        Component {
+            package,
+            member: false,
+            git: package.source.as_deref().and_then(git),
+            checksum: None,
+            dependencies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn identifies_git_packages_by_repository_and_commit() {
+        let package = package(
+            "sp_core",
+            Some("git+https://github.com/paritytech/polkadot-sdk?branch=stable2407#abc123"),
+            Some("Apache 2"),
+        );
+        let component = component(&package);
+        assert_eq!(
+            component.git,
+            Some((
+                "https://github.com/paritytech/polkadot-sdk".into(),
+                "abc123".into()
+            ))
+        );
+        assert_eq!(
+            component.purl(),
+            "pkg:cargo/sp_core@1.0.0?vcs_url=git%2Bhttps%3A%2F%2Fgithub.com%2Fparitytech%2Fpolkadot-sdk%40abc123"
+        );
+        assert_eq!(
+            component.download().as_deref(),
+            Some("git+https://github.com/paritytech/polkadot-sdk@abc123")
+        );
+        // Invalid SPDX expressions are not declared as such
+        assert_eq!(component.license(), None);
+        assert_eq!(identifier(&package, 3), "SPDXRef-Package-sp-core-1.0.0-3");
+    }
+
+    #[test]
+    fn identifies_registry_and_path_packages() {
+        let registry = package("serde", Some(CRATES_IO), Some("MIT OR Apache-2.0"));
+        let component = self::component(&registry);
+        assert_eq!(component.git, None);
+        assert_eq!(component.purl(), "pkg:cargo/serde@1.0.0");
+        assert_eq!(
+            component.download().as_deref(),
+            Some("https://crates.io/api/v1/crates/serde/1.0.0/download")
+        );
+        assert_eq!(component.license(), Some("MIT OR Apache-2.0"));
+        assert_eq!(identifier(&registry, 0), "SPDXRef-Package-serde-1.0.0-0");
+
+        let path = package("runtime", None, None);
+        assert_eq!(self::component(&path).download(), None);
+        assert_eq!(
+            git("registry+https://github.com/rust-lang/crates.io-index"),
+            None
+        );
+    }
+}
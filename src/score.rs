@@ -0,0 +1,106 @@
+//! A weighted scoring model, providing a consistent quantitative component to milestone evaluations.
+
+use crate::{config, Category, Finding, Severity};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+/// The score of a review, out of 100.
+pub struct Score {
+    /// The score of each category.
+    pub categories: Vec<(Category, u32)>,
+    /// The weighted average of the scores of the categories.
+    pub overall: u32,
+}
+
+impl Score {
+    /// Scores the findings of the categories, deducting points from each category per error and warning.
+    pub fn new(config: &config::Score, categories: &[Category], findings: &[Finding]) -> Self {
+        let categories: Vec<_> = categories
+            .iter()
+            .map(|category| {
+                let count = |severity| {
+                    findings
+                        .iter()
+                        .filter(|f| f.category == *category && f.severity == severity)
+                        .count() as u32
+                };
+                let deducted = count(Severity::Error).saturating_mul(config.error)
+                    + count(Severity::Warning).saturating_mul(config.warning);
+                (*category, 100u32.saturating_sub(deducted))
+            })
+            .collect();
+        let weight = |category: &Category| {
+            config
+                .weights
+                .get(&category.to_string())
+                .copied()
+                .unwrap_or_default()
+        };
+        let total: u32 = categories.iter().map(|(c, _)| weight(c)).sum();
+        let overall = match total {
+            0 => 100,
+            total => categories.iter().map(|(c, s)| weight(c) * s).sum::<u32>() / total,
+        };
+        Score {
+            categories,
+            overall,
+        }
+    }
+
+    /// The grade corresponding to the overall score, from A to F.
+    pub fn grade(&self) -> char {
+        match self.overall {
+            90.. => 'A',
+            80..=89 => 'B',
+            70..=79 => 'C',
+            60..=69 => 'D',
+            _ => 'F',
+        }
+    }
+}
+
+/// Serialized as the overall score and its grade, along with the score of each category keyed by category.
+impl Serialize for Score {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let categories: BTreeMap<String, u32> = self
+            .categories
+            .iter()
+            .map(|(category, score)| (category.to_string(), *score))
+            .collect();
+        let mut score = serializer.serialize_struct("Score", 3)?;
+        score.serialize_field("overall", &self.overall)?;
+        score.serialize_field("grade", &self.grade())?;
+        score.serialize_field("categories", &categories)?;
+        score.end()
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/100 ({})", self.overall, self.grade())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_grade_and_categories() {
+        let score = Score {
+            categories: vec![(Category::Code, 80), (Category::Fmt, 100)],
+            overall: 85,
+        };
+        assert_eq!(
+            serde_json::to_value(&score).unwrap(),
+            serde_json::json!({
+                "overall": 85,
+                "grade": "B",
+                "categories": { "code": 80, "fmt": 100 }
+            })
+        );
+    }
+}
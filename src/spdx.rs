@@ -0,0 +1,190 @@
+//! Validation of SPDX license expressions, e.g. `(MIT OR Apache-2.0) AND Unicode-DFS-2016`, against the SPDX license
+//! list of the `spdx` crate.
+
+use ::spdx::{
+    expression::ExpressionReq, Expression, LicenseItem, LicenseReq, ParseError, ParseMode,
+};
+
+/// Parses an SPDX license expression, as strictly required by crates.io. The legacy `/` separator is rejected, along
+/// with deprecated identifiers (e.g. `GPL-3.0`) and imprecise license names (e.g. `GPLv3` or `Apache 2.0`), suggesting
+/// the corresponding expression where known.
+pub fn validate(expression: &str) -> Result<Expression, String> {
+    if expression.contains('/') {
+        return Err("use 'OR' rather than '/' to separate licenses".into());
+    }
+    let parsed = Expression::parse(expression).map_err(|e| error(&e))?;
+    for requirement in parsed.requirements() {
+        let LicenseItem::Spdx { id, .. } = requirement.req.license else {
+            continue;
+        };
+        if !id.is_deprecated() {
+            continue;
+        }
+        // The GNU `-only` and `-or-later` identifiers are parsed as their deprecated counterparts
+        let term = term(expression, requirement);
+        if id.is_gnu() && (term.ends_with("-only") || term.ends_with("-or-later")) {
+            continue;
+        }
+        return Err(match id.is_gnu() {
+            true => format!(
+                "'{0}' is deprecated, use '{1}-only' or '{1}-or-later'",
+                term, id.name
+            ),
+            false => format!("'{}' is a deprecated license identifier", term),
+        });
+    }
+    Ok(parsed)
+}
+
+/// The license identifiers referenced by the expression, e.g. `GPL-3.0-only` or `Apache-2.0+`.
+pub fn licenses(expression: &Expression) -> Vec<String> {
+    expression
+        .requirements()
+        .map(|r| identifier(&r.req))
+        .collect()
+}
+
+/// Describes why an expression is invalid, suggesting the corresponding expression where it can be parsed leniently.
+fn error(error: &ParseError) -> String {
+    use ::spdx::error::Reason;
+
+    let term = error.original.get(error.span.clone()).unwrap_or_default();
+    let message = match &error.reason {
+        // Exceptions are only recognised following `WITH`, so are otherwise reported as unknown terms
+        Reason::UnknownTerm
+            if error.original[..error.span.start]
+                .trim_end()
+                .ends_with("WITH") =>
+        {
+            format!("unknown license exception '{}'", term)
+        }
+        Reason::UnknownException => format!("unknown license exception '{}'", term),
+        Reason::UnknownLicense | Reason::UnknownTerm => {
+            format!("unknown license identifier '{}'", term)
+        }
+        reason => reason.to_string(),
+    };
+    match suggestion(&error.original) {
+        Some(suggestion) => format!("{}, use '{}'", message, suggestion),
+        None => message,
+    }
+}
+
+/// Suggests a valid expression for an expression which can only be parsed leniently, e.g. `GPL-3.0-only` for `GPLv3`
+/// or `MIT OR Apache-2.0` for `MIT or Apache 2.0`.
+fn suggestion(expression: &str) -> Option<String> {
+    let parsed = Expression::parse_mode(expression, ParseMode::LAX).ok()?;
+    let mut suggestion = expression.to_string();
+    // Replace each license term with its identifier, from last to first so that earlier spans remain valid
+    let mut requirements: Vec<_> = parsed.requirements().collect();
+    requirements.sort_by_key(|r| std::cmp::Reverse(r.span.start));
+    for requirement in requirements {
+        // The span of a license excludes any postfix `+`, which the identifier includes
+        let (start, mut end) = (
+            requirement.span.start as usize,
+            requirement.span.end as usize,
+        );
+        if expression[end..].starts_with('+') {
+            end += 1;
+        }
+        suggestion.replace_range(start..end, &identifier(&requirement.req));
+    }
+    let suggestion = suggestion
+        .split_whitespace()
+        .map(|t| match t {
+            "and" | "or" | "with" => t.to_uppercase(),
+            _ => t.into(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    Expression::parse(&suggestion).is_ok().then_some(suggestion)
+}
+
+/// The identifier of a license requirement, e.g. `GPL-3.0-or-later` or `Apache-2.0+`, excluding any exception.
+fn identifier(requirement: &LicenseReq) -> String {
+    match &requirement.license {
+        LicenseItem::Spdx { id, or_later } if id.is_gnu() && id.is_deprecated() => match or_later {
+            true => format!("{}-or-later", id.name),
+            false => format!("{}-only", id.name),
+        },
+        license => license.to_string(),
+    }
+}
+
+/// The term of the expression referencing the license of a requirement.
+fn term<'a>(expression: &'a str, requirement: &ExpressionReq) -> &'a str {
+    let span = requirement.span.start as usize..requirement.span.end as usize;
+    expression.get(span).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_valid_expressions() {
+        for expression in [
+            "MIT",
+            "MIT OR Apache-2.0",
+            "(MIT OR Apache-2.0) AND Unicode-DFS-2016",
+            "Apache-2.0 WITH LLVM-exception",
+            "GPL-3.0-only",
+            "GPL-3.0-or-later WITH Classpath-exception-2.0",
+            "Apache-2.0+",
+            "LicenseRef-Proprietary",
+        ] {
+            assert!(validate(expression).is_ok(), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn rejects_legacy_separator() {
+        assert_eq!(
+            validate("MIT/Apache-2.0").unwrap_err(),
+            "use 'OR' rather than '/' to separate licenses"
+        );
+    }
+
+    #[test]
+    fn rejects_deprecated_identifiers() {
+        assert_eq!(
+            validate("MIT OR GPL-3.0").unwrap_err(),
+            "'GPL-3.0' is deprecated, use 'GPL-3.0-only' or 'GPL-3.0-or-later'"
+        );
+        assert_eq!(
+            validate("wxWindows").unwrap_err(),
+            "'wxWindows' is a deprecated license identifier"
+        );
+    }
+
+    #[test]
+    fn suggests_identifiers_for_imprecise_names() {
+        assert_eq!(
+            validate("GPLv3").unwrap_err(),
+            "unknown license identifier 'GPLv3', use 'GPL-3.0-only'"
+        );
+        assert_eq!(
+            validate("MIT OR Apache 2.0").unwrap_err(),
+            "unknown license identifier 'Apache', use 'MIT OR Apache-2.0'"
+        );
+        assert_eq!(
+            validate("GPL-2.0+").unwrap_err(),
+            "a GNU license was followed by a `+`, use 'GPL-2.0-or-later'"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_terms() {
+        assert_eq!(
+            validate("Proprietary").unwrap_err(),
+            "unknown license identifier 'Proprietary'"
+        );
+        assert_eq!(
+            validate("Apache-2.0 WITH Unknown-exception").unwrap_err(),
+            "unknown license exception 'Unknown-exception'"
+        );
+        assert!(validate("MIT OR").is_err());
+        assert!(validate("(MIT").is_err());
+        assert!(validate("MIT AND OR Apache-2.0").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    manifests, runtime, Analysis, Context, Error, Format,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{io, path::Path, process::Command};
+
+/// Builds a runtime deterministically within an [srtool](https://github.com/paritytech/srtool) container, reporting
+/// the resulting proposal hash and blob hashes so that reproducibility claims can be verified.
+pub struct Srtool {
+    /// The expected proposal hash, e.g. as claimed within release notes, if any.
+    pub proposal_hash: Option<String>,
+}
+
+impl Analysis for Srtool {
+    fn category(&self) -> Category {
+        Category::Runtime
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        let metadata = manifests::metadata(context)?;
+        let runtime = runtime::package(context, &metadata, None)?;
+        // The runtime directory is relative to the workspace root, which is mounted within the container
+        let runtime_directory = metadata
+            .packages
+            .iter()
+            .find(|p| p.name == runtime)
+            .and_then(|p| Path::new(&p.manifest_path).parent())
+            .and_then(|d| d.strip_prefix(&context.root).ok())
+            .map(|d| match d.as_os_str().is_empty() {
+                true => ".".into(),
+                false => d.to_string_lossy().to_string(),
+            })
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "runtime package '{}' not found within the workspace",
+                    runtime
+                ))
+            })?;
+
+        let image = &context.config.srtool.image;
+        if text {
+            println!("Building {} via srtool ({})...", runtime, image);
+        }
+        let output = Command::new("docker")
+            .args(["run", "--rm"])
+            .arg("--env")
+            .arg(format!("PACKAGE={}", runtime))
+            .arg("--env")
+            .arg(format!("RUNTIME_DIR={}", runtime_directory))
+            .arg("--volume")
+            .arg(format!("{}:/build", context.root.display()))
+            .arg(image)
+            .args(["build", "--app", "--json"])
+            .stderr(context.stdout())
+            .output()
+            .map_err(|source| match source.kind() {
+                io::ErrorKind::NotFound => Error::NotInstalled {
+                    tool: "docker",
+                    hint: "install it via https://docs.docker.com/get-docker",
+                },
+                _ => Error::Command {
+                    command: "docker run",
+                    source,
+                },
+            })?;
+
+        let mut findings = Vec::new();
+        let mut report = |finding: Finding| {
+            if let Some(finding) = context.config.apply(finding.with_package(&runtime)) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let Some(build) = build(&stdout).filter(|_| output.status.success()) else {
+            report(
+                Finding::new(
+                    Category::Runtime,
+                    Severity::Error,
+                    format!("srtool build failed ({})", output.status),
+                )
+                .with_code("sbp::srtool_failed"),
+            );
+            return Ok(findings);
+        };
+
+        for (name, blob) in [
+            ("compact", &build.runtimes.compact),
+            ("compressed", &build.runtimes.compressed),
+        ] {
+            let Some(blob) = blob else {
+                continue;
+            };
+            report(
+                Finding::new(
+                    Category::Runtime,
+                    Severity::Info,
+                    format!(
+                        "srtool {} runtime: proposal hash {}, blake2-256 {}, sha256 {}, {} bytes",
+                        name,
+                        blob.prop,
+                        blob.blake2_256,
+                        blob.sha256,
+                        blob.size
+                            .as_str()
+                            .map_or(blob.size.to_string(), str::to_string)
+                    ),
+                )
+                .with_code("sbp::srtool_build"),
+            );
+        }
+        let proposal_hash = build
+            .runtimes
+            .compressed
+            .as_ref()
+            .or(build.runtimes.compact.as_ref())
+            .map(|b| b.prop.as_str());
+        if let Some(expected) = &self.proposal_hash {
+            if proposal_hash.is_none_or(|actual| !actual.eq_ignore_ascii_case(expected)) {
+                report(
+                    Finding::new(
+                        Category::Runtime,
+                        Severity::Error,
+                        format!(
+                            "srtool proposal hash {} does not match the expected {}",
+                            proposal_hash.unwrap_or("-"),
+                            expected
+                        ),
+                    )
+                    .with_code("sbp::srtool_mismatch"),
+                );
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// The result of an srtool build, as output via `--json`.
+#[derive(Deserialize)]
+struct Build {
+    runtimes: Runtimes,
+}
+
+#[derive(Deserialize)]
+struct Runtimes {
+    compact: Option<Blob>,
+    compressed: Option<Blob>,
+}
+
+/// A runtime Wasm blob built by srtool.
+#[derive(Deserialize)]
+struct Blob {
+    /// The size in bytes, as a string or number depending on the version of srtool.
+    size: Value,
+    /// The hash of the `authorize_upgrade` (or `set_code`) proposal.
+    prop: String,
+    blake2_256: String,
+    sha256: String,
+}
+
+/// Parses the build result from the output of srtool, which follows any build log.
+fn build(output: &str) -> Option<Build> {
+    output
+        .match_indices('{')
+        .filter(|(index, _)| *index == 0 || output[..*index].ends_with('\n'))
+        .rev()
+        .find_map(|(index, _)| serde_json::from_str(&output[index..]).ok())
+}
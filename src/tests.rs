@@ -0,0 +1,507 @@
+//! Execution of the tests of the workspace, via `cargo test`. Compilation is tracked via the JSON messages of
+//! `--message-format=json`, which also identify the package of each test executable. The results of the tests
+//! themselves are parsed from the JSON events of libtest (`--format json --report-time`), which include the duration
+//! of each test. These require `-Z unstable-options`, so are enabled on stable toolchains via `RUSTC_BOOTSTRAP`.
+
+use crate::{
+    findings::{Category, Finding, Severity},
+    junit,
+    manifests::{self, Package},
+    progress::Progress,
+    watchdog::Watchdog,
+    Analysis, Context, Error, Format,
+};
+use colored::Colorize;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    process::{Child, ExitStatus, Stdio},
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+/// Executes available tests, via `cargo test`.
+pub struct Tests;
+
+impl Analysis for Tests {
+    fn category(&self) -> Category {
+        Category::Tests
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Executing available tests...");
+        }
+
+        // Doc tests cannot be combined with other target selection flags, so are executed separately
+        let targets: &[&[&str]] = match (context.all_targets, context.doc) {
+            (false, false) => &[&[]],
+            (true, false) => &[&["--all-targets"]],
+            (false, true) => &[&["--doc"]],
+            (true, true) => &[&["--all-targets"], &["--doc"]],
+        };
+        let mut statuses = Vec::new();
+        let mut suites = Vec::new();
+        let mut timeouts = Vec::new();
+        let progress = Progress::new(context, "cargo test", None);
+        for targets in targets {
+            let child = context
+                .cargo_packages("test")
+                .args(*targets)
+                .arg("--no-fail-fast")
+                .arg("--message-format=json")
+                .args([
+                    "--",
+                    "-Z",
+                    "unstable-options",
+                    "--format",
+                    "json",
+                    "--report-time",
+                ])
+                .env("RUSTC_BOOTSTRAP", "1")
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| Error::command("cargo test", e))?;
+            let (status, target_suites) = match execute(child, context, &progress) {
+                Ok(result) => result,
+                Err(e) => {
+                    let finding = e.timed_out(Category::Tests)?;
+                    if text {
+                        finding.print(context);
+                    }
+                    timeouts.push(finding);
+                    break;
+                }
+            };
+            statuses.push(status);
+            suites.extend(target_suites);
+        }
+        drop(progress);
+        if let Some(path) = &context.junit {
+            fs::write(path, junit::render(&suites)).map_err(|source| Error::Io {
+                path: path.clone(),
+                source,
+            })?;
+        }
+        if text {
+            let metadata = manifests::metadata(context)?;
+            let packages: Vec<_> = metadata
+                .packages
+                .iter()
+                .filter(|p| context.selected(&p.name))
+                .collect();
+            summarise(&suites, &packages);
+        }
+
+        let mut findings: Vec<Finding> = suites
+            .iter()
+            .flat_map(|s| s.cases.iter().map(move |c| (s, c)))
+            .filter(|(_, c)| matches!(c.outcome, Outcome::Failed(_)))
+            .map(|(suite, case)| {
+                Finding::new(
+                    Category::Tests,
+                    Severity::Error,
+                    format!("test '{}' failed", case.name),
+                )
+                .with_code("sbp::test_failed")
+                .with_package(&suite.package)
+            })
+            .collect();
+        // Tests may also fail to compile, or a test binary may fail without reporting any failed tests
+        if findings.is_empty() && timeouts.is_empty() {
+            if let Some(status) = statuses.iter().find(|s| !s.success()) {
+                findings.push(
+                    Finding::new(
+                        Category::Tests,
+                        Severity::Error,
+                        format!("tests failed ({})", status),
+                    )
+                    .with_code("sbp::tests_failed"),
+                );
+            }
+        }
+        Ok(findings
+            .into_iter()
+            .chain(timeouts)
+            .filter_map(|f| context.config.apply(f))
+            .collect())
+    }
+}
+
+/// The results of the tests of a single test target.
+pub struct Suite {
+    pub name: String,
+    /// The package containing the test target.
+    pub package: String,
+    pub cases: Vec<Case>,
+    /// The duration of the suite, in seconds.
+    pub time: f64,
+}
+
+pub struct Case {
+    pub name: String,
+    pub outcome: Outcome,
+    /// The duration of the test, in seconds.
+    pub time: f64,
+}
+
+pub enum Outcome {
+    Passed,
+    /// The test failed, with any captured output.
+    Failed(String),
+    Ignored,
+}
+
+enum Line {
+    Stdout(String),
+    Stderr(String),
+}
+
+/// A message emitted by `cargo test --message-format=json`.
+#[derive(Deserialize)]
+struct Message {
+    reason: String,
+    package_id: Option<String>,
+    target: Option<Target>,
+    executable: Option<String>,
+    message: Option<Diagnostic>,
+}
+
+#[derive(Deserialize)]
+struct Target {
+    name: String,
+    kind: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Diagnostic {
+    rendered: Option<String>,
+}
+
+/// An event emitted by libtest via `--format json`, e.g. `{ "type": "test", "event": "ok", "name": "..." }`.
+#[derive(Deserialize)]
+struct Event {
+    #[serde(rename = "type")]
+    kind: String,
+    event: String,
+    name: Option<String>,
+    /// The duration of the test or suite, in seconds, via `--report-time`.
+    exec_time: Option<f64>,
+    /// The captured output of a failed test.
+    stdout: Option<String>,
+}
+
+/// Waits for the `cargo test` process to complete, passing its output through whilst collecting the test results.
+fn execute(
+    mut child: Child,
+    context: &Context,
+    progress: &Progress,
+) -> Result<(ExitStatus, Vec<Suite>), Error> {
+    let timeout = context.config.timeout("tests");
+    let watchdog = Watchdog::new(&child, timeout);
+    let (sender, receiver) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward(stdout, sender.clone(), Line::Stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward(stderr, sender, Line::Stderr);
+    }
+
+    // The packages of test executables (by file name) and libraries (by crate name), as reported by cargo
+    let mut executables = HashMap::new();
+    let mut libraries = HashMap::new();
+    let mut suites: Vec<Suite> = Vec::new();
+    for line in receiver {
+        let line = match line {
+            Line::Stderr(line) => {
+                progress.suspend(|| eprintln!("{}", line));
+                if let Some((target, key)) = suite(&line) {
+                    let package = match &key {
+                        Key::Executable(file) => executables.get(file),
+                        Key::Library(name) => libraries.get(name),
+                    }
+                    .cloned()
+                    .unwrap_or_else(|| match key {
+                        Key::Executable(file) => file
+                            .rsplit_once('-')
+                            .map_or(file.clone(), |(name, _)| name.into()),
+                        Key::Library(name) => name,
+                    });
+                    let name = format!("{}: {}", package, target);
+                    progress.message(format!("running {}", name));
+                    suites.push(Suite {
+                        name,
+                        package,
+                        cases: Vec::new(),
+                        time: 0.0,
+                    });
+                }
+                continue;
+            }
+            Line::Stdout(line) => line,
+        };
+        if let Ok(message) = serde_json::from_str::<Message>(&line) {
+            let package = message
+                .package_id
+                .as_deref()
+                .and_then(manifests::package_name)
+                .map(String::from);
+            match message.reason.as_str() {
+                "compiler-artifact" => {
+                    if let (Some(package), Some(target)) = (package, &message.target) {
+                        progress.message(format!("compiled {}", package));
+                        if target.kind.iter().any(|k| k == "lib") {
+                            libraries.insert(target.name.replace('-', "_"), package.clone());
+                        }
+                        if let Some(file) = message.executable.as_deref().and_then(file_name) {
+                            executables.insert(file, package);
+                        }
+                    }
+                }
+                "compiler-message" => {
+                    if let Some(rendered) = message.message.and_then(|m| m.rendered) {
+                        progress.suspend(|| eprint!("{}", rendered));
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        // Lines which are neither cargo messages nor libtest events are output by the tests themselves
+        let line = match serde_json::from_str::<Event>(&line) {
+            Ok(event) => match suites.last_mut() {
+                Some(suite) => match record(suite, event) {
+                    Some(line) => line,
+                    None => continue,
+                },
+                None => continue,
+            },
+            Err(_) => line,
+        };
+        progress.suspend(|| match context.format {
+            Format::Text => println!("{}", line),
+            _ => eprintln!("{}", line),
+        });
+    }
+
+    let status = child.wait().map_err(|e| Error::command("cargo test", e))?;
+    if let (true, Some(timeout)) = (watchdog.finish(), timeout) {
+        return Err(Error::Timeout {
+            command: "cargo test",
+            timeout,
+        });
+    }
+    Ok((status, suites))
+}
+
+/// Records the libtest event against the suite, returning the equivalent line of the human-readable output, if any.
+fn record(suite: &mut Suite, event: Event) -> Option<String> {
+    match (event.kind.as_str(), event.event.as_str()) {
+        (_, "started") => None,
+        ("suite", result) => {
+            suite.time = event.exec_time.unwrap_or_default();
+            Some(format!(
+                "test result: {}. finished in {:.2}s",
+                result, suite.time
+            ))
+        }
+        ("test", result) => {
+            let outcome = match result {
+                "ok" => Outcome::Passed,
+                "failed" => Outcome::Failed(event.stdout.unwrap_or_default()),
+                "ignored" => Outcome::Ignored,
+                _ => return None,
+            };
+            let name = event.name?;
+            let mut line = format!("test {} ... {}", name, result);
+            if let Outcome::Failed(output) = &outcome {
+                if !output.is_empty() {
+                    line.push_str(&format!(
+                        "\n---- {} stdout ----\n{}",
+                        name,
+                        output.trim_end()
+                    ));
+                }
+            }
+            suite.cases.push(Case {
+                name,
+                outcome,
+                time: event.exec_time.unwrap_or_default(),
+            });
+            Some(line)
+        }
+        _ => None,
+    }
+}
+
+/// Outputs a summary of the test results and the kinds of targets of each package, listing any failed tests.
+fn summarise(suites: &[Suite], packages: &[&Package]) {
+    let mut results: BTreeMap<&str, Vec<&Suite>> = packages
+        .iter()
+        .map(|p| (p.name.as_str(), Vec::new()))
+        .collect();
+    for suite in suites {
+        results.entry(&suite.package).or_default().push(suite);
+    }
+
+    println!("{}", "Test Summary".bold());
+    for (package, suites) in results {
+        let cases: Vec<_> = suites.iter().flat_map(|s| &s.cases).collect();
+        let count =
+            |outcome: fn(&Outcome) -> bool| cases.iter().filter(|c| outcome(&c.outcome)).count();
+        let failed = count(|o| matches!(o, Outcome::Failed(_)));
+        println!(
+            "  {}: {} total, {} {}, {} {}, {} ignored",
+            package.cyan(),
+            cases.len(),
+            count(|o| matches!(o, Outcome::Passed)),
+            "passed".green(),
+            failed,
+            match failed {
+                0 => "failed".normal(),
+                _ => "failed".red(),
+            },
+            count(|o| matches!(o, Outcome::Ignored)),
+        );
+        let mut failures: Vec<_> = cases
+            .iter()
+            .filter(|c| matches!(c.outcome, Outcome::Failed(_)))
+            .map(|c| c.name.as_str())
+            .collect();
+        failures.sort_unstable();
+        for failure in failures {
+            println!("    {} {}", "FAILED".red().bold(), failure);
+        }
+
+        if let Some(package) = packages.iter().find(|p| p.name == package) {
+            let mut kinds: Vec<_> = package
+                .targets
+                .iter()
+                .flat_map(|t| &t.kind)
+                .map(String::as_str)
+                .collect();
+            kinds.sort_unstable();
+            kinds.dedup();
+            let missing: Vec<_> = ["test", "example", "bench"]
+                .into_iter()
+                .filter(|k| !kinds.contains(k))
+                .collect();
+            print!("    targets: {}", kinds.join(", "));
+            match missing.is_empty() {
+                true => println!(),
+                false => println!(" ({} {})", "missing:".yellow(), missing.join(", ")),
+            }
+        }
+    }
+}
+
+/// Forwards each line read from the reader to the channel, on a separate thread.
+fn forward(reader: impl Read + Send + 'static, sender: Sender<Line>, line: fn(String) -> Line) {
+    thread::spawn(move || {
+        for l in BufReader::new(reader).lines().map_while(Result::ok) {
+            if sender.send(line(l)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Identifies the test target of a suite.
+enum Key {
+    /// The file name of the test executable.
+    Executable(String),
+    /// The crate name of the library whose doc tests are executed.
+    Library(String),
+}
+
+/// Returns the description and key of the test target whose suite is started by the line of cargo output, if any.
+fn suite(line: &str) -> Option<(String, Key)> {
+    let line = line.trim();
+    if let Some(library) = line.strip_prefix("Doc-tests ") {
+        return Some(("doc-tests".into(), Key::Library(library.into())));
+    }
+    // e.g. `Running unittests src/lib.rs (target/debug/deps/sample-e6daf73c296d6df0)`
+    let (target, executable) = line.strip_prefix("Running ")?.rsplit_once(" (")?;
+    let executable = file_name(executable.trim_end_matches(')'))?;
+    Some((target.into(), Key::Executable(executable)))
+}
+
+fn file_name(path: &str) -> Option<String> {
+    Path::new(path)
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod unit {
+    use super::*;
+
+    #[test]
+    fn identifies_suites() {
+        let Some((target, Key::Executable(file))) =
+            suite("     Running unittests src/lib.rs (target/debug/deps/sample-e6daf73c296d6df0)")
+        else {
+            panic!("expected a test executable");
+        };
+        assert_eq!(target, "unittests src/lib.rs");
+        assert_eq!(file, "sample-e6daf73c296d6df0");
+
+        let Some((target, Key::Library(library))) = suite("   Doc-tests sample_lib") else {
+            panic!("expected a library");
+        };
+        assert_eq!(target, "doc-tests");
+        assert_eq!(library, "sample_lib");
+
+        assert!(suite("test result: ok. 1 passed; 0 failed").is_none());
+        assert!(suite("   Compiling sample v0.1.0").is_none());
+    }
+
+    #[test]
+    fn records_events() {
+        let mut suite = Suite {
+            name: "sample: unittests src/lib.rs".into(),
+            package: "sample".into(),
+            cases: Vec::new(),
+            time: 0.0,
+        };
+        let lines: Vec<_> = [
+            r#"{ "type": "suite", "event": "started", "test_count": 3 }"#,
+            r#"{ "type": "test", "event": "started", "name": "tests::a" }"#,
+            r#"{ "type": "test", "name": "tests::a", "event": "ok", "exec_time": 0.25 }"#,
+            r#"{ "type": "test", "name": "tests::b", "event": "failed", "exec_time": 0.5, "stdout": "panicked\n" }"#,
+            r#"{ "type": "test", "name": "tests::c", "event": "ignored" }"#,
+            r#"{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "ignored": 1, "exec_time": 1.5 }"#,
+        ]
+        .into_iter()
+        .filter_map(|e| record(&mut suite, serde_json::from_str(e).unwrap()))
+        .collect();
+
+        assert_eq!(
+            lines,
+            [
+                "test tests::a ... ok",
+                "test tests::b ... failed\n---- tests::b stdout ----\npanicked",
+                "test tests::c ... ignored",
+                "test result: failed. finished in 1.50s",
+            ]
+        );
+        let cases: Vec<_> = suite
+            .cases
+            .iter()
+            .map(|c| (c.name.as_str(), c.time))
+            .collect();
+        assert_eq!(
+            cases,
+            [("tests::a", 0.25), ("tests::b", 0.5), ("tests::c", 0.0)]
+        );
+        assert!(
+            matches!(&suite.cases[1].outcome, Outcome::Failed(output) if output == "panicked\n")
+        );
+        assert_eq!(suite.time, 1.5);
+    }
+}
@@ -0,0 +1,381 @@
+//! An interactive terminal user interface for browsing findings.
+
+use crate::{Error, Finding, Severity};
+use ratatui::{
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{self, IsTerminal},
+    path::Path,
+    process::Command,
+};
+
+/// The number of lines of source shown either side of the location of a finding.
+const CONTEXT: usize = 3;
+/// The number of findings moved by a page.
+const PAGE: isize = 10;
+
+/// Browses the findings interactively, until the user quits.
+pub fn browse(root: &Path, findings: &[Finding]) -> Result<(), Error> {
+    let io = |source| Error::Command {
+        command: "terminal user interface",
+        source,
+    };
+    if !io::stdout().is_terminal() {
+        return Err(Error::Config(
+            "--tui requires an interactive terminal".into(),
+        ));
+    }
+    if findings.is_empty() {
+        println!("No findings");
+        return Ok(());
+    }
+    let mut app = App::new(root, findings);
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal).map_err(io);
+    ratatui::restore();
+    result
+}
+
+/// The state of the user interface.
+struct App<'a> {
+    root: &'a Path,
+    findings: &'a [Finding],
+    /// The lines of each file in which findings are located.
+    sources: BTreeMap<&'a str, Vec<String>>,
+    packages: Vec<&'a str>,
+    lints: Vec<&'a str>,
+    /// The selected package, lint and severity filters, if any.
+    package: Option<usize>,
+    lint: Option<usize>,
+    severity: Option<Severity>,
+    /// The indices of the findings matching the filters.
+    visible: Vec<usize>,
+    list: ListState,
+    /// A message shown within the footer, e.g. when the editor could not be opened.
+    status: Option<String>,
+}
+
+impl<'a> App<'a> {
+    fn new(root: &'a Path, findings: &'a [Finding]) -> Self {
+        let files: BTreeSet<&str> = findings
+            .iter()
+            .filter_map(|f| f.location.as_ref().map(|l| l.file.as_str()))
+            .collect();
+        let sources = files
+            .into_iter()
+            .filter_map(|file| {
+                let source = fs::read_to_string(root.join(file)).ok()?;
+                Some((file, source.lines().map(str::to_string).collect()))
+            })
+            .collect();
+        let packages: BTreeSet<&str> = findings
+            .iter()
+            .filter_map(|f| f.package.as_deref())
+            .collect();
+        let lints: BTreeSet<&str> = findings.iter().filter_map(|f| f.code.as_deref()).collect();
+        let mut app = App {
+            root,
+            findings,
+            sources,
+            packages: packages.into_iter().collect(),
+            lints: lints.into_iter().collect(),
+            package: None,
+            lint: None,
+            severity: None,
+            visible: Vec::new(),
+            list: ListState::default(),
+            status: None,
+        };
+        app.filter();
+        app
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            self.status = None;
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Down | KeyCode::Char('j') => self.select(1),
+                KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+                KeyCode::PageDown => self.select(PAGE),
+                KeyCode::PageUp => self.select(-PAGE),
+                KeyCode::Home | KeyCode::Char('g') => self.select(isize::MIN),
+                KeyCode::End | KeyCode::Char('G') => self.select(isize::MAX),
+                KeyCode::Char('p') => {
+                    self.package = cycle(self.package, self.packages.len());
+                    self.filter();
+                }
+                KeyCode::Char('l') => {
+                    self.lint = cycle(self.lint, self.lints.len());
+                    self.filter();
+                }
+                KeyCode::Char('s') => {
+                    self.severity = match self.severity {
+                        None => Some(Severity::Error),
+                        Some(Severity::Error) => Some(Severity::Warning),
+                        Some(Severity::Warning) => Some(Severity::Info),
+                        Some(Severity::Info) => None,
+                    };
+                    self.filter();
+                }
+                KeyCode::Char('c') => {
+                    (self.package, self.lint, self.severity) = (None, None, None);
+                    self.filter();
+                }
+                KeyCode::Enter | KeyCode::Char('e') => {
+                    self.open()?;
+                    terminal.clear()?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The selected finding, if any.
+    fn selected(&self) -> Option<&'a Finding> {
+        let index = self.visible.get(self.list.selected()?)?;
+        Some(&self.findings[*index])
+    }
+
+    /// Moves the selection by the offset, within the bounds of the visible findings.
+    fn select(&mut self, offset: isize) {
+        let Some(last) = self.visible.len().checked_sub(1) else {
+            return;
+        };
+        let current = self.list.selected().unwrap_or(0) as isize;
+        let index = current.saturating_add(offset).clamp(0, last as isize);
+        self.list.select(Some(index as usize));
+    }
+
+    /// Applies the filters, retaining the selected finding when it remains visible.
+    fn filter(&mut self) {
+        let selected = self
+            .list
+            .selected()
+            .and_then(|i| self.visible.get(i).copied());
+        let package = self.package.map(|i| self.packages[i]);
+        let lint = self.lint.map(|i| self.lints[i]);
+        self.visible = self
+            .findings
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| package.is_none_or(|p| f.package.as_deref() == Some(p)))
+            .filter(|(_, f)| lint.is_none_or(|l| f.code.as_deref() == Some(l)))
+            .filter(|(_, f)| self.severity.is_none_or(|s| f.severity == s))
+            .map(|(index, _)| index)
+            .collect();
+        let position = selected.and_then(|s| self.visible.iter().position(|i| *i == s));
+        self.list.select(match self.visible.is_empty() {
+            true => None,
+            false => Some(position.unwrap_or(0)),
+        });
+    }
+
+    /// Opens the file of the selected finding within `$EDITOR` (or `$VISUAL`), at the line of the finding.
+    fn open(&mut self) -> io::Result<()> {
+        let Some(location) = self.selected().and_then(|f| f.location.as_ref()) else {
+            self.status = Some("the finding has no location".into());
+            return Ok(());
+        };
+        let Some(editor) = std::env::var("EDITOR")
+            .or_else(|_| std::env::var("VISUAL"))
+            .ok()
+            .filter(|e| !e.trim().is_empty())
+        else {
+            self.status = Some("set $EDITOR to open files".into());
+            return Ok(());
+        };
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or_default();
+        let mut command = Command::new(program);
+        command.args(words).current_dir(self.root);
+        let (file, line, column) = (&location.file, location.line, location.column);
+        // Editors differ in how a line is specified, with most supporting `+line`
+        match Path::new(program).file_name().and_then(|n| n.to_str()) {
+            Some("code" | "code-insiders" | "codium" | "cursor") => command
+                .arg("--goto")
+                .arg(format!("{}:{}:{}", file, line, column)),
+            Some("hx" | "helix" | "subl" | "zed") => {
+                command.arg(format!("{}:{}:{}", file, line, column))
+            }
+            _ => command.arg(format!("+{}", line)).arg(file),
+        };
+
+        terminal::disable_raw_mode()?;
+        execute!(io::stdout(), LeaveAlternateScreen)?;
+        let status = command.status();
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        terminal::enable_raw_mode()?;
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => self.status = Some(format!("{} exited with {}", program, status)),
+            Err(e) => self.status = Some(format!("could not execute {}: {}", program, e)),
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [list, detail] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(main);
+
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|index| {
+                let finding = &self.findings[*index];
+                let mut spans = vec![Span::styled(
+                    format!("{:<8}", finding.severity.as_str()),
+                    style(finding.severity),
+                )];
+                if let Some(location) = &finding.location {
+                    spans.push(Span::styled(
+                        format!("{}:{} ", location.file, location.line),
+                        Style::new().fg(Color::Cyan),
+                    ));
+                }
+                spans.push(Span::raw(&finding.message));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let items = List::new(items)
+            .block(Block::new().borders(Borders::ALL).title(format!(
+                " Findings ({}/{}) ",
+                self.visible.len(),
+                self.findings.len()
+            )))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(items, list, &mut self.list);
+
+        let details = Paragraph::new(self.selected().map(|f| self.details(f)).unwrap_or_default())
+            .block(Block::new().borders(Borders::ALL).title(" Details "))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details, detail);
+
+        let filter = |name, value: Option<&str>| {
+            vec![
+                Span::styled(name, Style::new().add_modifier(Modifier::BOLD)),
+                Span::raw(format!(" {}  ", value.unwrap_or("all"))),
+            ]
+        };
+        let mut spans = Vec::new();
+        spans.extend(filter("p package:", self.package.map(|i| self.packages[i])));
+        spans.extend(filter("l lint:", self.lint.map(|i| self.lints[i])));
+        spans.extend(filter("s severity:", self.severity.map(|s| s.as_str())));
+        spans.push(Span::raw("c clear  e open in $EDITOR  q quit"));
+        if let Some(status) = &self.status {
+            spans.push(Span::styled(
+                format!("  {}", status),
+                Style::new().fg(Color::Red),
+            ));
+        }
+        frame.render_widget(Line::from(spans), footer);
+    }
+
+    /// The details of the finding: its message, source snippet and help.
+    fn details(&self, finding: &'a Finding) -> Vec<Line<'_>> {
+        let mut title = vec![Span::styled(
+            finding.severity.as_str(),
+            style(finding.severity),
+        )];
+        if let Some(code) = &finding.code {
+            title.push(Span::raw(format!("[{}]", code)));
+        }
+        let mut lines = vec![
+            Line::from(title),
+            Line::styled(
+                finding.message.as_str(),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Line::raw(format!("category: {}", finding.category)),
+        ];
+        if let Some(package) = &finding.package {
+            lines.push(Line::raw(format!("package: {}", package)));
+        }
+        if let Some(location) = &finding.location {
+            lines.push(Line::raw(format!(
+                "location: {}:{}:{}",
+                location.file, location.line, location.column
+            )));
+            if let Some(source) = self.sources.get(location.file.as_str()) {
+                let (start, end) = (
+                    usize::from(location.line),
+                    usize::from(location.line_end.max(location.line)),
+                );
+                let width = (end + CONTEXT).to_string().len();
+                lines.push(Line::default());
+                for number in start.saturating_sub(CONTEXT).max(1)..=end + CONTEXT {
+                    let Some(text) = source.get(number - 1) else {
+                        break;
+                    };
+                    let highlighted = (start..=end).contains(&number);
+                    lines.push(Line::from(vec![
+                        Span::styled(
+                            format!("{:>width$} | ", number, width = width),
+                            Style::new().fg(Color::DarkGray),
+                        ),
+                        Span::styled(
+                            text.as_str(),
+                            match highlighted {
+                                true => style(finding.severity).add_modifier(Modifier::BOLD),
+                                false => Style::new(),
+                            },
+                        ),
+                    ]));
+                }
+            }
+        }
+        if !finding.help.is_empty() {
+            lines.push(Line::default());
+            for help in &finding.help {
+                lines.push(Line::from(vec![
+                    Span::styled("help: ", Style::new().fg(Color::Green)),
+                    Span::raw(help.as_str()),
+                ]));
+            }
+        }
+        lines
+    }
+}
+
+/// The style of a severity.
+fn style(severity: Severity) -> Style {
+    match severity {
+        Severity::Error => Style::new().fg(Color::Red),
+        Severity::Warning => Style::new().fg(Color::Yellow),
+        Severity::Info => Style::new(),
+    }
+}
+
+/// Cycles a filter through each of its values, followed by none.
+fn cycle(current: Option<usize>, len: usize) -> Option<usize> {
+    match current {
+        None if len > 0 => Some(0),
+        Some(index) if index + 1 < len => Some(index + 1),
+        _ => None,
+    }
+}
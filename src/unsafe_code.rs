@@ -0,0 +1,327 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    manifests,
+    pallets::{self, Source},
+    Analysis, Context, Error, Format,
+};
+use colored::Colorize;
+use proc_macro2::Span;
+use std::{collections::BTreeMap, path::Path};
+use syn::{
+    visit::{self, Visit},
+    ExprUnsafe, ImplItemFn, ItemFn, ItemImpl, ItemTrait, TraitItemFn,
+};
+
+/// Counts and locates `unsafe` code (blocks, functions, implementations and traits) within the workspace and its
+/// direct dependencies, in the style of `cargo geiger`. Unsafe code within runtime crates is reported as an error, as
+/// undefined behaviour within the runtime can compromise the chain.
+pub struct Unsafe;
+
+/// The kinds of `unsafe` code.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Block,
+    Function,
+    Implementation,
+    Trait,
+}
+
+impl Kind {
+    const ALL: [Kind; 4] = [
+        Kind::Block,
+        Kind::Function,
+        Kind::Implementation,
+        Kind::Trait,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Block => "block",
+            Kind::Function => "fn",
+            Kind::Implementation => "impl",
+            Kind::Trait => "trait",
+        }
+    }
+}
+
+impl Analysis for Unsafe {
+    fn category(&self) -> Category {
+        Category::Code
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Analysing unsafe code...");
+        }
+
+        let metadata = manifests::dependency_metadata(context)?;
+        let members: Vec<_> = metadata
+            .packages
+            .iter()
+            .filter(|p| metadata.workspace_members.contains(&p.id) && context.selected(&p.name))
+            .collect();
+        // The direct (normal) dependencies of the selected members, excluding any workspace members
+        let mut dependencies: Vec<_> = members
+            .iter()
+            .filter_map(|m| {
+                metadata
+                    .resolve
+                    .as_ref()?
+                    .nodes
+                    .iter()
+                    .find(|n| n.id == m.id)
+            })
+            .flat_map(|n| &n.deps)
+            .filter(|d| d.dep_kinds.iter().any(|k| k.kind.is_none()))
+            .filter(|d| !metadata.workspace_members.contains(&d.pkg))
+            .filter_map(|d| metadata.package(&d.pkg))
+            .collect();
+        dependencies.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        dependencies.dedup_by(|a, b| a.id == b.id);
+
+        let mut findings = Vec::new();
+        let mut census: BTreeMap<(bool, String), Vec<Kind>> = BTreeMap::new();
+        let mut report = |finding: Finding| {
+            if let Some(finding) = context.config.apply(finding) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        };
+        for member in members {
+            let Some(directory) = Path::new(&member.manifest_path).parent() else {
+                continue;
+            };
+            // Runtime crates (pallets, runtimes and their primitives) are those built without `std`, for WebAssembly
+            let runtime = member.features.contains_key("std");
+            let usages = census.entry((false, member.name.clone())).or_default();
+            for source in pallets::sources(directory, &context.root) {
+                for (kind, span) in usages_of(&source) {
+                    usages.push(kind);
+                    let (severity, reason) = match runtime {
+                        true => (
+                            Severity::Error,
+                            " within a runtime crate, where undefined behaviour can compromise the chain",
+                        ),
+                        false => (Severity::Warning, ""),
+                    };
+                    let mut finding = Finding::new(
+                        Category::Code,
+                        severity,
+                        format!("unsafe {}{}", kind.as_str(), reason),
+                    )
+                    .with_code("sbp::unsafe_code")
+                    .with_package(&member.name)
+                    .with_location(source.location(span));
+                    finding
+                        .help
+                        .push("document the invariants upheld via a `// SAFETY:` comment".into());
+                    report(finding);
+                }
+            }
+        }
+        for dependency in dependencies {
+            let Some(directory) = Path::new(&dependency.manifest_path).parent() else {
+                continue;
+            };
+            let usages: Vec<_> = pallets::sources(directory, &context.root)
+                .iter()
+                .flat_map(|source| usages_of(source).into_iter().map(|(kind, _)| kind))
+                .collect();
+            if !usages.is_empty() {
+                report(
+                    Finding::new(
+                        Category::Code,
+                        Severity::Info,
+                        format!("dependency contains {}", describe(&usages)),
+                    )
+                    .with_code("sbp::unsafe_dependency")
+                    .with_package(&dependency.name),
+                );
+            }
+            census.insert(
+                (true, format!("{} {}", dependency.name, dependency.version)),
+                usages,
+            );
+        }
+
+        if text {
+            print(&census);
+        }
+        Ok(findings)
+    }
+}
+
+/// Describes the number of usages of each kind of `unsafe` code, e.g. `2 unsafe block(s) and 1 unsafe fn(s)`.
+fn describe(usages: &[Kind]) -> String {
+    let counts: Vec<_> = Kind::ALL
+        .iter()
+        .map(|kind| (kind, usages.iter().filter(|k| *k == kind).count()))
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| format!("{} unsafe {}(s)", count, kind.as_str()))
+        .collect();
+    match counts.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => "no unsafe code".into(),
+    }
+}
+
+/// Outputs the number of usages of each kind of `unsafe` code, per workspace member and then per direct dependency.
+fn print(census: &BTreeMap<(bool, String), Vec<Kind>>) {
+    println!("{}", "Unsafe Census".bold());
+    let width = census
+        .keys()
+        .map(|(_, name)| name.len())
+        .max()
+        .unwrap_or_default()
+        .max("package".len());
+    print!("  {:width$}", "package", width = width);
+    for kind in Kind::ALL {
+        print!("  {:>6}", kind.as_str());
+    }
+    println!();
+    let mut dependencies = false;
+    for ((dependency, name), usages) in census {
+        if *dependency && !dependencies {
+            println!("  {}", "dependencies".dimmed());
+            dependencies = true;
+        }
+        let mut line = format!("  {:width$}", name, width = width);
+        for kind in Kind::ALL {
+            let count = usages.iter().filter(|k| **k == kind).count();
+            line.push_str(&format!("  {:>6}", count));
+        }
+        match usages.is_empty() {
+            true => println!("{}", line.dimmed()),
+            false => println!("{}", line),
+        }
+    }
+}
+
+/// Returns each usage of `unsafe` code within the source, along with its location.
+fn usages_of(source: &Source) -> Vec<(Kind, Span)> {
+    #[derive(Default)]
+    struct Usages(Vec<(Kind, Span)>);
+    impl<'a> Visit<'a> for Usages {
+        fn visit_expr_unsafe(&mut self, expr: &'a ExprUnsafe) {
+            self.0.push((Kind::Block, expr.unsafe_token.span));
+            visit::visit_expr_unsafe(self, expr);
+        }
+
+        fn visit_item_fn(&mut self, item: &'a ItemFn) {
+            if let Some(token) = &item.sig.unsafety {
+                self.0.push((Kind::Function, token.span));
+            }
+            visit::visit_item_fn(self, item);
+        }
+
+        fn visit_impl_item_fn(&mut self, item: &'a ImplItemFn) {
+            if let Some(token) = &item.sig.unsafety {
+                self.0.push((Kind::Function, token.span));
+            }
+            visit::visit_impl_item_fn(self, item);
+        }
+
+        fn visit_trait_item_fn(&mut self, item: &'a TraitItemFn) {
+            if let Some(token) = &item.sig.unsafety {
+                self.0.push((Kind::Function, token.span));
+            }
+            visit::visit_trait_item_fn(self, item);
+        }
+
+        fn visit_item_impl(&mut self, item: &'a ItemImpl) {
+            if let Some(token) = &item.unsafety {
+                self.0.push((Kind::Implementation, token.span));
+            }
+            visit::visit_item_impl(self, item);
+        }
+
+        fn visit_item_trait(&mut self, item: &'a ItemTrait) {
+            if let Some(token) = &item.unsafety {
+                self.0.push((Kind::Trait, token.span));
+            }
+            visit::visit_item_trait(self, item);
+        }
+    }
+
+    let mut usages = Usages::default();
+    usages.visit_file(&source.file);
+    usages.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usages(code: &str) -> Vec<(&'static str, usize)> {
+        let source = Source {
+            path: "src/lib.rs".into(),
+            file: syn::parse_file(code).unwrap(),
+        };
+        usages_of(&source)
+            .into_iter()
+            .map(|(kind, span)| (kind.as_str(), span.start().line))
+            .collect()
+    }
+
+    #[test]
+    fn counts_unsafe_code() {
+        let usages = usages(
+            r#"
+            unsafe fn read(pointer: *const u8) -> u8 {
+                unsafe { *pointer }
+            }
+            unsafe trait Zeroable {}
+            unsafe impl Zeroable for u8 {}
+            trait Raw {
+                unsafe fn raw(&self);
+            }
+            impl Raw for u8 {
+                unsafe fn raw(&self) {}
+            }
+            "#,
+        );
+        assert_eq!(
+            usages,
+            [
+                ("fn", 2),
+                ("block", 3),
+                ("trait", 5),
+                ("impl", 6),
+                ("fn", 8),
+                ("fn", 11)
+            ]
+        );
+        let kinds = [
+            Kind::Function,
+            Kind::Block,
+            Kind::Trait,
+            Kind::Implementation,
+            Kind::Function,
+            Kind::Function,
+        ];
+        assert_eq!(
+            describe(&kinds),
+            "1 unsafe block(s), 3 unsafe fn(s), 1 unsafe impl(s) and 1 unsafe trait(s)"
+        );
+        assert_eq!(describe(&[Kind::Block]), "1 unsafe block(s)");
+    }
+
+    #[test]
+    fn safe_code_is_accepted() {
+        assert!(usages(
+            r#"
+            fn read(bytes: &[u8]) -> Option<u8> {
+                bytes.first().copied()
+            }
+            // unsafe { }
+            const UNSAFE: &str = "unsafe fn";
+            "#
+        )
+        .is_empty());
+        assert_eq!(describe(&[]), "no unsafe code");
+    }
+}
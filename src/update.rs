@@ -0,0 +1,193 @@
+//! Updating of the current binary to the latest release.
+
+use crate::{github, registry::Version, Error};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{env, fs, path::Path};
+
+/// The repository from which releases are published.
+const REPOSITORY: &str = "evilrobot-01/sbp-review";
+/// Files listing the checksums of all assets of a release, each line containing a checksum followed by an asset name.
+const CHECKSUMS: [&str; 3] = ["SHA256SUMS", "sha256sums.txt", "checksums.txt"];
+
+/// A release, as returned by the GitHub releases API.
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// The outcome of checking for (and applying) an update.
+pub enum Update {
+    /// The current version is the latest release.
+    UpToDate(String),
+    /// A newer release is available, but was not installed.
+    Available { version: String, url: String },
+    /// The newer release was installed.
+    Updated { version: String, url: String },
+}
+
+/// Checks for a newer release of sbp-review, replacing the current binary with that of the release for the current
+/// platform unless only checking. The binary is only replaced once its SHA-256 checksum has been verified against that
+/// published with the release.
+pub fn update(check: bool) -> Result<Update, Error> {
+    let release = github::json(
+        github::get(&format!("/repos/{}/releases/latest", REPOSITORY)),
+        "GitHub release",
+    )?;
+    let release: Release = serde_json::from_value(release).map_err(|source| Error::Parse {
+        command: "GitHub release",
+        source,
+    })?;
+    let current = env!("CARGO_PKG_VERSION");
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    let newer = Version::parse(&version)
+        .zip(Version::parse(current))
+        .is_some_and(|(latest, current)| latest > current);
+    if !newer {
+        return Ok(Update::UpToDate(current.into()));
+    }
+    if check {
+        return Ok(Update::Available {
+            version,
+            url: release.html_url,
+        });
+    }
+
+    let asset = asset(&release.assets).ok_or_else(|| {
+        Error::Config(format!(
+            "release {} has no binary for {}-{}, install it via `cargo install --git https://github.com/{}`",
+            version,
+            env::consts::ARCH,
+            env::consts::OS,
+            REPOSITORY
+        ))
+    })?;
+    let expected = checksum(&release.assets, &asset.name).ok_or_else(|| {
+        Error::Config(format!(
+            "release {} has no checksum for {}, so it cannot be verified",
+            version, asset.name
+        ))
+    })?;
+    let binary = github::download(&asset.browser_download_url).ok_or_else(|| Error::Failed {
+        command: "self-update",
+        stderr: format!("could not download {}", asset.browser_download_url),
+    })?;
+    let actual = hex(&Sha256::digest(&binary));
+    if !actual.eq_ignore_ascii_case(&expected) {
+        return Err(Error::Failed {
+            command: "self-update",
+            stderr: format!(
+                "checksum of {} is {}, expected {}",
+                asset.name, actual, expected
+            ),
+        });
+    }
+    let executable = env::current_exe().map_err(|source| Error::Command {
+        command: "self-update",
+        source,
+    })?;
+    replace(&executable, &binary)?;
+    Ok(Update::Updated {
+        version,
+        url: release.html_url,
+    })
+}
+
+/// The binary asset for the current platform, named by its target (e.g. `sbp-review-x86_64-unknown-linux-gnu`).
+fn asset(assets: &[Asset]) -> Option<&Asset> {
+    let os: &[&str] = match env::consts::OS {
+        "macos" => &["apple", "darwin", "macos"],
+        os => &[os],
+    };
+    assets.iter().find(|a| {
+        let name = a.name.to_lowercase();
+        name.starts_with("sbp-review")
+            && name.contains(env::consts::ARCH)
+            && os.iter().any(|os| name.contains(os))
+            && !name.ends_with(".sha256")
+            && !CHECKSUMS.iter().any(|c| name == c.to_lowercase())
+    })
+}
+
+/// The published checksum of the asset, either within a `<asset>.sha256` file or a file listing all checksums.
+fn checksum(assets: &[Asset], name: &str) -> Option<String> {
+    let sha256 = format!("{}.sha256", name);
+    if let Some(file) = assets.iter().find(|a| a.name == sha256) {
+        let contents = github::download(&file.browser_download_url)?;
+        return String::from_utf8_lossy(&contents)
+            .split_whitespace()
+            .next()
+            .map(str::to_string);
+    }
+    let file = assets
+        .iter()
+        .find(|a| CHECKSUMS.contains(&a.name.as_str()))?;
+    let contents = github::download(&file.browser_download_url)?;
+    listed(&String::from_utf8_lossy(&contents), name)
+}
+
+/// The checksum of the asset within a file listing the checksums of all assets, as output by `sha256sum`.
+fn listed(checksums: &str, name: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let (checksum, file) = line.split_once(char::is_whitespace)?;
+        // Files may be prefixed with `*` when checksummed in binary mode
+        (file.trim().trim_start_matches('*') == name).then(|| checksum.to_string())
+    })
+}
+
+/// Replaces the executable with the binary. The executable is first moved aside, as a running executable cannot be
+/// overwritten on all platforms.
+fn replace(executable: &Path, binary: &[u8]) -> Result<(), Error> {
+    let io = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| Error::Io { path, source }
+    };
+    let new = executable.with_extension("new");
+    let old = executable.with_extension("old");
+    fs::write(&new, binary).map_err(io(&new))?;
+    let permissions = fs::metadata(executable)
+        .map_err(io(executable))?
+        .permissions();
+    fs::set_permissions(&new, permissions).map_err(io(&new))?;
+    fs::rename(executable, &old).map_err(io(executable))?;
+    if let Err(e) = fs::rename(&new, executable) {
+        // Restore the previous executable, so that the installation is left intact
+        let _ = fs::rename(&old, executable);
+        return Err(io(executable)(e));
+    }
+    let _ = fs::remove_file(&old);
+    Ok(())
+}
+
+/// Formats the bytes as lowercase hexadecimal.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_listed_checksums() {
+        let checksums = "abc123  sbp-review-x86_64-unknown-linux-gnu\n\
+                         def456 *sbp-review-aarch64-apple-darwin\n";
+        assert_eq!(
+            listed(checksums, "sbp-review-x86_64-unknown-linux-gnu").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            listed(checksums, "sbp-review-aarch64-apple-darwin").as_deref(),
+            Some("def456")
+        );
+        assert_eq!(listed(checksums, "sbp-review"), None);
+    }
+}
@@ -0,0 +1,478 @@
+use crate::{
+    findings::{Category, Finding, Location, Severity},
+    manifests::{self, line_containing, Package},
+    Analysis, Context, Error, Format,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    path::{Path, PathBuf},
+};
+use toml_edit::{DocumentMut, Item, TableLike};
+
+/// The number of lines of Rust beyond which a dependency is considered large.
+const LARGE: usize = 10_000;
+
+/// Reports which dependencies are covered by supply-chain audits, being those recorded by `cargo vet` within the
+/// `supply-chain` directory of the workspace (including imported audits) and the package reviews within the local
+/// `cargo crev` proof repositories, along with the large or critical dependencies which remain unaudited.
+pub struct Vet;
+
+/// How a dependency is covered by supply-chain audits.
+#[derive(Clone, Copy, PartialEq)]
+enum Coverage {
+    /// Audited (or trusted) via `cargo vet`.
+    Vet,
+    /// Reviewed via `cargo crev`.
+    Crev,
+    /// Exempted from auditing within the `cargo vet` configuration.
+    Exempted,
+    Unaudited,
+}
+
+impl Analysis for Vet {
+    fn category(&self) -> Category {
+        Category::Dependencies
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Checking supply-chain audits of dependencies...");
+        }
+
+        let supply_chain = context.root.join("supply-chain");
+        let vet = supply_chain.is_dir().then(|| Audits::vet(&supply_chain));
+        let crev = crev_reviews();
+        let mut findings = Vec::new();
+        let mut report = |finding: Finding| {
+            if let Some(finding) = context.config.apply(finding) {
+                if text {
+                    finding.print(context);
+                }
+                findings.push(finding);
+            }
+        };
+        if vet.is_none() && crev.is_empty() {
+            let mut finding = Finding::new(
+                Category::Dependencies,
+                Severity::Warning,
+                "no `supply-chain` directory (cargo vet) or crev reviews found, so no dependencies are covered by \
+                 audits"
+                    .into(),
+            )
+            .with_code("sbp::no_supply_chain_audits");
+            finding.help.push(
+                "run `cargo vet init` and audit (or import the audits of) critical dependencies"
+                    .into(),
+            );
+            report(finding);
+            return Ok(findings);
+        }
+
+        let metadata = manifests::dependency_metadata(context)?;
+        let lock_file = context.root.join("Cargo.lock");
+        let lock = fs::read_to_string(&lock_file).unwrap_or_default();
+        let file = manifests::relative(&context.root, &lock_file.to_string_lossy());
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut dependencies: Vec<_> = metadata
+            .packages
+            .iter()
+            .filter(|p| p.source.is_some() && !metadata.workspace_members.contains(&p.id))
+            .collect();
+        dependencies.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        for package in &dependencies {
+            let reviewed = |audits: &Audits| audits.covers(&package.name, &package.version);
+            let coverage = if vet.as_ref().is_some_and(reviewed) {
+                Coverage::Vet
+            } else if crev.contains(&(package.name.clone(), package.version.clone())) {
+                Coverage::Crev
+            } else if vet.as_ref().is_some_and(|v| {
+                v.exempted
+                    .contains(&(package.name.clone(), package.version.clone()))
+            }) {
+                Coverage::Exempted
+            } else {
+                Coverage::Unaudited
+            };
+            *counts
+                .entry(match coverage {
+                    Coverage::Vet => "cargo vet",
+                    Coverage::Crev => "crev",
+                    Coverage::Exempted => "exempted",
+                    Coverage::Unaudited => "unaudited",
+                })
+                .or_default() += 1;
+            if matches!(coverage, Coverage::Vet | Coverage::Crev) {
+                continue;
+            }
+
+            // Large dependencies and those executing code at build time warrant an audit
+            let reasons = critical(package);
+            if reasons.is_empty() {
+                continue;
+            }
+            let mut finding = Finding::new(
+                Category::Dependencies,
+                Severity::Warning,
+                format!(
+                    "{} {} is {}, despite {}",
+                    package.name,
+                    package.version,
+                    match coverage {
+                        Coverage::Exempted => "exempted from auditing",
+                        _ => "unaudited",
+                    },
+                    reasons.join(" and ")
+                ),
+            )
+            .with_code("sbp::unaudited_dependency")
+            .with_package(&package.name);
+            let line = line_containing(&lock, &format!("name = \"{}\"", package.name));
+            finding.location = Some(Location {
+                file: file.clone(),
+                line,
+                column: 1,
+                line_end: line,
+                column_end: 1,
+            });
+            finding.help.push(format!(
+                "audit via `cargo vet inspect {} {}` (or import the audits of a trusted organisation)",
+                package.name, package.version
+            ));
+            report(finding);
+        }
+
+        if text {
+            let covered = counts.get("cargo vet").copied().unwrap_or_default()
+                + counts.get("crev").copied().unwrap_or_default();
+            println!(
+                "{} of {} dependencies audited ({})",
+                covered,
+                dependencies.len(),
+                counts
+                    .iter()
+                    .map(|(coverage, count)| format!("{}: {}", coverage, count))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        Ok(findings)
+    }
+}
+
+/// The audits recorded by `cargo vet`.
+#[derive(Default)]
+struct Audits {
+    /// The versions fully audited, keyed by crate.
+    full: BTreeMap<String, BTreeSet<String>>,
+    /// The audited changes between versions (from, to), keyed by crate.
+    deltas: BTreeMap<String, Vec<(String, String)>>,
+    /// The crates whose publishers (or authors) are trusted.
+    trusted: BTreeSet<String>,
+    /// The crate versions exempted from auditing.
+    exempted: BTreeSet<(String, String)>,
+}
+
+impl Audits {
+    /// Reads the audits within the `supply-chain` directory: those of the workspace (`audits.toml`), those imported from
+    /// other organisations (`imports.lock`) and the exemptions (`config.toml`).
+    fn vet(directory: &Path) -> Self {
+        let read = |file: &str| {
+            fs::read_to_string(directory.join(file))
+                .ok()
+                .and_then(|c| c.parse::<DocumentMut>().ok())
+                .unwrap_or_default()
+        };
+        let mut audits = Audits::default();
+        let local = read("audits.toml");
+        audits.add(local.as_table());
+        let imports = read("imports.lock");
+        if let Some(imported) = imports.get("audits").and_then(Item::as_table_like) {
+            for (_, import) in imported.iter() {
+                if let Some(import) = import.as_table_like() {
+                    audits.add(import);
+                }
+            }
+        }
+        // Trusted publishers are recorded within the imports once resolved
+        for (name, _) in entries(imports.get("publisher")) {
+            audits.trusted.insert(name.to_string());
+        }
+        for (name, exemption) in entries(read("config.toml").get("exemptions")) {
+            if let Some(version) = string(exemption, "version") {
+                audits.exempted.insert((name.to_string(), version));
+            }
+        }
+        audits
+    }
+
+    /// Adds the audits and trusted entries of the table, e.g. the root of `audits.toml`.
+    fn add(&mut self, table: &dyn TableLike) {
+        for (name, audit) in entries(table.get("audits")) {
+            // Violations record versions which fail the criteria, rather than audits
+            if audit.get("violation").is_some() {
+                continue;
+            }
+            if let Some(version) = string(audit, "version") {
+                self.full.entry(name.into()).or_default().insert(version);
+            } else if let Some((from, to)) = string(audit, "delta")
+                .as_deref()
+                .and_then(|d| d.split_once("->"))
+            {
+                self.deltas
+                    .entry(name.into())
+                    .or_default()
+                    .push((from.trim().into(), to.trim().into()));
+            }
+        }
+        for (name, _) in entries(table.get("trusted")) {
+            self.trusted.insert(name.into());
+        }
+    }
+
+    /// Whether the version of the crate is covered by a full audit, a chain of delta audits from a fully audited
+    /// version, or trust.
+    fn covers(&self, name: &str, version: &str) -> bool {
+        if self.trusted.contains(name) {
+            return true;
+        }
+        let mut audited: BTreeSet<&str> = self
+            .full
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        let deltas = self.deltas.get(name).map(Vec::as_slice).unwrap_or_default();
+        // Deltas are applied until no further versions are reached
+        loop {
+            let before = audited.len();
+            for (from, to) in deltas {
+                if audited.contains(from.as_str()) {
+                    audited.insert(to);
+                }
+            }
+            if audited.len() == before {
+                return audited.contains(version);
+            }
+        }
+    }
+}
+
+/// Returns the entries of each array of tables within the table, along with their key, e.g. each `[[audits.serde]]`.
+/// Arrays of inline tables (`serde = [{ ... }]`) are equivalent.
+fn entries(table: Option<&Item>) -> impl Iterator<Item = (&str, &dyn TableLike)> {
+    table
+        .and_then(Item::as_table_like)
+        .into_iter()
+        .flat_map(|t| t.iter())
+        .flat_map(|(key, value)| {
+            let tables: Vec<&dyn TableLike> = match value.as_array_of_tables() {
+                Some(tables) => tables.iter().map(|t| t as &dyn TableLike).collect(),
+                None => value
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_inline_table())
+                    .map(|t| t as &dyn TableLike)
+                    .collect(),
+            };
+            tables.into_iter().map(move |table| (key, table))
+        })
+}
+
+fn string(table: &dyn TableLike, key: &str) -> Option<String> {
+    table.get(key)?.as_str().map(String::from)
+}
+
+/// Returns the crate versions positively reviewed within the local proof repositories of `cargo crev`.
+fn crev_reviews() -> BTreeSet<(String, String)> {
+    let Some(directory) = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+        .map(|d| d.join("crev").join("proofs"))
+    else {
+        return BTreeSet::new();
+    };
+    let mut reviews = BTreeSet::new();
+    let mut directories = vec![directory];
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = fs::read_dir(&directory) else {
+            continue;
+        };
+        for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+            if path.is_dir() {
+                directories.push(path);
+            } else if path.extension().is_some_and(|e| e == "crev") {
+                reviews.extend(crev_proofs(&fs::read_to_string(&path).unwrap_or_default()));
+            }
+        }
+    }
+    reviews
+}
+
+/// Parses the (YAML) package reviews within a proof file, returning the crate versions not rated negatively.
+fn crev_proofs(contents: &str) -> Vec<(String, String)> {
+    let mut reviews = Vec::new();
+    // Each proof is delimited by `----- BEGIN CREV PACKAGE REVIEW -----`, followed by its signature
+    for proof in contents
+        .split("----- BEGIN CREV PACKAGE REVIEW -----")
+        .skip(1)
+    {
+        let body = proof.split("-----").next().unwrap_or_default();
+        let (mut section, mut name, mut version, mut negative) = ("", None, None, false);
+        for line in body.lines() {
+            let indented = line.starts_with(' ');
+            let Some((key, value)) = line.trim().split_once(':') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"');
+            if !indented {
+                section = key;
+            } else if section == "package" && key == "name" {
+                name = Some(value.to_string());
+            } else if section == "package" && key == "version" {
+                version = Some(value.to_string());
+            } else if section == "review" && key == "rating" {
+                negative = value == "negative" || value == "dangerous";
+            }
+        }
+        if let (Some(name), Some(version), false) = (name, version, negative) {
+            reviews.push((name, version));
+        }
+    }
+    reviews
+}
+
+/// Returns the reasons the dependency is considered critical: its size and whether it executes code at build time.
+fn critical(package: &Package) -> Vec<String> {
+    let mut reasons = Vec::new();
+    let lines = Path::new(&package.manifest_path)
+        .parent()
+        .map(lines)
+        .unwrap_or_default();
+    if lines >= LARGE {
+        reasons.push(format!("comprising {} lines of Rust", lines));
+    }
+    let kinds: Vec<_> = package.targets.iter().flat_map(|t| &t.kind).collect();
+    if kinds.iter().any(|k| *k == "custom-build") {
+        reasons.push("executing a build script".into());
+    }
+    if kinds.iter().any(|k| *k == "proc-macro") {
+        reasons.push("being a procedural macro executed at build time".into());
+    }
+    reasons
+}
+
+/// Counts the lines of Rust within the directory, recursively.
+fn lines(directory: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .map(|path| match path.is_dir() {
+            true => lines(&path),
+            false if path.extension().is_some_and(|e| e == "rs") => fs::read_to_string(&path)
+                .map(|c| c.lines().count())
+                .unwrap_or_default(),
+            false => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audits(contents: &str) -> Audits {
+        let mut audits = Audits::default();
+        audits.add(contents.parse::<DocumentMut>().unwrap().as_table());
+        audits
+    }
+
+    #[test]
+    fn covers_audited_and_trusted_versions() {
+        let audits = audits(
+            r#"
+            [[audits.serde]]
+            who = "Alice <alice@example.com>"
+            criteria = "safe-to-deploy"
+            version = "1.0.0"
+
+            [[audits.serde]]
+            criteria = "safe-to-deploy"
+            delta = "1.0.0 -> 1.0.1"
+
+            [[audits.serde]]
+            criteria = "safe-to-deploy"
+            delta = "1.0.1 -> 1.0.2"
+
+            [audits]
+            log = [{ criteria = "safe-to-deploy", version = "0.4.22" }]
+
+            [[trusted.tokio]]
+            criteria = "safe-to-deploy"
+            user-id = 6741
+            "#,
+        );
+        for (name, version) in [
+            ("serde", "1.0.0"),
+            ("serde", "1.0.2"),
+            ("log", "0.4.22"),
+            ("tokio", "1.40.0"),
+        ] {
+            assert!(audits.covers(name, version), "{name} {version}");
+        }
+    }
+
+    #[test]
+    fn unaudited_versions_and_violations_are_not_covered() {
+        let audits = audits(
+            r#"
+            [[audits.serde]]
+            criteria = "safe-to-deploy"
+            delta = "1.0.0 -> 1.0.1"
+
+            [[audits.openssl]]
+            criteria = "safe-to-deploy"
+            violation = "<0.10.55"
+            "#,
+        );
+        // Deltas only cover versions reached from a fully audited version
+        assert!(!audits.covers("serde", "1.0.1"));
+        assert!(!audits.covers("openssl", "0.10.54"));
+        assert!(!audits.covers("tokio", "1.40.0"));
+    }
+
+    #[test]
+    fn parses_crev_reviews() {
+        let proof = |name: &str, rating: &str| {
+            format!(
+                "----- BEGIN CREV PACKAGE REVIEW -----\n\
+                 kind: package review\n\
+                 package:\n  source: \"https://crates.io\"\n  name: {name}\n  version: 1.0.0\n\
+                 review:\n  thoroughness: low\n  understanding: medium\n  rating: {rating}\n\
+                 ----- BEGIN CREV PACKAGE REVIEW SIGNATURE -----\n\
+                 signature\n\
+                 ----- END CREV PACKAGE REVIEW -----\n"
+            )
+        };
+        let contents = [
+            proof("serde", "positive"),
+            proof("log", "neutral"),
+            proof("evil", "negative"),
+            proof("worse", "dangerous"),
+        ]
+        .concat();
+        assert_eq!(
+            crev_proofs(&contents),
+            [
+                ("serde".to_string(), "1.0.0".to_string()),
+                ("log".to_string(), "1.0.0".to_string()),
+            ]
+        );
+        assert!(crev_proofs("no proofs").is_empty());
+    }
+}
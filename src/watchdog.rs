@@ -0,0 +1,140 @@
+//! Termination of spawned commands which do not complete within their timeout.
+
+use std::{
+    error,
+    fmt::{self, Display, Formatter},
+    io,
+    process::{Child, Command, Output, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Watches a child process, terminating it (along with any processes it spawned, such as test executables) once its
+/// timeout elapses, so that a hung command does not block the analysis forever.
+pub(crate) struct Watchdog {
+    sender: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    timed_out: Arc<AtomicBool>,
+}
+
+impl Watchdog {
+    /// Watches the child process, if it has a timeout.
+    pub(crate) fn new(child: &Child, timeout: Option<Duration>) -> Self {
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let Some(timeout) = timeout else {
+            return Watchdog {
+                sender: None,
+                thread: None,
+                timed_out,
+            };
+        };
+        let (sender, receiver) = mpsc::channel::<()>();
+        let pid = child.id();
+        let thread = thread::spawn({
+            let timed_out = timed_out.clone();
+            move || {
+                // The sender is dropped once the process completes, disconnecting the channel
+                if let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(timeout) {
+                    timed_out.store(true, Ordering::SeqCst);
+                    kill(pid);
+                }
+            }
+        });
+        Watchdog {
+            sender: Some(sender),
+            thread: Some(thread),
+            timed_out,
+        }
+    }
+
+    /// Stops watching the (completed) process, returning whether it was terminated as a result of its timeout.
+    pub(crate) fn finish(mut self) -> bool {
+        drop(self.sender.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        self.timed_out.load(Ordering::SeqCst)
+    }
+}
+
+/// The error of a command terminated as its timeout elapsed, wrapped within an I/O error of kind `TimedOut`.
+#[derive(Debug)]
+pub(crate) struct Elapsed(pub(crate) Duration);
+
+impl Display for Elapsed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out after {}s", self.0.as_secs())
+    }
+}
+
+impl error::Error for Elapsed {}
+
+/// Executes the command to completion like `Command::output`, terminating it once the timeout (if any) elapses. Unlike
+/// `Command::output`, only output which is piped is captured.
+pub(crate) fn output(command: &mut Command, timeout: Option<Duration>) -> io::Result<Output> {
+    let child = command.stdin(Stdio::null()).spawn()?;
+    let watchdog = Watchdog::new(&child, timeout);
+    let output = child.wait_with_output();
+    match (watchdog.finish(), timeout) {
+        (true, Some(timeout)) => Err(io::Error::new(io::ErrorKind::TimedOut, Elapsed(timeout))),
+        _ => output,
+    }
+}
+
+/// Kills the process and its descendants, which otherwise continue to run (and hold its output open) once it is killed.
+#[cfg(unix)]
+fn kill(pid: u32) {
+    let signal = |signal: &str| {
+        let _ = Command::new("kill")
+            .args([signal, &pid.to_string()])
+            .stderr(Stdio::null())
+            .status();
+    };
+    // The process is stopped first, so that it cannot spawn further children whilst they are killed
+    signal("-STOP");
+    let children = Command::new("pgrep")
+        .args(["-P", &pid.to_string()])
+        .stderr(Stdio::null())
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        .unwrap_or_default();
+    for child in children.split_whitespace().filter_map(|c| c.parse().ok()) {
+        kill(child);
+    }
+    signal("-KILL");
+}
+
+/// Kills the process and its descendants, which otherwise continue to run (and hold its output open) once it is killed.
+#[cfg(not(unix))]
+fn kill(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn terminates_commands_once_timed_out() {
+        let timeout = Duration::from_millis(200);
+        let error = output(Command::new("sleep").arg("10"), Some(timeout)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(matches!(
+            Error::command("sleep", error),
+            Error::Timeout { command: "sleep", timeout: t } if t == timeout
+        ));
+
+        let completed = output(&mut Command::new("true"), Some(Duration::from_secs(10))).unwrap();
+        assert!(completed.status.success());
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{
+    benchmarks,
+    findings::{Category, Finding, Location, Severity},
+    manifests::{self, Metadata},
+    pallets::Source,
+    Analysis, Context, Error, Format,
+};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+use syn::{FnArg, ImplItem, ImplItemFn, Item, Type};
+
+/// The header of weight files generated by the benchmark CLI, regardless of the template used.
+const HEADER: &str = "THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI";
+
+/// Regenerates the weights of the selected pallets via the `benchmark pallet` command of a node and compares them with
+/// the committed weights, flagging any which appear stale or hand-edited.
+pub struct Weights;
+
+impl Analysis for Weights {
+    fn category(&self) -> Category {
+        Category::Benchmarks
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        let binary = benchmarks::binary(context)?;
+        if text {
+            println!(
+                "Regenerating weights via {} benchmark pallet...",
+                binary.display()
+            );
+        }
+
+        let metadata = manifests::metadata(context)?;
+        let directory = env::temp_dir().join(format!("sbp-review-weights-{}", process::id()));
+        let output = benchmarks::benchmark(context, &binary, &directory);
+        let findings = output.map(|output| {
+            let mut findings = Vec::new();
+            let mut report = |finding: Finding| {
+                if let Some(finding) = context.config.apply(finding) {
+                    if text {
+                        finding.print(context);
+                    }
+                    findings.push(finding);
+                }
+            };
+            if !output.status.success() {
+                report(
+                    Finding::new(
+                        Category::Benchmarks,
+                        Severity::Error,
+                        format!("benchmarks failed ({})", output.status),
+                    )
+                    .with_code("sbp::benchmarks_failed"),
+                );
+                return findings;
+            }
+            for generated in generated(&directory) {
+                let pallet = generated
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                compare(context, &metadata, &pallet, &generated, &mut report);
+            }
+            findings
+        });
+        // The temporary directory is removed regardless of the outcome
+        let _ = fs::remove_dir_all(&directory);
+        findings
+    }
+}
+
+/// Compares the weights generated for the pallet with those committed, reporting any differences.
+fn compare(
+    context: &Context,
+    metadata: &Metadata,
+    pallet: &str,
+    generated: &Path,
+    report: &mut impl FnMut(Finding),
+) {
+    let Some((package, path)) = committed(context, metadata, pallet) else {
+        report(
+            Finding::new(
+                Category::Benchmarks,
+                Severity::Info,
+                format!("no committed weights found for '{}'", pallet),
+            )
+            .with_code("sbp::missing_weights"),
+        );
+        return;
+    };
+    let (Some(generated), Some(committed)) = (
+        Source::parse(generated, &context.root),
+        Source::parse(&path, &context.root),
+    ) else {
+        return;
+    };
+    let finding = |severity, message: String| {
+        Finding::new(Category::Benchmarks, severity, message).with_package(package)
+    };
+
+    if !fs::read_to_string(&path).is_ok_and(|c| c.contains(HEADER)) {
+        report(
+            finding(
+                Severity::Warning,
+                format!(
+                    "weights for '{}' do not appear to be generated by the benchmark CLI",
+                    pallet
+                ),
+            )
+            .with_code("sbp::hand_edited_weights")
+            .with_location(Location {
+                file: committed.path.clone(),
+                line: 1,
+                column: 1,
+                line_end: 1,
+                column_end: 1,
+            }),
+        );
+    }
+
+    let (generated_functions, committed_functions) =
+        (functions(&generated.file), functions(&committed.file));
+    for function in &generated_functions {
+        if !committed_functions
+            .iter()
+            .any(|f| f.sig.ident == function.sig.ident)
+        {
+            report(
+                finding(
+                    Severity::Warning,
+                    format!(
+                        "weights for '{}' are missing benchmarked extrinsic '{}'",
+                        pallet, function.sig.ident
+                    ),
+                )
+                .with_code("sbp::stale_weights"),
+            );
+        }
+    }
+    for function in &committed_functions {
+        let name = &function.sig.ident;
+        let location = committed.location(name.span());
+        let Some(regenerated) = generated_functions.iter().find(|f| &f.sig.ident == name) else {
+            report(
+                finding(
+                    Severity::Warning,
+                    format!("weight function '{}' has no corresponding benchmark", name),
+                )
+                .with_code("sbp::stale_weights")
+                .with_location(location),
+            );
+            continue;
+        };
+        let (expected, actual) = (components(regenerated), components(function));
+        if expected != actual {
+            report(
+                finding(
+                    Severity::Warning,
+                    format!(
+                        "components of weight function '{}' ({}) differ from its benchmark ({})",
+                        name,
+                        actual.join(", "),
+                        expected.join(", ")
+                    ),
+                )
+                .with_code("sbp::stale_weights")
+                .with_location(location),
+            );
+        } else if accesses(regenerated) != accesses(function) {
+            report(
+                finding(
+                    Severity::Warning,
+                    format!(
+                        "storage reads/writes of weight function '{}' differ from its benchmark",
+                        name
+                    ),
+                )
+                .with_code("sbp::stale_weights")
+                .with_location(location),
+            );
+        }
+    }
+}
+
+/// The weight files generated within the directory, in order.
+fn generated(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "rs"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Locates the committed weights of the pallet: either `src/weights.rs` of the pallet package itself or
+/// `src/weights/<pallet>.rs` of another selected package, such as a runtime.
+fn committed<'a>(
+    context: &Context,
+    metadata: &'a Metadata,
+    pallet: &str,
+) -> Option<(&'a str, PathBuf)> {
+    let packages: Vec<_> = metadata
+        .packages
+        .iter()
+        .filter(|p| context.selected(&p.name))
+        .filter_map(|p| Some((p, Path::new(&p.manifest_path).parent()?.join("src"))))
+        .collect();
+    packages
+        .iter()
+        .filter(|(p, _)| p.name.replace('-', "_") == pallet)
+        .map(|(p, src)| (*p, src.join("weights.rs")))
+        .chain(
+            packages
+                .iter()
+                .map(|(p, src)| (*p, src.join("weights").join(format!("{}.rs", pallet)))),
+        )
+        .find(|(_, path)| path.is_file())
+        .map(|(p, path)| (p.name.as_str(), path))
+}
+
+/// The weight functions of the `WeightInfo` implementation of the weight file, excluding any implementation for `()`.
+fn functions(file: &syn::File) -> Vec<&ImplItemFn> {
+    file.items
+        .iter()
+        .find_map(|item| match item {
+            Item::Impl(block)
+                if block.trait_.as_ref().is_some_and(|(_, path, _)| {
+                    path.segments
+                        .last()
+                        .is_some_and(|s| s.ident == "WeightInfo")
+                }) && !matches!(*block.self_ty, Type::Tuple(_)) =>
+            {
+                Some(block)
+            }
+            _ => None,
+        })
+        .map(|block| {
+            block
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    ImplItem::Fn(function) => Some(function),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The names of the components (parameters) of the weight function.
+fn components(function: &ImplItemFn) -> Vec<String> {
+    function
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(input) => Some(input.pat.to_token_stream().to_string()),
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// The database reads/writes of the weight function, e.g. `reads(1)` or `writes((1_u64).saturating_mul(x.into()))`.
+fn accesses(function: &ImplItemFn) -> Vec<String> {
+    fn visit(tokens: TokenStream, accesses: &mut Vec<String>) {
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Ident(ident)
+                    if ident == "reads" || ident == "writes" || ident == "reads_writes" =>
+                {
+                    if let Some(TokenTree::Group(group)) = tokens.peek() {
+                        accesses.push(format!("{}{}", ident, group));
+                    }
+                }
+                TokenTree::Group(group) => visit(group.stream(), accesses),
+                _ => {}
+            }
+        }
+    }
+
+    let mut accesses = Vec::new();
+    visit(function.block.to_token_stream(), &mut accesses);
+    accesses
+}
@@ -0,0 +1,294 @@
+use crate::{
+    findings::{Category, Finding, Severity},
+    manifests,
+    pallets::{self, Source},
+    Analysis, Context, Error, Format,
+};
+use proc_macro2::Span;
+use quote::ToTokens;
+use std::path::Path;
+use syn::{
+    visit::{self, Visit},
+    ImplItemType, ItemType, Type,
+};
+
+/// Associated types of the XCM executor and `pallet-xcm` configuration which are dangerous when set to `Everything`,
+/// along with the severity and reason.
+const EVERYTHING: [(&str, Severity, &str); 6] = [
+    (
+        "IsReserve",
+        Severity::Error,
+        "trusts any location as a reserve of any asset",
+    ),
+    (
+        "IsTeleporter",
+        Severity::Error,
+        "trusts any location to teleport any asset",
+    ),
+    (
+        "Aliasers",
+        Severity::Error,
+        "permits any location to alias any other location",
+    ),
+    (
+        "SafeCallFilter",
+        Severity::Warning,
+        "permits any call to be dispatched via `Transact`",
+    ),
+    (
+        "XcmExecuteFilter",
+        Severity::Warning,
+        "permits any origin to execute arbitrary XCM locally",
+    ),
+    (
+        "XcmTeleportFilter",
+        Severity::Warning,
+        "permits any asset to be teleported",
+    ),
+];
+
+/// Barriers which permit execution without payment, so are dangerous when permitting `Everything`.
+const UNPAID: [&str; 2] = [
+    "AllowUnpaidExecutionFrom",
+    "AllowExplicitUnpaidExecutionFrom",
+];
+
+/// Inspects the XCM configuration of runtimes (barrier, call filter, trader and reserve/teleport filters) for known
+/// dangerous settings.
+pub struct Xcm;
+
+impl Analysis for Xcm {
+    fn category(&self) -> Category {
+        Category::Runtime
+    }
+
+    fn run(&self, context: &Context) -> Result<Vec<Finding>, Error> {
+        let text = context.format == Format::Text;
+        if text {
+            println!("Analysing XCM configuration...");
+        }
+
+        let mut findings = Vec::new();
+        let metadata = manifests::metadata(context)?;
+        for package in metadata
+            .packages
+            .iter()
+            .filter(|p| context.selected(&p.name))
+        {
+            let Some(directory) = Path::new(&package.manifest_path).parent() else {
+                continue;
+            };
+            let mut report = |finding: Finding| {
+                if let Some(finding) = context.config.apply(finding.with_package(&package.name)) {
+                    if text {
+                        finding.print(context);
+                    }
+                    findings.push(finding)
+                }
+            };
+            for source in pallets::sources(directory, &context.root) {
+                for definition in definitions(&source) {
+                    check(&source, &definition, &mut report);
+                }
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// A type definition, either a type alias or an associated type of a trait implementation such as
+/// `impl xcm_executor::Config for XcmConfig`.
+struct Definition {
+    name: String,
+    /// The type, with any whitespace removed, e.g. `AllowUnpaidExecutionFrom<Everything>`.
+    ty: String,
+    span: Span,
+}
+
+/// Checks the type definition against known dangerous settings.
+fn check(source: &Source, definition: &Definition, report: &mut impl FnMut(Finding)) {
+    let finding = |severity, code: &str, message: String| {
+        Finding::new(Category::Runtime, severity, message)
+            .with_code(code)
+            .with_location(source.location(definition.span))
+    };
+    for barrier in UNPAID {
+        if arguments(&definition.ty, barrier)
+            .iter()
+            .any(|a| is_everything(a))
+        {
+            report(finding(
+                Severity::Error,
+                "sbp::xcm_unpaid_execution",
+                format!(
+                    "'{}' permits unpaid execution from any location via `{}<Everything>`",
+                    definition.name, barrier
+                ),
+            ));
+        }
+    }
+    if let Some((name, severity, reason)) = EVERYTHING
+        .iter()
+        .find(|(name, ..)| definition.name == *name && is_everything(&definition.ty))
+    {
+        report(finding(
+            *severity,
+            "sbp::xcm_unsafe_config",
+            format!("'{}' is set to `Everything`, which {}", name, reason),
+        ));
+    }
+    if definition.name == "Trader" && definition.ty == "()" {
+        report(finding(
+            Severity::Warning,
+            "sbp::xcm_unsafe_config",
+            "'Trader' is not configured, so execution cannot be paid for".into(),
+        ));
+    }
+}
+
+/// Whether the (whitespace-free) type is `Everything`, e.g. `frame_support::traits::Everything`.
+fn is_everything(ty: &str) -> bool {
+    ty.rsplit("::").next() == Some("Everything")
+}
+
+/// Returns the (whitespace-free) generic arguments of each usage of the named type within the type, e.g. `Everything`
+/// for `AllowUnpaidExecutionFrom` within `(TakeWeightCredit, AllowUnpaidExecutionFrom<Everything>)`.
+fn arguments<'a>(ty: &'a str, name: &str) -> Vec<&'a str> {
+    let pattern = format!("{}<", name);
+    ty.match_indices(&pattern)
+        .filter_map(|(index, _)| {
+            let start = index + pattern.len();
+            // Locate the matching closing bracket, allowing for nested generics
+            let mut depth = 1;
+            for (offset, c) in ty[start..].char_indices() {
+                match c {
+                    '<' => depth += 1,
+                    '>' if depth == 1 => return Some(&ty[start..start + offset]),
+                    '>' => depth -= 1,
+                    _ => {}
+                }
+            }
+            None
+        })
+        .collect()
+}
+
+/// Returns the type definitions within the source.
+fn definitions(source: &Source) -> Vec<Definition> {
+    #[derive(Default)]
+    struct Definitions(Vec<Definition>);
+    impl Definitions {
+        fn define(&mut self, ident: &syn::Ident, ty: &Type) {
+            self.0.push(Definition {
+                name: ident.to_string(),
+                ty: ty
+                    .to_token_stream()
+                    .to_string()
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect(),
+                span: ident.span(),
+            });
+        }
+    }
+    impl<'a> Visit<'a> for Definitions {
+        fn visit_item_type(&mut self, item: &'a ItemType) {
+            self.define(&item.ident, &item.ty);
+            visit::visit_item_type(self, item);
+        }
+
+        fn visit_impl_item_type(&mut self, item: &'a ImplItemType) {
+            self.define(&item.ident, &item.ty);
+            visit::visit_impl_item_type(self, item);
+        }
+    }
+
+    let mut definitions = Definitions::default();
+    definitions.visit_file(&source.file);
+    definitions.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The codes and messages of the findings for the type definitions within the source.
+    fn findings(code: &str) -> Vec<(String, String)> {
+        let source = Source {
+            path: "runtime/src/xcm_config.rs".into(),
+            file: syn::parse_file(code).unwrap(),
+        };
+        let mut findings = Vec::new();
+        for definition in definitions(&source) {
+            check(&source, &definition, &mut |f: Finding| {
+                findings.push((f.code.unwrap_or_default(), f.message))
+            });
+        }
+        findings
+    }
+
+    #[test]
+    fn reports_dangerous_xcm_configuration() {
+        let findings = findings(
+            r#"
+            pub type Barrier = TrailingSetTopicAsId<(
+                TakeWeightCredit,
+                WithComputedOrigin<AllowUnpaidExecutionFrom<Everything>, UniversalLocation, ConstU32<8>>,
+            )>;
+            impl xcm_executor::Config for XcmConfig {
+                type IsReserve = frame_support::traits::Everything;
+                type Trader = ();
+            }
+            impl pallet_xcm::Config for Runtime {
+                type XcmExecuteFilter = Everything;
+            }
+            "#,
+        );
+        assert_eq!(
+            findings,
+            [
+                (
+                    "sbp::xcm_unpaid_execution".into(),
+                    "'Barrier' permits unpaid execution from any location via `AllowUnpaidExecutionFrom<Everything>`".into()
+                ),
+                (
+                    "sbp::xcm_unsafe_config".into(),
+                    "'IsReserve' is set to `Everything`, which trusts any location as a reserve of any asset".into()
+                ),
+                (
+                    "sbp::xcm_unsafe_config".into(),
+                    "'Trader' is not configured, so execution cannot be paid for".into()
+                ),
+                (
+                    "sbp::xcm_unsafe_config".into(),
+                    "'XcmExecuteFilter' is set to `Everything`, which permits any origin to execute arbitrary XCM locally".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn restricted_xcm_configuration_is_accepted() {
+        assert!(findings(
+            r#"
+            pub type Barrier = (
+                TakeWeightCredit,
+                AllowTopLevelPaidExecutionFrom<Everything>,
+                AllowExplicitUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
+            );
+            impl xcm_executor::Config for XcmConfig {
+                type IsReserve = NativeAsset;
+                type IsTeleporter = ();
+                type SafeCallFilter = Everything2;
+                type Trader = UsingComponents<WeightToFee, RelayLocation, AccountId, Balances, ()>;
+            }
+            "#
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn arguments_allow_for_nested_generics() {
+        assert_eq!(arguments("(A<B<C>>,A<D>,AB<E>)", "A"), ["B<C>", "D"]);
+    }
+}